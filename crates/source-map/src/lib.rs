@@ -3,8 +3,11 @@
 //! This crate provides utilities for tracking source positions and mapping
 //! between original Vue source code and generated TypeScript code.
 
+mod v3;
+
 use std::ops::Range;
 pub use text_size::{TextRange, TextSize};
+pub use v3::base64_encode;
 
 /// A span in the source code, representing a half-open range [start, end).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
@@ -65,6 +68,24 @@ impl Span {
         self.start <= offset && offset < self.end
     }
 
+    /// Check if two spans share any offset.
+    ///
+    /// An empty span counts as the offset it sits at.
+    #[inline]
+    pub const fn intersects(&self, other: Span) -> bool {
+        let end = if self.is_empty() {
+            self.end + 1
+        } else {
+            self.end
+        };
+        let other_end = if other.is_empty() {
+            other.end + 1
+        } else {
+            other.end
+        };
+        self.start < other_end && other.start < end
+    }
+
     /// Merge two spans into one that covers both.
     #[inline]
     pub fn merge(self, other: Span) -> Span {
@@ -74,6 +95,18 @@ impl Span {
         }
     }
 
+    /// Move the span by `delta` bytes, clamping at zero.
+    ///
+    /// Used to turn spans relative to a block's content into file offsets,
+    /// and to follow edits earlier in the file.
+    #[inline]
+    pub const fn shift(self, delta: i64) -> Span {
+        Span {
+            start: shift_offset(self.start, delta),
+            end: shift_offset(self.end, delta),
+        }
+    }
+
     /// Convert to a TextRange.
     #[inline]
     pub fn to_text_range(self) -> TextRange {
@@ -114,6 +147,19 @@ impl From<Span> for Range<usize> {
     }
 }
 
+/// Move an offset by `delta` bytes, clamping to the `u32` range.
+#[inline]
+const fn shift_offset(offset: u32, delta: i64) -> u32 {
+    let shifted = offset as i64 + delta;
+    if shifted < 0 {
+        0
+    } else if shifted > u32::MAX as i64 {
+        u32::MAX
+    } else {
+        shifted as u32
+    }
+}
+
 /// A line index for converting between byte offsets and line/column positions.
 #[derive(Debug, Clone)]
 pub struct LineIndex {
@@ -121,6 +167,8 @@ pub struct LineIndex {
     line_starts: Vec<u32>,
     /// Total length of the source.
     len: u32,
+    /// Length of a leading UTF-8 BOM, which takes up no column.
+    bom: u32,
 }
 
 impl LineIndex {
@@ -135,6 +183,11 @@ impl LineIndex {
         Self {
             line_starts,
             len: text.len() as u32,
+            bom: if text.starts_with('\u{FEFF}') {
+                '\u{FEFF}'.len_utf8() as u32
+            } else {
+                0
+            },
         }
     }
 
@@ -146,7 +199,10 @@ impl LineIndex {
             .partition_point(|&start| start <= offset)
             .saturating_sub(1);
         let line_start = self.line_starts[line];
-        let col = offset - line_start;
+        let mut col = offset - line_start;
+        if line == 0 {
+            col = col.saturating_sub(self.bom);
+        }
         LineCol {
             line: line as u32,
             col,
@@ -156,7 +212,10 @@ impl LineIndex {
     /// Get the byte offset for a line and column.
     /// Returns None if the position is out of bounds.
     pub fn offset(&self, line_col: LineCol) -> Option<u32> {
-        let line_start = self.line_starts.get(line_col.line as usize)?;
+        let mut line_start = *self.line_starts.get(line_col.line as usize)?;
+        if line_col.line == 0 {
+            line_start += self.bom;
+        }
         let offset = line_start + line_col.col;
         if offset <= self.len {
             Some(offset)
@@ -224,6 +283,12 @@ pub struct SourceMapping {
     pub source_offset: u32,
     /// Length in the original source.
     pub source_length: u32,
+    /// Length of generated code inserted before the mapped text.
+    ///
+    /// Offsets in the prefix (like `__VLS_ctx.` before an identifier) map to
+    /// the source start, and the rest maps one-to-one.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub generated_prefix: u32,
     /// Optional source file name for multi-file mappings.
     pub source_file: Option<String>,
 }
@@ -236,6 +301,7 @@ impl SourceMapping {
             generated_length: length,
             source_offset,
             source_length: length,
+            generated_prefix: 0,
             source_file: None,
         }
     }
@@ -252,6 +318,24 @@ impl SourceMapping {
             generated_length,
             source_offset,
             source_length,
+            generated_prefix: 0,
+            source_file: None,
+        }
+    }
+
+    /// Create a mapping for source text emitted after a generated prefix.
+    pub fn new_with_prefix(
+        generated_offset: u32,
+        prefix_length: u32,
+        source_offset: u32,
+        source_length: u32,
+    ) -> Self {
+        Self {
+            generated_offset,
+            generated_length: prefix_length + source_length,
+            source_offset,
+            source_length,
+            generated_prefix: prefix_length,
             source_file: None,
         }
     }
@@ -262,6 +346,13 @@ impl SourceMapping {
         self
     }
 
+    /// Move the mapping by `generated_delta` bytes in the generated code and
+    /// `source_delta` bytes in the source, clamping at zero.
+    pub fn shift(&mut self, generated_delta: i32, source_delta: i32) {
+        self.generated_offset = shift_offset(self.generated_offset, generated_delta.into());
+        self.source_offset = shift_offset(self.source_offset, source_delta.into());
+    }
+
     /// Get the generated span.
     pub fn generated_span(&self) -> Span {
         Span::new(
@@ -279,17 +370,38 @@ impl SourceMapping {
 /// A source map containing multiple mappings.
 #[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "RawSourceMap"))]
 pub struct SourceMap {
     /// All mappings, sorted by generated offset.
     mappings: Vec<SourceMapping>,
 }
 
+/// Serialized form of a [`SourceMap`], whose mappings may be in any order.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawSourceMap {
+    mappings: Vec<SourceMapping>,
+}
+
+#[cfg(feature = "serde")]
+impl From<RawSourceMap> for SourceMap {
+    fn from(raw: RawSourceMap) -> Self {
+        Self::from_mappings(raw.mappings)
+    }
+}
+
 impl SourceMap {
     /// Create a new empty source map.
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Create a source map from mappings in any order.
+    pub fn from_mappings(mut mappings: Vec<SourceMapping>) -> Self {
+        mappings.sort_by_key(|m| m.generated_offset);
+        Self { mappings }
+    }
+
     /// Add a mapping to the source map.
     pub fn add_mapping(&mut self, mapping: SourceMapping) {
         // Insert in sorted order by generated offset
@@ -334,16 +446,49 @@ impl SourceMap {
         self.find_source(generated_offset).map(|m| {
             let delta = generated_offset - m.generated_offset;
             // Scale the delta if lengths differ
-            if m.generated_length == m.source_length {
+            if m.generated_prefix > 0 {
+                // Clamp inside the prefix, then map one-to-one
+                let delta = delta.saturating_sub(m.generated_prefix);
+                m.source_offset + delta.min(m.source_length.saturating_sub(1))
+            } else if m.generated_length == m.source_length {
                 m.source_offset + delta
-            } else if m.generated_length > 0 {
-                m.source_offset + (delta * m.source_length / m.generated_length)
             } else {
                 m.source_offset
+                    + (delta * m.source_length)
+                        .checked_div(m.generated_length)
+                        .unwrap_or(0)
             }
         })
     }
 
+    /// Map a generated span to the source span it covers.
+    ///
+    /// The start and end are mapped independently. When they land in
+    /// different mappings the result covers everything between them; when
+    /// only one end is mapped, the span is clamped to that mapping.
+    pub fn map_span(&self, generated: Span) -> Option<Span> {
+        let start = self.to_source_offset(generated.start);
+        let end = if generated.is_empty() {
+            start
+        } else {
+            // The end is exclusive, so map the last covered offset
+            self.to_source_offset(generated.end - 1).map(|o| o + 1)
+        };
+
+        match (start, end) {
+            (Some(start), Some(end)) => Some(Span::empty(start).merge(Span::empty(end))),
+            (Some(start), None) => {
+                let mapping = self.find_source(generated.start)?;
+                Some(Span::new(start, mapping.source_span().end.max(start)))
+            }
+            (None, Some(end)) => {
+                let mapping = self.find_source(generated.end - 1)?;
+                Some(Span::new(mapping.source_span().start.min(end), end))
+            }
+            (None, None) => None,
+        }
+    }
+
     /// Get all mappings.
     pub fn mappings(&self) -> &[SourceMapping] {
         &self.mappings
@@ -407,6 +552,24 @@ impl CodeBuilder {
         }
     }
 
+    /// Append generated `prefix` followed by `code` mapped to the source.
+    ///
+    /// The prefix maps to the start of `code` in the source rather than
+    /// stretching the mapping across both.
+    pub fn push_prefixed(&mut self, prefix: &str, code: &str, source_offset: u32) {
+        let generated_offset = self.offset();
+        self.code.push_str(prefix);
+        self.code.push_str(code);
+        if !code.is_empty() {
+            self.source_map.add_mapping(SourceMapping::new_with_prefix(
+                generated_offset,
+                prefix.len() as u32,
+                source_offset,
+                code.len() as u32,
+            ));
+        }
+    }
+
     /// Append code with a custom mapping.
     pub fn push_with_mapping(&mut self, code: &str, source_offset: u32, source_length: u32) {
         let generated_offset = self.offset();
@@ -457,6 +620,49 @@ mod tests {
         assert!(!span.contains_offset(25));
     }
 
+    #[test]
+    fn test_span_shift() {
+        assert_eq!(Span::new(2, 5).shift(10), Span::new(12, 15));
+        assert_eq!(Span::new(12, 15).shift(-10), Span::new(2, 5));
+        assert_eq!(Span::new(2, 5).shift(0), Span::new(2, 5));
+    }
+
+    #[test]
+    fn test_span_shift_clamps() {
+        assert_eq!(Span::new(2, 5).shift(-3), Span::new(0, 2));
+        assert_eq!(Span::new(2, 5).shift(-10), Span::empty(0));
+        assert_eq!(Span::new(2, u32::MAX - 1).shift(5), Span::new(7, u32::MAX));
+    }
+
+    #[test]
+    fn test_source_mapping_shift() {
+        let mut mapping = SourceMapping::new_with_prefix(20, 10, 4, 3);
+        mapping.shift(5, -2);
+        assert_eq!(mapping.generated_span(), Span::new(25, 38));
+        assert_eq!(mapping.source_span(), Span::new(2, 5));
+        assert_eq!(mapping.generated_prefix, 10);
+
+        mapping.shift(-30, -4);
+        assert_eq!(mapping.generated_offset, 0);
+        assert_eq!(mapping.source_offset, 0);
+    }
+
+    #[test]
+    fn test_span_intersects() {
+        let span = Span::new(10, 20);
+        assert!(span.intersects(Span::new(15, 30)));
+        assert!(span.intersects(Span::new(0, 11)));
+        assert!(span.intersects(Span::new(12, 14)));
+        assert!(!span.intersects(Span::new(20, 25)));
+        assert!(!span.intersects(Span::new(0, 10)));
+
+        // Empty spans intersect the spans containing their offset
+        assert!(span.intersects(Span::empty(10)));
+        assert!(Span::empty(19).intersects(span));
+        assert!(!span.intersects(Span::empty(20)));
+        assert!(Span::empty(5).intersects(Span::empty(5)));
+    }
+
     #[test]
     fn test_span_merge() {
         let span1 = Span::new(10, 20);
@@ -466,6 +672,18 @@ mod tests {
         assert_eq!(merged.end, 30);
     }
 
+    #[test]
+    fn test_line_index_skips_bom() {
+        let text = "\u{FEFF}<template>\n<div>";
+        let index = LineIndex::new(text);
+
+        assert_eq!(index.line_col(3), LineCol::new(0, 0));
+        assert_eq!(index.line_col(4), LineCol::new(0, 1));
+        assert_eq!(index.line_col(14), LineCol::new(1, 0));
+        assert_eq!(index.offset(LineCol::new(0, 0)), Some(3));
+        assert_eq!(index.offset(LineCol::new(1, 1)), Some(15));
+    }
+
     #[test]
     fn test_line_index() {
         let text = "hello\nworld\nfoo";
@@ -506,6 +724,95 @@ mod tests {
         assert_eq!(map.to_source_offset(15), None);
     }
 
+    #[test]
+    fn test_map_span_within_mapping() {
+        let mut map = SourceMap::new();
+        map.add(0, 100, 10);
+        assert_eq!(map.map_span(Span::new(2, 6)), Some(Span::new(102, 106)));
+        assert_eq!(map.map_span(Span::empty(3)), Some(Span::empty(103)));
+    }
+
+    #[test]
+    fn test_map_span_across_mappings() {
+        let mut map = SourceMap::new();
+        map.add(0, 100, 10);
+        map.add(10, 150, 10);
+
+        // Starts in the first mapping and ends in the second
+        assert_eq!(map.map_span(Span::new(5, 15)), Some(Span::new(105, 155)));
+    }
+
+    #[test]
+    fn test_map_span_partially_unmapped() {
+        let mut map = SourceMap::new();
+        map.add(0, 100, 10);
+        map.add(20, 200, 10);
+
+        // End lands in the unmapped gap: clamp to the first mapping
+        assert_eq!(map.map_span(Span::new(5, 15)), Some(Span::new(105, 110)));
+        // Start lands in the gap: clamp to the second mapping
+        assert_eq!(map.map_span(Span::new(15, 25)), Some(Span::new(200, 205)));
+        // Nothing mapped
+        assert_eq!(map.map_span(Span::new(12, 18)), None);
+    }
+
+    #[test]
+    fn test_from_mappings_sorts() {
+        let map = SourceMap::from_mappings(vec![
+            SourceMapping::new(20, 200, 5),
+            SourceMapping::new(0, 100, 5),
+            SourceMapping::new(10, 150, 5),
+        ]);
+        assert_eq!(map.to_source_offset(2), Some(102));
+        assert_eq!(map.to_source_offset(12), Some(152));
+        assert_eq!(map.to_source_offset(22), Some(202));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_out_of_order_mappings() {
+        let json = r#"{"mappings": [
+            {"generated_offset": 20, "generated_length": 5, "source_offset": 200, "source_length": 5, "source_file": null},
+            {"generated_offset": 0, "generated_length": 5, "source_offset": 100, "source_length": 5, "source_file": null}
+        ]}"#;
+        let map: SourceMap = serde_json::from_str(json).unwrap();
+        assert_eq!(map.mappings()[0].generated_offset, 0);
+        assert_eq!(map.to_source_offset(3), Some(103));
+        assert_eq!(map.to_source_offset(23), Some(203));
+
+        // Round-trips through the same shape
+        let round_trip: SourceMap =
+            serde_json::from_str(&serde_json::to_string(&map).unwrap()).unwrap();
+        assert_eq!(round_trip.to_source_offset(23), Some(203));
+    }
+
+    #[test]
+    fn test_prefixed_mapping_clamps_to_source_start() {
+        // `{{ count }}` at offset 10 generates `__VLS_ctx.count`
+        let mut builder = CodeBuilder::new();
+        builder.push_str("(");
+        builder.push_prefixed("__VLS_ctx.", "count", 13);
+        let (code, map) = builder.finish();
+        assert_eq!(code, "(__VLS_ctx.count");
+
+        // An error on the whole expression points at the identifier start
+        assert_eq!(map.to_source_offset(1), Some(13));
+        assert_eq!(map.to_source_offset(6), Some(13));
+        // Offsets past the prefix map one-to-one
+        let ident = code.find("count").unwrap() as u32;
+        assert_eq!(map.to_source_offset(ident), Some(13));
+        assert_eq!(map.to_source_offset(ident + 2), Some(15));
+        assert_eq!(map.map_span(Span::new(1, 16)), Some(Span::new(13, 18)));
+    }
+
+    #[test]
+    fn test_scaled_mapping_without_prefix() {
+        let mut map = SourceMap::new();
+        map.add_mapping(SourceMapping::new_with_lengths(0, 15, 13, 5));
+        // Without the prefix flag the delta is scaled, landing mid-identifier
+        assert_eq!(map.to_source_offset(6), Some(15));
+    }
+
     #[test]
     fn test_code_builder() {
         let mut builder = CodeBuilder::new();
@@ -0,0 +1,206 @@
+//! Export to the Source Map v3 format.
+
+use crate::{LineIndex, SourceMap};
+
+/// The base64 alphabet, also used for VLQ digits.
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+impl SourceMap {
+    /// Export as a Source Map v3 JSON document.
+    ///
+    /// `generated` and `source` are the texts the offsets refer to, needed to
+    /// compute lines and columns (in UTF-16 code units, as the format
+    /// requires). Mappings into other files are skipped.
+    pub fn to_v3(&self, generated: &str, source: &str, source_name: &str) -> String {
+        let generated_index = LineIndex::new(generated);
+        let source_index = LineIndex::new(source);
+
+        // Segments per generated line: (column, source line, source column)
+        let mut lines = vec![Vec::new(); generated_index.line_count()];
+        for mapping in &self.mappings {
+            if mapping.source_file.is_some() {
+                continue;
+            }
+
+            // A generated prefix maps to the source start
+            let mapped_start = mapping.generated_offset + mapping.generated_prefix;
+            let mut points = vec![(mapping.generated_offset, mapping.source_offset)];
+            if mapping.generated_prefix > 0 {
+                points.push((mapped_start, mapping.source_offset));
+            }
+
+            // One-to-one text may span lines, so map the start of each line
+            let mapped_length = mapping
+                .generated_length
+                .saturating_sub(mapping.generated_prefix);
+            if mapped_length == mapping.source_length {
+                let end = mapped_start + mapped_length;
+                let text = generated
+                    .get(mapped_start as usize..end as usize)
+                    .unwrap_or_default();
+                for (i, _) in text.match_indices('\n') {
+                    let delta = i as u32 + 1;
+                    if delta < mapped_length {
+                        points.push((mapped_start + delta, mapping.source_offset + delta));
+                    }
+                }
+            }
+
+            for (generated_offset, source_offset) in points {
+                let (line, column) = position(generated, &generated_index, generated_offset);
+                let (source_line, source_column) = position(source, &source_index, source_offset);
+                lines[line as usize].push((column, source_line, source_column));
+            }
+        }
+
+        let mut mappings = String::new();
+        let (mut prev_source_line, mut prev_source_column) = (0, 0);
+        for (i, segments) in lines.iter_mut().enumerate() {
+            if i > 0 {
+                mappings.push(';');
+            }
+            segments.sort_by_key(|&(column, _, _)| column);
+            segments.dedup_by_key(|&mut (column, _, _)| column);
+
+            let mut prev_column = 0;
+            for (j, &(column, source_line, source_column)) in segments.iter().enumerate() {
+                if j > 0 {
+                    mappings.push(',');
+                }
+                encode_vlq(&mut mappings, i64::from(column) - prev_column);
+                encode_vlq(&mut mappings, 0);
+                encode_vlq(&mut mappings, i64::from(source_line) - prev_source_line);
+                encode_vlq(&mut mappings, i64::from(source_column) - prev_source_column);
+                prev_column = i64::from(column);
+                prev_source_line = i64::from(source_line);
+                prev_source_column = i64::from(source_column);
+            }
+        }
+
+        format!(
+            r#"{{"version":3,"sources":[{}],"sourcesContent":[{}],"names":[],"mappings":"{}"}}"#,
+            json_string(source_name),
+            json_string(source),
+            mappings
+        )
+    }
+}
+
+/// Get the 0-indexed line and UTF-16 column of an offset.
+fn position(text: &str, index: &LineIndex, offset: u32) -> (u32, u32) {
+    let line_col = index.line_col(offset);
+    let column = text
+        .get((offset - line_col.col) as usize..offset as usize)
+        .map_or(line_col.col, |s| s.encode_utf16().count() as u32);
+    (line_col.line, column)
+}
+
+/// Append a value as a base64 VLQ.
+fn encode_vlq(out: &mut String, value: i64) {
+    let mut vlq = if value < 0 {
+        (value.unsigned_abs() << 1) | 1
+    } else {
+        (value as u64) << 1
+    };
+    loop {
+        let mut digit = (vlq & 0b1_1111) as usize;
+        vlq >>= 5;
+        if vlq > 0 {
+            // Continuation bit
+            digit |= 0b10_0000;
+        }
+        out.push(BASE64_CHARS[digit] as char);
+        if vlq == 0 {
+            break;
+        }
+    }
+}
+
+/// Encode bytes as padded base64, for inline `sourceMappingURL` comments.
+pub fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_CHARS[(n >> (18 - 6 * i) & 0b11_1111) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Quote a string as JSON.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SourceMapping;
+    use pretty_assertions::assert_eq;
+
+    fn vlq(value: i64) -> String {
+        let mut out = String::new();
+        encode_vlq(&mut out, value);
+        out
+    }
+
+    #[test]
+    fn test_encode_vlq() {
+        assert_eq!(vlq(0), "A");
+        assert_eq!(vlq(1), "C");
+        assert_eq!(vlq(-1), "D");
+        assert_eq!(vlq(15), "e");
+        assert_eq!(vlq(16), "gB");
+        assert_eq!(vlq(-1000), "x+B");
+    }
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"hello"), "aGVsbG8=");
+    }
+
+    #[test]
+    fn test_to_v3() {
+        let source = "ab\ncd";
+        let generated = "let ab\ncd;\n(__VLS_ctx.x);";
+        let mut map = SourceMap::new();
+        // Multi-line text maps each line
+        map.add(4, 0, 5);
+        // A prefixed identifier maps both the prefix and the name
+        map.add_mapping(SourceMapping::new_with_prefix(12, 10, 3, 1));
+
+        let json = map.to_v3(generated, source, "App.vue");
+        assert_eq!(
+            json,
+            r#"{"version":3,"sources":["App.vue"],"sourcesContent":["ab\ncd"],"names":[],"mappings":"IAAA;AACA;CAAA,UAAA"}"#
+        );
+    }
+}
@@ -2,10 +2,10 @@
 
 use camino::Utf8PathBuf;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
-use crate::{TsError, TsResult};
+use crate::{TsError, TsErrorKind, TsResult};
 
 /// TypeScript configuration (tsconfig.json).
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -26,6 +26,9 @@ pub struct TsConfig {
     /// Extends another config.
     #[serde(default)]
     pub extends: Option<String>,
+    /// Project references.
+    #[serde(default)]
+    pub references: Vec<ProjectReference>,
     /// Vue compiler options.
     #[serde(default)]
     pub vue_compiler_options: VueCompilerOptions,
@@ -46,8 +49,20 @@ impl TsConfig {
 
     /// Find tsconfig.json in a directory or its parents.
     pub fn find(dir: &Path) -> Option<Utf8PathBuf> {
+        Self::find_within(dir, None)
+    }
+
+    /// Find tsconfig.json in a directory or its parents, searching no higher
+    /// than `root`.
+    ///
+    /// Nothing is found when `dir` is outside `root`.
+    pub fn find_within(dir: &Path, root: Option<&Path>) -> Option<Utf8PathBuf> {
         let mut current = dir;
         loop {
+            if root.is_some_and(|root| !current.starts_with(root)) {
+                return None;
+            }
+
             let tsconfig = current.join("tsconfig.json");
             if tsconfig.exists() {
                 return Utf8PathBuf::from_path_buf(tsconfig).ok();
@@ -59,8 +74,8 @@ impl TsConfig {
             }
 
             match current.parent() {
-                Some(parent) => current = parent,
-                None => return None,
+                Some(parent) if Some(current) != root => current = parent,
+                _ => return None,
             }
         }
     }
@@ -68,7 +83,12 @@ impl TsConfig {
     /// Resolve the configuration by handling extends.
     pub fn resolve(&mut self, base_dir: &Path) -> TsResult<()> {
         if let Some(extends) = &self.extends.take() {
-            let extends_path = base_dir.join(extends);
+            let extends_path = resolve_extends(extends, base_dir).ok_or_else(|| {
+                TsError::new(
+                    format!("Cannot find extended config '{}'", extends),
+                    TsErrorKind::NotFound,
+                )
+            })?;
             let mut base = TsConfig::load(&extends_path)?;
             base.resolve(extends_path.parent().unwrap_or(base_dir))?;
 
@@ -78,6 +98,99 @@ impl TsConfig {
         Ok(())
     }
 
+    /// Check the configuration for problems without running tsc.
+    ///
+    /// Every `extends` in the chain must resolve and parse, `files`,
+    /// `baseUrl` and project references must exist, and referenced projects
+    /// must not disable emit (`tsc --build` requires it). All problems are
+    /// returned rather than stopping at the first.
+    pub fn validate(&self, base_dir: &Path) -> Vec<TsError> {
+        let mut errors = Vec::new();
+
+        // Follow the extends chain
+        let mut visited = HashSet::new();
+        let mut next = self.extends.clone().map(|e| (e, base_dir.to_path_buf()));
+        while let Some((extends, dir)) = next.take() {
+            let Some(path) = resolve_extends(&extends, &dir) else {
+                errors.push(TsError::new(
+                    format!("Cannot find extended config '{}'", extends),
+                    TsErrorKind::NotFound,
+                ));
+                break;
+            };
+            if !visited.insert(path.clone()) {
+                errors.push(TsError::config(format!(
+                    "Circular extends: {}",
+                    path.display()
+                )));
+                break;
+            }
+            match TsConfig::load(&path) {
+                Ok(base) => {
+                    let parent = path.parent().unwrap_or(&dir).to_path_buf();
+                    next = base.extends.map(|e| (e, parent));
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+
+        for file in &self.files {
+            if !base_dir.join(file).is_file() {
+                errors.push(TsError::new(
+                    format!("File '{}' does not exist", file),
+                    TsErrorKind::NotFound,
+                ));
+            }
+        }
+
+        if let Some(base_url) = &self.compiler_options.base_url {
+            if !base_dir.join(base_url).is_dir() {
+                errors.push(TsError::new(
+                    format!("baseUrl '{}' is not a directory", base_url),
+                    TsErrorKind::NotFound,
+                ));
+            }
+        }
+
+        let mut aliases: Vec<_> = self.compiler_options.paths.iter().collect();
+        aliases.sort_by_key(|(alias, _)| *alias);
+        for (alias, targets) in aliases {
+            if targets.is_empty() {
+                errors.push(TsError::config(format!(
+                    "paths['{}'] has no targets",
+                    alias
+                )));
+            }
+        }
+
+        for reference in &self.references {
+            let path = reference.resolve(base_dir);
+            if !path.is_file() {
+                errors.push(TsError::new(
+                    format!(
+                        "Referenced project '{}' not found at {}",
+                        reference.path,
+                        path.display()
+                    ),
+                    TsErrorKind::NotFound,
+                ));
+                continue;
+            }
+            match TsConfig::load(&path) {
+                Ok(referenced) if referenced.compiler_options.no_emit == Some(true) => {
+                    errors.push(TsError::config(format!(
+                        "Referenced project '{}' sets noEmit, which tsc --build rejects",
+                        reference.path
+                    )));
+                }
+                Ok(_) => {}
+                Err(e) => errors.push(e),
+            }
+        }
+
+        errors
+    }
+
     /// Merge another config into this one.
     fn merge_from(&mut self, other: &TsConfig) {
         // Merge compiler options
@@ -109,6 +222,29 @@ impl TsConfig {
     }
 }
 
+/// A project reference (`{ "path": "../lib" }`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectReference {
+    /// Path to the referenced project directory or tsconfig file.
+    pub path: String,
+    /// Prepend the referenced project's output.
+    #[serde(default)]
+    pub prepend: Option<bool>,
+}
+
+impl ProjectReference {
+    /// Resolve the referenced tsconfig path relative to a base directory.
+    pub fn resolve(&self, base_dir: &Path) -> std::path::PathBuf {
+        let path = base_dir.join(&self.path);
+        if path.extension().is_some_and(|ext| ext == "json") {
+            path
+        } else {
+            path.join("tsconfig.json")
+        }
+    }
+}
+
 /// TypeScript compiler options.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -173,6 +309,13 @@ impl CompilerOptions {
     pub fn is_strict(&self) -> bool {
         self.strict.unwrap_or(false)
     }
+
+    /// Check if JavaScript files are type checked.
+    ///
+    /// `checkJs` implies `allowJs` unless that is turned off explicitly.
+    pub fn checks_js(&self) -> bool {
+        self.check_js == Some(true) && self.allow_js != Some(false)
+    }
 }
 
 /// Vue compiler options in tsconfig.
@@ -197,6 +340,10 @@ pub struct VueCompilerOptions {
     /// Native tags to not treat as components.
     #[serde(default)]
     pub native_tags: Vec<String>,
+    /// Generated `components.d.ts` declaring auto-imported global components.
+    pub global_components_dts: Option<String>,
+    /// Path to the tsc or tsgo binary, instead of searching for one.
+    pub ts_path: Option<String>,
 }
 
 impl VueCompilerOptions {
@@ -215,6 +362,50 @@ impl VueCompilerOptions {
     }
 }
 
+/// Check if a file name ends with one of `extensions`.
+///
+/// Extensions may have several parts, like `.vue.html`, and the leading dot
+/// is optional.
+pub fn has_file_extension(path: &Path, extensions: &[impl AsRef<str>]) -> bool {
+    let Some(name) = path.file_name().map(|name| name.to_string_lossy()) else {
+        return false;
+    };
+    extensions.iter().any(|extension| {
+        let extension = extension.as_ref().trim_start_matches('.');
+        name.len() > extension.len() + 1
+            && name.ends_with(extension)
+            && name[..name.len() - extension.len()].ends_with('.')
+    })
+}
+
+/// Resolve an `extends` value to a config file.
+///
+/// Relative and absolute paths may omit `.json`; anything else is looked up
+/// as a package in `node_modules` of `base_dir` or its parents.
+fn resolve_extends(extends: &str, base_dir: &Path) -> Option<PathBuf> {
+    let with_json = |path: PathBuf| {
+        if path.is_file() {
+            return Some(path);
+        }
+        let mut json = path.into_os_string();
+        json.push(".json");
+        Some(PathBuf::from(json)).filter(|p| p.is_file())
+    };
+
+    let path = Path::new(extends);
+    if path.is_absolute() || extends.starts_with('.') {
+        return with_json(base_dir.join(path));
+    }
+
+    base_dir.ancestors().find_map(|dir| {
+        let package = dir.join("node_modules").join(extends);
+        with_json(package.clone()).or_else(|| {
+            let tsconfig = package.join("tsconfig.json");
+            tsconfig.is_file().then_some(tsconfig)
+        })
+    })
+}
+
 /// Remove JSON comments (// and /* */).
 fn remove_json_comments(json: &str) -> String {
     let mut result = String::with_capacity(json.len());
@@ -296,6 +487,52 @@ mod tests {
         assert!(result.contains("// in string")); // In string, should be preserved
     }
 
+    #[test]
+    fn test_parse_references() {
+        let config: TsConfig = serde_json::from_str(
+            r#"{
+                "references": [{ "path": "../lib" }, { "path": "./tsconfig.node.json", "prepend": true }]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.references.len(), 2);
+        assert_eq!(config.references[0].path, "../lib");
+        assert_eq!(config.references[1].prepend, Some(true));
+
+        let base = Path::new("/repo/app");
+        assert_eq!(
+            config.references[0].resolve(base),
+            Path::new("/repo/app/../lib/tsconfig.json")
+        );
+        assert_eq!(
+            config.references[1].resolve(base),
+            Path::new("/repo/app/./tsconfig.node.json")
+        );
+    }
+
+    #[test]
+    fn test_find_within_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("monorepo");
+        let package = root.join("packages").join("app");
+        std::fs::create_dir_all(&package).unwrap();
+        std::fs::write(dir.path().join("tsconfig.json"), "{}").unwrap();
+
+        // The tsconfig above the root is out of reach
+        assert!(TsConfig::find_within(&package, Some(&root)).is_none());
+        assert!(TsConfig::find(&package).is_some());
+
+        // The root's own tsconfig is found from a package directory
+        std::fs::write(root.join("tsconfig.json"), "{}").unwrap();
+        assert_eq!(
+            TsConfig::find_within(&package, Some(&root)).unwrap(),
+            root.join("tsconfig.json")
+        );
+
+        // A directory outside the root finds nothing
+        assert!(TsConfig::find_within(dir.path(), Some(&root)).is_none());
+    }
+
     #[test]
     fn test_compiler_options() {
         let opts = CompilerOptions {
@@ -306,4 +543,96 @@ mod tests {
         assert!(opts.is_node_next());
         assert!(opts.is_strict());
     }
+
+    #[test]
+    fn test_has_file_extension() {
+        let extensions = [".vue", ".vue.html", "md"];
+        assert!(has_file_extension(Path::new("src/App.vue"), &extensions));
+        assert!(has_file_extension(
+            Path::new("src/App.vue.html"),
+            &extensions
+        ));
+        assert!(has_file_extension(Path::new("docs/guide.md"), &extensions));
+        assert!(!has_file_extension(Path::new("src/App.html"), &extensions));
+        assert!(!has_file_extension(Path::new("src/Appvue"), &extensions));
+        assert!(!has_file_extension(Path::new("src/.vue"), &extensions));
+    }
+
+    #[test]
+    fn test_checks_js() {
+        let check = |allow_js, check_js| {
+            CompilerOptions {
+                allow_js,
+                check_js,
+                ..Default::default()
+            }
+            .checks_js()
+        };
+        assert!(check(Some(true), Some(true)));
+        assert!(check(None, Some(true)));
+        assert!(!check(Some(false), Some(true)));
+        assert!(!check(Some(true), None));
+        assert!(!check(None, None));
+    }
+
+    #[test]
+    fn test_parse_ts_path() {
+        let config: TsConfig =
+            serde_json::from_str(r#"{ "vueCompilerOptions": { "tsPath": "./tools/tsc" } }"#)
+                .unwrap();
+        assert_eq!(
+            config.vue_compiler_options.ts_path.as_deref(),
+            Some("./tools/tsc")
+        );
+    }
+
+    #[test]
+    fn test_validate_valid_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path();
+        let package = base.join("node_modules").join("@vue").join("tsconfig");
+        std::fs::create_dir_all(&package).unwrap();
+        std::fs::create_dir_all(base.join("lib")).unwrap();
+        std::fs::create_dir_all(base.join("src")).unwrap();
+        std::fs::write(package.join("tsconfig.json"), "{}").unwrap();
+        std::fs::write(
+            base.join("tsconfig.base.json"),
+            r#"{ "extends": "@vue/tsconfig" }"#,
+        )
+        .unwrap();
+        std::fs::write(base.join("lib").join("tsconfig.json"), "{}").unwrap();
+        std::fs::write(base.join("src").join("main.ts"), "").unwrap();
+
+        let config: TsConfig = serde_json::from_str(
+            r#"{
+                "extends": "./tsconfig.base",
+                "files": ["src/main.ts"],
+                "compilerOptions": { "baseUrl": ".", "paths": { "@/*": ["src/*"] } },
+                "references": [{ "path": "./lib" }]
+            }"#,
+        )
+        .unwrap();
+        assert!(config.validate(base).is_empty());
+    }
+
+    #[test]
+    fn test_validate_reports_every_problem() {
+        let dir = tempfile::tempdir().unwrap();
+        let config: TsConfig = serde_json::from_str(
+            r#"{
+                "extends": "./missing.json",
+                "files": ["src/main.ts"],
+                "compilerOptions": { "paths": { "@/*": [] } },
+                "references": [{ "path": "./lib" }]
+            }"#,
+        )
+        .unwrap();
+
+        let errors = config.validate(dir.path());
+        let messages: Vec<_> = errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(errors.len(), 4, "{:?}", messages);
+        assert_eq!(messages[0], "Cannot find extended config './missing.json'");
+        assert_eq!(errors[0].kind, TsErrorKind::NotFound);
+        assert_eq!(messages[2], "paths['@/*'] has no targets");
+    }
 }
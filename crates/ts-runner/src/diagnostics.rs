@@ -1,9 +1,10 @@
 //! TypeScript diagnostics parsing and remapping.
 
 use serde::{Deserialize, Serialize};
-use source_map::{LineIndex, SourceMap, Span};
+use source_map::{LineCol, LineIndex, SourceMap, Span};
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 
 /// A collection of TypeScript diagnostics.
 #[derive(Debug, Clone, Default)]
@@ -38,10 +39,13 @@ impl TsDiagnostics {
     }
 
     /// Get diagnostics for a specific file.
+    ///
+    /// Paths match regardless of separator style.
     pub fn for_file(&self, file: &str) -> Vec<&TsDiagnostic> {
+        let key = path_key(Path::new(file));
         self.diagnostics
             .iter()
-            .filter(|d| d.file.as_ref().map(|f| f.to_string_lossy()) == Some(file.into()))
+            .filter(|d| d.file.as_deref().map(path_key).as_ref() == Some(&key))
             .collect()
     }
 
@@ -159,12 +163,56 @@ impl TsSeverity {
     }
 }
 
+/// Normalize a path for comparison.
+///
+/// tsc reports forward-slash paths on Windows, where discovered paths use
+/// backslashes (and canonicalized ones a `\\?\` prefix), so separators and
+/// the drive letter case are made uniform.
+fn path_key(path: &Path) -> PathBuf {
+    let path = path.to_string_lossy();
+    let path = path.strip_prefix(r"\\?\").unwrap_or(&path);
+    let mut key = path.replace('\\', "/");
+    if key.as_bytes().get(1) == Some(&b':') {
+        key[..1].make_ascii_lowercase();
+    }
+    PathBuf::from(key)
+}
+
+/// Resolve `.` and `..` components without touching the file system.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// A 1-indexed `(line, column)` start and end.
+type DisplayRange = ((u32, u32), (u32, u32));
+
 /// Remapper for converting virtual file positions to original positions.
+///
+/// Virtual files are keyed by [`path_key`], so a diagnostic matches its file
+/// whichever separators tsc used. tsc reports a 1-indexed line and column,
+/// which go through the virtual file's [`LineIndex`] to an offset, through
+/// the source map to an offset in the original file, and through the original
+/// file's [`LineIndex`] back to a line and column.
 pub struct DiagnosticRemapper {
+    /// Directory relative diagnostic paths are resolved against, the
+    /// working directory of tsc.
+    base_dir: Option<PathBuf>,
     /// Map from virtual file to original file.
     virtual_to_original: HashMap<PathBuf, PathBuf>,
     /// Source maps for each virtual file.
     source_maps: HashMap<PathBuf, SourceMap>,
+    /// Line indices for virtual files.
+    virtual_line_indices: HashMap<PathBuf, LineIndex>,
     /// Line indices for original files.
     line_indices: HashMap<PathBuf, LineIndex>,
 }
@@ -173,64 +221,129 @@ impl DiagnosticRemapper {
     /// Create a new remapper.
     pub fn new() -> Self {
         Self {
+            base_dir: None,
             virtual_to_original: HashMap::new(),
             source_maps: HashMap::new(),
+            virtual_line_indices: HashMap::new(),
             line_indices: HashMap::new(),
         }
     }
 
+    /// Create a remapper resolving relative diagnostic paths against
+    /// `base_dir`.
+    pub fn with_base_dir(base_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            base_dir: Some(base_dir.into()),
+            ..Self::new()
+        }
+    }
+
     /// Register a virtual file mapping.
     pub fn register(
         &mut self,
         virtual_file: PathBuf,
         original_file: PathBuf,
         source_map: SourceMap,
+        virtual_content: &str,
         original_content: &str,
     ) {
+        let virtual_file = path_key(&virtual_file);
         self.virtual_to_original
             .insert(virtual_file.clone(), original_file.clone());
-        self.source_maps.insert(virtual_file, source_map);
+        self.source_maps.insert(virtual_file.clone(), source_map);
+        self.virtual_line_indices
+            .insert(virtual_file, LineIndex::new(virtual_content));
         self.line_indices
             .insert(original_file, LineIndex::new(original_content));
     }
 
     /// Remap a diagnostic from virtual to original positions.
+    ///
+    /// Each related entry is remapped through the source map of its own
+    /// file, which may be a different virtual file than the diagnostic's.
     pub fn remap(&self, diagnostic: &mut TsDiagnostic) {
-        let file = match &diagnostic.file {
-            Some(f) => f,
-            None => return,
+        for related in &mut diagnostic.related {
+            let Some(file) = related.file.as_deref() else {
+                continue;
+            };
+            let start = (related.line, related.column);
+            if let Some((original_file, range)) = self.remap_range(file, start, start) {
+                related.file = Some(original_file);
+                if let Some(((line, col), _)) = range {
+                    related.line = Some(line);
+                    related.column = Some(col);
+                }
+            }
+        }
+
+        let Some(file) = diagnostic.file.as_deref() else {
+            return;
+        };
+        let start = (diagnostic.line, diagnostic.column);
+        let end = (diagnostic.end_line, diagnostic.end_column);
+        let Some((original_file, range)) = self.remap_range(file, start, end) else {
+            return;
         };
 
-        // Check if this is a virtual file
-        let original_file = match self.virtual_to_original.get(file) {
-            Some(f) => f,
-            None => return,
+        diagnostic.file = Some(original_file);
+        if let Some(((line, col), (end_line, end_col))) = range {
+            diagnostic.line = Some(line);
+            diagnostic.column = Some(col);
+            diagnostic.end_line = Some(end_line);
+            diagnostic.end_column = Some(end_col);
+        }
+    }
+
+    /// Map a 1-indexed range in `file` to its original file.
+    ///
+    /// Returns `None` when `file` isn't a registered virtual file. The range
+    /// is `None` when it doesn't map to the original source (generated code
+    /// without a mapping); the diagnostic then only moves to the original file.
+    fn remap_range(
+        &self,
+        file: &Path,
+        start: (Option<u32>, Option<u32>),
+        end: (Option<u32>, Option<u32>),
+    ) -> Option<(PathBuf, Option<DisplayRange>)> {
+        let file = self.virtual_key(file)?;
+        let original_file = self.virtual_to_original[&file].clone();
+        let (Some(source_map), Some(virtual_index)) = (
+            self.source_maps.get(&file),
+            self.virtual_line_indices.get(&file),
+        ) else {
+            return None;
         };
 
-        let source_map = match self.source_maps.get(file) {
-            Some(sm) => sm,
-            None => return,
+        // Convert the 1-indexed line/col range to offsets in the virtual file
+        let to_offset = |(line, col): (Option<u32>, Option<u32>)| {
+            let (line, col) = (line?, col?);
+            virtual_index.offset(LineCol::new(line.checked_sub(1)?, col.checked_sub(1)?))
         };
+        let mapped = to_offset(start).and_then(|start| {
+            let end = to_offset(end).unwrap_or(start);
+            source_map.map_span(Span::new(start, end.max(start)))
+        });
 
-        // Update file path
-        diagnostic.file = Some(original_file.clone());
-
-        // Remap position
-        if let (Some(_line), Some(col)) = (diagnostic.line, diagnostic.column) {
-            // Convert line/col to offset in virtual file
-            // Then use source map to get original offset
-            // Then convert back to line/col
-
-            // This is a simplified version - a full implementation would need
-            // the line index for the virtual file as well
-            if let Some(mapping) = source_map.find_source(col) {
-                if let Some(line_index) = self.line_indices.get(original_file) {
-                    let orig_line_col = line_index.line_col(mapping.source_offset);
-                    diagnostic.line = Some(orig_line_col.line + 1);
-                    diagnostic.column = Some(orig_line_col.col + 1);
-                }
-            }
+        let range = mapped
+            .zip(self.line_indices.get(&original_file))
+            .map(|(span, line_index)| {
+                (
+                    line_index.line_col(span.start).to_display(),
+                    line_index.line_col(span.end).to_display(),
+                )
+            });
+        Some((original_file, range))
+    }
+
+    /// Get the key of a registered virtual file, if `file` is one.
+    fn virtual_key(&self, file: &Path) -> Option<PathBuf> {
+        let key = path_key(file);
+        if self.virtual_to_original.contains_key(&key) {
+            return Some(key);
         }
+        let base_dir = self.base_dir.as_deref().filter(|_| file.is_relative())?;
+        let key = path_key(&normalize_path(&base_dir.join(file)));
+        self.virtual_to_original.contains_key(&key).then_some(key)
     }
 
     /// Remap all diagnostics.
@@ -262,7 +375,9 @@ pub fn parse_ts_output(output: &str) -> Vec<TsDiagnostic> {
         return diagnostics;
     }
 
-    // Try to parse line by line (standard tsc output)
+    // Try to parse line by line (standard tsc output). Colors are dropped
+    // first, as tsgo may add them even when not writing to a terminal.
+    let output = strip_ansi(output);
     for line in output.lines() {
         if let Some(diag) = parse_tsc_line(line) {
             diagnostics.push(diag);
@@ -272,6 +387,31 @@ pub fn parse_ts_output(output: &str) -> Vec<TsDiagnostic> {
     diagnostics
 }
 
+/// Remove ANSI escape sequences (colors and other styling) from text.
+fn strip_ansi(text: &str) -> Cow<'_, str> {
+    if !text.contains('\x1b') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            result.push(c);
+            continue;
+        }
+        // A CSI sequence (`ESC [ ... m`) ends at its first letter
+        if chars.next() == Some('[') {
+            for nc in chars.by_ref() {
+                if nc.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        }
+    }
+    Cow::Owned(result)
+}
+
 /// JSON format for TypeScript diagnostics.
 #[derive(Debug, Deserialize)]
 struct TsDiagnosticJson {
@@ -396,6 +536,236 @@ mod tests {
         assert_eq!(diag.severity, TsSeverity::Error);
     }
 
+    #[test]
+    fn test_parse_colored_tsc_line() {
+        let output = "\x1b[96msrc/main.ts\x1b[0m(\x1b[93m10\x1b[0m,\x1b[93m5\x1b[0m): \x1b[91merror\x1b[0m\x1b[90m TS2322: \x1b[0mType 'string' is not assignable to type 'number'.\n";
+        let diagnostics = parse_ts_output(output);
+        assert_eq!(diagnostics.len(), 1);
+        let diag = &diagnostics[0];
+        assert_eq!(diag.file, Some(PathBuf::from("src/main.ts")));
+        assert_eq!(diag.line, Some(10));
+        assert_eq!(diag.column, Some(5));
+        assert_eq!(diag.code, 2322);
+        assert_eq!(diag.severity, TsSeverity::Error);
+        assert_eq!(
+            diag.message,
+            "Type 'string' is not assignable to type 'number'."
+        );
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("plain"), "plain");
+        assert_eq!(strip_ansi("\x1b[1;31merror\x1b[0m: x"), "error: x");
+    }
+
+    #[test]
+    fn test_remap_full_range() {
+        // `value` on line 2 of the virtual file maps to `value` on line 3 of the original
+        let virtual_content = "// header\nconst x = value + other;\n";
+        let original_content = "<template>\n</template>\n{{ value + other }}\n";
+
+        let mut source_map = SourceMap::new();
+        source_map.add(20, 26, 5);
+        source_map.add(25, 31, 8);
+
+        let mut remapper = DiagnosticRemapper::new();
+        remapper.register(
+            PathBuf::from("/tmp/App.vue.ts"),
+            PathBuf::from("/src/App.vue"),
+            source_map,
+            virtual_content,
+            original_content,
+        );
+
+        let mut diag = TsDiagnostic {
+            message: "Type error".to_string(),
+            code: 2322,
+            severity: TsSeverity::Error,
+            file: Some(PathBuf::from("/tmp/App.vue.ts")),
+            line: Some(2),
+            column: Some(11),
+            end_line: Some(2),
+            end_column: Some(24),
+            related: Vec::new(),
+        };
+        remapper.remap(&mut diag);
+
+        assert_eq!(diag.file, Some(PathBuf::from("/src/App.vue")));
+        assert_eq!((diag.line, diag.column), (Some(3), Some(4)));
+        assert_eq!((diag.end_line, diag.end_column), (Some(3), Some(17)));
+    }
+
+    #[test]
+    fn test_remap_related_info_in_other_file() {
+        // The diagnostic is in App.vue, its related entry in Child.vue
+        let app_virtual = "const a = value;\n";
+        let app_original = "<template>{{ value }}</template>\n";
+        let child_virtual = "// header\nconst props = { size: 1 };\n";
+        let child_original = "<script setup>\nconst props = { size: 1 }\n</script>\n";
+
+        let mut app_map = SourceMap::new();
+        app_map.add(10, 13, 5);
+        let mut child_map = SourceMap::new();
+        child_map.add(26, 31, 4);
+
+        let mut remapper = DiagnosticRemapper::new();
+        remapper.register(
+            PathBuf::from("/tmp/App.vue.ts"),
+            PathBuf::from("/src/App.vue"),
+            app_map,
+            app_virtual,
+            app_original,
+        );
+        remapper.register(
+            PathBuf::from("/tmp/Child.vue.ts"),
+            PathBuf::from("/src/Child.vue"),
+            child_map,
+            child_virtual,
+            child_original,
+        );
+
+        let mut diag = TsDiagnostic {
+            message: "Type error".to_string(),
+            code: 2322,
+            severity: TsSeverity::Error,
+            file: Some(PathBuf::from("/tmp/App.vue.ts")),
+            line: Some(1),
+            column: Some(11),
+            end_line: None,
+            end_column: None,
+            related: vec![
+                RelatedInfo {
+                    message: "The expected type comes from property 'size'".to_string(),
+                    file: Some(PathBuf::from("/tmp/Child.vue.ts")),
+                    line: Some(2),
+                    column: Some(17),
+                },
+                RelatedInfo {
+                    message: "Declared here".to_string(),
+                    file: Some(PathBuf::from("/src/types.ts")),
+                    line: Some(4),
+                    column: Some(2),
+                },
+            ],
+        };
+        remapper.remap(&mut diag);
+
+        assert_eq!(diag.file, Some(PathBuf::from("/src/App.vue")));
+        assert_eq!((diag.line, diag.column), (Some(1), Some(14)));
+
+        // `size` is on line 2 of Child.vue, after `const props = { `
+        let related = &diag.related[0];
+        assert_eq!(related.file, Some(PathBuf::from("/src/Child.vue")));
+        assert_eq!((related.line, related.column), (Some(2), Some(17)));
+
+        // Entries outside virtual files are left alone
+        let related = &diag.related[1];
+        assert_eq!(related.file, Some(PathBuf::from("/src/types.ts")));
+        assert_eq!((related.line, related.column), (Some(4), Some(2)));
+    }
+
+    #[test]
+    fn test_remap_generated_code() {
+        let original = "<script setup lang=\"ts\">\nconst msg = 'hi'\n</script>\n\n<template>\n  <p>{{ msg }}</p>\n  <p>{{ missing }}</p>\n</template>\n";
+        let sfc = vue_parser::parse(original).unwrap();
+        let result = vue_codegen::generate(&sfc, &Default::default());
+
+        // tsc reports `missing` at its 1-indexed line and column in the virtual file
+        let start = result.code.find("__VLS_ctx.missing").unwrap() as u32;
+        let end = start + "__VLS_ctx.missing".len() as u32;
+        let virtual_index = LineIndex::new(&result.code);
+        let (line, column) = virtual_index.line_col(start).to_display();
+        let (end_line, end_column) = virtual_index.line_col(end).to_display();
+
+        let mut remapper = DiagnosticRemapper::with_base_dir("/project");
+        remapper.register(
+            PathBuf::from("/tmp/vue-tsc-rs/src/App.vue.ts"),
+            PathBuf::from("/project/src/App.vue"),
+            result.source_map,
+            &result.code,
+            original,
+        );
+
+        let mut diag = TsDiagnostic {
+            message: "Property 'missing' does not exist".to_string(),
+            code: 2339,
+            severity: TsSeverity::Error,
+            file: Some(PathBuf::from("../tmp/vue-tsc-rs/src/App.vue.ts")),
+            line: Some(line),
+            column: Some(column),
+            end_line: Some(end_line),
+            end_column: Some(end_column),
+            related: Vec::new(),
+        };
+        remapper.remap(&mut diag);
+
+        // `missing` is on line 7 of the SFC, after `  <p>{{ `
+        assert_eq!(diag.file, Some(PathBuf::from("/project/src/App.vue")));
+        assert_eq!((diag.line, diag.column), (Some(7), Some(9)));
+        assert_eq!((diag.end_line, diag.end_column), (Some(7), Some(16)));
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(
+            normalize_path(Path::new("/project/./src/../../tmp/App.vue.ts")),
+            PathBuf::from("/tmp/App.vue.ts")
+        );
+    }
+
+    #[test]
+    fn test_path_key_ignores_separator_style() {
+        assert_eq!(
+            path_key(Path::new(r"C:\project\src\App.vue.ts")),
+            path_key(Path::new("c:/project/src/App.vue.ts"))
+        );
+        assert_eq!(
+            path_key(Path::new(r"\\?\C:\project\App.vue")),
+            PathBuf::from("c:/project/App.vue")
+        );
+        assert_eq!(
+            path_key(Path::new("/tmp/App.vue.ts")),
+            PathBuf::from("/tmp/App.vue.ts")
+        );
+    }
+
+    #[test]
+    fn test_remap_with_mixed_separators() {
+        let mut source_map = SourceMap::new();
+        source_map.add(0, 10, 5);
+
+        let mut remapper = DiagnosticRemapper::new();
+        remapper.register(
+            PathBuf::from(r"C:\tmp\src\App.vue.ts"),
+            PathBuf::from(r"C:\project\src\App.vue"),
+            source_map,
+            "value;\n",
+            "<template>value</template>\n",
+        );
+
+        let mut diag = TsDiagnostic {
+            message: "Type error".to_string(),
+            code: 2322,
+            severity: TsSeverity::Error,
+            file: Some(PathBuf::from("C:/tmp/src/App.vue.ts")),
+            line: Some(1),
+            column: Some(1),
+            end_line: Some(1),
+            end_column: Some(6),
+            related: Vec::new(),
+        };
+        remapper.remap(&mut diag);
+
+        assert_eq!(diag.file, Some(PathBuf::from(r"C:\project\src\App.vue")));
+        assert_eq!((diag.line, diag.column), (Some(1), Some(11)));
+
+        let mut diags = TsDiagnostics::new();
+        diags.add(diag);
+        assert_eq!(diags.for_file("C:/project/src/App.vue").len(), 1);
+        assert_eq!(diags.for_file(r"C:\project\src\App.vue").len(), 1);
+    }
+
     #[test]
     fn test_ts_diagnostics() {
         let mut diags = TsDiagnostics::new();
@@ -10,9 +10,9 @@ pub mod diagnostics;
 pub mod runner;
 pub mod virtual_files;
 
-pub use config::TsConfig;
-pub use diagnostics::{TsDiagnostic, TsDiagnostics, TsSeverity};
-pub use runner::{TsRunner, TsRunnerOptions};
+pub use config::{ProjectReference, TsConfig};
+pub use diagnostics::{RelatedInfo, TsDiagnostic, TsDiagnostics, TsSeverity};
+pub use runner::{FileTimings, RunTimings, TsRunner, TsRunnerOptions};
 pub use virtual_files::VirtualFileSystem;
 
 use std::path::Path;
@@ -1,11 +1,14 @@
 //! TypeScript compiler runner.
 
-use crate::config::TsConfig;
+use crate::config::{has_file_extension, TsConfig};
 use crate::diagnostics::{parse_ts_output, DiagnosticRemapper, TsDiagnostics};
-use crate::virtual_files::VirtualFileSystem;
+use crate::virtual_files::{generate_virtual_tsconfig, VirtualFileSystem};
 use crate::{TsError, TsResult};
+use std::ffi::OsString;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::time::{Duration, Instant};
 use tokio::process::Command;
 
 /// Options for the TypeScript runner.
@@ -23,6 +26,39 @@ pub struct TsRunnerOptions {
     pub generate_virtual: bool,
     /// Temp directory for virtual files.
     pub temp_dir: Option<PathBuf>,
+    /// Path to the tsc or tsgo binary, used instead of searching for one.
+    pub ts_path: Option<PathBuf>,
+    /// The highest directory to search for a tsconfig.
+    pub root_dir: Option<PathBuf>,
+    /// Leave templates out of the virtual files, checking only scripts.
+    pub skip_template: bool,
+    /// Keep diagnostics in the order tsc reported them instead of sorting
+    /// them by file and position.
+    pub preserve_order: bool,
+}
+
+/// Time spent in each phase of a run.
+#[derive(Debug, Clone, Default)]
+pub struct RunTimings {
+    /// Time spent generating virtual files.
+    pub codegen: Duration,
+    /// Time spent running the TypeScript compiler.
+    pub tsc: Duration,
+    /// Time spent on each virtual file.
+    pub files: Vec<FileTimings>,
+}
+
+/// Time spent generating the virtual file for a single component.
+#[derive(Debug, Clone, Default)]
+pub struct FileTimings {
+    /// The `.vue` file.
+    pub path: PathBuf,
+    /// Time spent parsing the SFC.
+    pub parse: Duration,
+    /// Time spent generating TypeScript.
+    pub codegen: Duration,
+    /// Time spent writing the virtual file.
+    pub write: Duration,
 }
 
 /// TypeScript compiler runner.
@@ -35,8 +71,19 @@ pub struct TsRunner {
     tsconfig: Option<TsConfig>,
     /// Virtual file system.
     vfs: VirtualFileSystem,
-    /// Diagnostic remapper.
-    remapper: DiagnosticRemapper,
+}
+
+/// Get the directory for the virtual files of a workspace.
+///
+/// Each workspace gets its own, so projects checked in one run don't see
+/// each other's virtual files.
+fn default_temp_dir(workspace: &Path) -> PathBuf {
+    let workspace = std::fs::canonicalize(workspace).unwrap_or_else(|_| workspace.to_path_buf());
+    let mut hasher = std::hash::DefaultHasher::new();
+    workspace.hash(&mut hasher);
+    std::env::temp_dir()
+        .join("vue-tsc-rs")
+        .join(format!("{:016x}", hasher.finish()))
 }
 
 impl TsRunner {
@@ -45,7 +92,7 @@ impl TsRunner {
         // Load tsconfig if specified or find it
         let tsconfig = if let Some(path) = &options.tsconfig {
             Some(TsConfig::load(path)?)
-        } else if let Some(path) = TsConfig::find(workspace) {
+        } else if let Some(path) = TsConfig::find_within(workspace, options.root_dir.as_deref()) {
             Some(TsConfig::load(path.as_std_path())?)
         } else {
             None
@@ -54,30 +101,44 @@ impl TsRunner {
         let temp_dir = options
             .temp_dir
             .clone()
-            .unwrap_or_else(|| std::env::temp_dir().join("vue-tsc-rs"));
+            .unwrap_or_else(|| default_temp_dir(workspace));
 
         Ok(Self {
             workspace: workspace.to_path_buf(),
             options,
             tsconfig,
-            vfs: VirtualFileSystem::new(temp_dir),
-            remapper: DiagnosticRemapper::new(),
+            vfs: VirtualFileSystem::with_source_root(temp_dir, workspace.to_path_buf()),
         })
     }
 
     /// Run type checking.
     pub async fn run(&self) -> TsResult<TsDiagnostics> {
-        // Generate virtual files for Vue components
+        self.run_with_timings()
+            .await
+            .map(|(diagnostics, _)| diagnostics)
+    }
+
+    /// Run type checking, also reporting how long each phase took.
+    pub async fn run_with_timings(&self) -> TsResult<(TsDiagnostics, RunTimings)> {
+        let mut timings = RunTimings::default();
+
+        // Generate virtual files for Vue components; tsc runs in the
+        // workspace, so relative paths it reports are resolved against it
+        let mut remapper = DiagnosticRemapper::with_base_dir(&self.workspace);
         if self.options.generate_virtual {
-            self.generate_virtual_files()?;
+            let start = Instant::now();
+            timings.files = self.generate_virtual_files(&mut remapper)?;
+            timings.codegen = start.elapsed();
         }
 
         // Run the TypeScript compiler
+        let start = Instant::now();
         let output = if self.options.use_tsgo {
             self.run_tsgo().await?
         } else {
             self.run_tsc().await?
         };
+        timings.tsc = start.elapsed();
 
         // Parse diagnostics
         let mut diagnostics = TsDiagnostics::new();
@@ -86,19 +147,46 @@ impl TsRunner {
         }
 
         // Remap diagnostics from virtual files to original files
-        self.remapper.remap_all(&mut diagnostics);
+        remapper.remap_all(&mut diagnostics);
 
-        // Sort diagnostics
-        diagnostics.sort();
+        // Sort diagnostics, unless tsc's own order was asked for
+        if !self.options.preserve_order {
+            diagnostics.sort();
+        }
 
-        Ok(diagnostics)
+        Ok((diagnostics, timings))
     }
 
     /// Generate virtual TypeScript files for Vue components.
-    fn generate_virtual_files(&self) -> TsResult<()> {
+    ///
+    /// Each file is registered with `remapper`. Returns how long each file
+    /// took.
+    fn generate_virtual_files(
+        &self,
+        remapper: &mut DiagnosticRemapper,
+    ) -> TsResult<Vec<FileTimings>> {
         // Find all Vue files
         let vue_files = self.find_vue_files()?;
 
+        // Configured native tags are custom elements, not components
+        let mut codegen_options = vue_codegen::CodegenOptions {
+            skip_template: self.options.skip_template,
+            ..Default::default()
+        };
+        if let Some(tsconfig) = &self.tsconfig {
+            codegen_options.parse_options.custom_element_tags =
+                tsconfig.vue_compiler_options.native_tags.clone();
+            codegen_options.strict = tsconfig
+                .vue_compiler_options
+                .strict_templates
+                .unwrap_or(false);
+            codegen_options.check_js = tsconfig.compiler_options.checks_js();
+            codegen_options.verbatim_module_syntax =
+                tsconfig.compiler_options.verbatim_module_syntax == Some(true);
+        }
+
+        let mut timings = Vec::with_capacity(vue_files.len());
+
         for file in vue_files {
             // Read and parse the Vue file
             let content = std::fs::read_to_string(&file).map_err(|e| {
@@ -106,22 +194,65 @@ impl TsRunner {
             })?;
 
             // Parse the SFC
+            let start = Instant::now();
             let sfc = vue_parser::parse(&content).map_err(|e| {
                 TsError::parse(format!("Failed to parse {}: {}", file.display(), e))
             })?;
+            let parse = start.elapsed();
 
             // Generate TypeScript code
-            let result = vue_codegen::generate(&sfc, &vue_codegen::CodegenOptions::default());
+            let start = Instant::now();
+            codegen_options.filename = Some(file.display().to_string());
+            // A file can pin its own Vue target
+            codegen_options.target = sfc
+                .vue_target()
+                .or(self
+                    .tsconfig
+                    .as_ref()
+                    .and_then(|tsconfig| tsconfig.vue_compiler_options.target))
+                .map(vue_codegen::VueTarget::from_version)
+                .unwrap_or_default();
+            let result = vue_codegen::generate(&sfc, &codegen_options);
+            let codegen = start.elapsed();
 
             // Write virtual file
-            let virtual_path = self.vfs.virtual_path(&file, result.lang.extension());
-            self.vfs.write(&virtual_path, &result.code)?;
+            let start = Instant::now();
+            // Emitted files are kept for debugging, so they carry a source map
+            let virtual_path = self
+                .vfs
+                .virtual_path(&file, result.lang.virtual_extension());
+            if self.options.emit {
+                self.vfs
+                    .write(&virtual_path, &result.with_inline_source_map())?;
+            } else {
+                self.vfs.write(&virtual_path, &result.code)?;
+            }
+            let write = start.elapsed();
 
             // Register for remapping
-            // self.remapper.register(virtual_path, file, result.source_map, &content);
+            remapper.register(
+                virtual_path,
+                file.clone(),
+                result.source_map,
+                &result.code,
+                &content,
+            );
+
+            timings.push(FileTimings {
+                path: file,
+                parse,
+                codegen,
+                write,
+            });
         }
 
-        Ok(())
+        // The project for the virtual files maps their relative imports back
+        // to the sources
+        let tsconfig = generate_virtual_tsconfig(&self.vfs, self.options.tsconfig.as_deref())?;
+        self.vfs
+            .write(&self.vfs.root().join("tsconfig.json"), &tsconfig)?;
+
+        Ok(timings)
     }
 
     /// Find all Vue files in the workspace.
@@ -152,12 +283,8 @@ impl TsRunner {
                 continue;
             }
 
-            // Check extension
-            if let Some(ext) = path.extension() {
-                let ext_str = format!(".{}", ext.to_string_lossy());
-                if extensions.iter().any(|e| e == &ext_str) {
-                    files.push(path.to_path_buf());
-                }
+            if has_file_extension(path, &extensions) {
+                files.push(path.to_path_buf());
             }
         }
 
@@ -166,25 +293,7 @@ impl TsRunner {
 
     /// Run the TypeScript compiler (tsc).
     async fn run_tsc(&self) -> TsResult<String> {
-        let tsc = self.find_tsc()?;
-
-        let mut cmd = Command::new(&tsc);
-        cmd.current_dir(&self.workspace);
-
-        // Add noEmit if not emitting
-        if !self.options.emit {
-            cmd.arg("--noEmit");
-        }
-
-        // Add tsconfig if specified
-        if let Some(tsconfig) = &self.options.tsconfig {
-            cmd.arg("--project").arg(tsconfig);
-        }
-
-        // Add custom arguments
-        for arg in &self.options.tsc_args {
-            cmd.arg(arg);
-        }
+        let mut cmd = self.tsc_command()?;
 
         // Capture output
         cmd.stdout(Stdio::piped());
@@ -202,21 +311,84 @@ impl TsRunner {
         Ok(format!("{}{}", stdout, stderr))
     }
 
-    /// Run tsgo (Go-based TypeScript compiler).
-    async fn run_tsgo(&self) -> TsResult<String> {
-        let tsgo = self.find_tsgo()?;
+    /// Build the tsc command.
+    fn tsc_command(&self) -> TsResult<Command> {
+        let tsc = match &self.options.ts_path {
+            Some(path) => check_executable(path)?,
+            None => self.find_tsc()?,
+        };
 
-        let mut cmd = Command::new(&tsgo);
+        let mut cmd = Command::new(tsc);
         cmd.current_dir(&self.workspace);
+        cmd.args(self.tsc_args());
+        Ok(cmd)
+    }
 
-        // Add virtual files directory
-        cmd.arg("--virtualDir").arg(self.vfs.root());
+    /// Build the tsc command-line arguments.
+    ///
+    /// Projects with references are run in build mode so referenced projects
+    /// are checked and built first. Build mode takes the project path
+    /// positionally and doesn't accept `--noEmit`.
+    ///
+    /// Custom arguments come last. `--noEmit` and `--project` are only added
+    /// when the custom arguments don't already set them.
+    fn tsc_args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+
+        if self.uses_build_mode() {
+            args.push("--build".into());
+            if let Some(tsconfig) = &self.options.tsconfig {
+                args.push(tsconfig.into());
+            }
+        } else {
+            // Add noEmit if not emitting
+            if !self.options.emit && !self.has_tsc_arg(&["--noEmit"]) {
+                args.push("--noEmit".into());
+            }
 
-        // Add tsconfig if specified
-        if let Some(tsconfig) = &self.options.tsconfig {
-            cmd.arg("--project").arg(tsconfig);
+            // Add tsconfig if specified
+            if let Some(tsconfig) = &self.options.tsconfig {
+                if !self.has_tsc_arg(&["--project", "-p"]) {
+                    args.push("--project".into());
+                    args.push(tsconfig.into());
+                }
+            }
+        }
+
+        // Pretty output is colored and spread over several lines, which the
+        // diagnostics parser can't read
+        if !self.has_tsc_arg(&["--pretty"]) {
+            args.push("--pretty".into());
+            args.push("false".into());
         }
 
+        // Add custom arguments
+        args.extend(self.options.tsc_args.iter().map(OsString::from));
+
+        args
+    }
+
+    /// Check if the custom arguments include one of the given flags.
+    ///
+    /// tsc flags are case-insensitive.
+    fn has_tsc_arg(&self, flags: &[&str]) -> bool {
+        self.options
+            .tsc_args
+            .iter()
+            .any(|arg| flags.iter().any(|flag| arg.eq_ignore_ascii_case(flag)))
+    }
+
+    /// Check if tsc should run in build mode (`--build`).
+    fn uses_build_mode(&self) -> bool {
+        self.tsconfig
+            .as_ref()
+            .is_some_and(|config| !config.references.is_empty())
+    }
+
+    /// Run tsgo (Go-based TypeScript compiler).
+    async fn run_tsgo(&self) -> TsResult<String> {
+        let mut cmd = self.tsgo_command()?;
+
         // Capture output
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
@@ -232,6 +404,26 @@ impl TsRunner {
         Ok(format!("{}{}", stdout, stderr))
     }
 
+    /// Build the tsgo command.
+    fn tsgo_command(&self) -> TsResult<Command> {
+        let tsgo = match &self.options.ts_path {
+            Some(path) => check_executable(path)?,
+            None => self.find_tsgo()?,
+        };
+
+        let mut cmd = Command::new(tsgo);
+        cmd.current_dir(&self.workspace);
+
+        // Add virtual files directory
+        cmd.arg("--virtualDir").arg(self.vfs.root());
+
+        // Add tsconfig if specified
+        if let Some(tsconfig) = &self.options.tsconfig {
+            cmd.arg("--project").arg(tsconfig);
+        }
+        Ok(cmd)
+    }
+
     /// Find the tsc executable.
     fn find_tsc(&self) -> TsResult<PathBuf> {
         // Try local node_modules first
@@ -268,9 +460,286 @@ impl TsRunner {
     }
 }
 
+/// Check that a configured compiler path is an executable file.
+fn check_executable(path: &Path) -> TsResult<PathBuf> {
+    let metadata = std::fs::metadata(path).map_err(|e| {
+        TsError::process(format!(
+            "TypeScript compiler not found at {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    if !metadata.is_file() {
+        return Err(TsError::process(format!(
+            "TypeScript compiler path {} is not a file",
+            path.display()
+        )));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o111 == 0 {
+            return Err(TsError::process(format!(
+                "TypeScript compiler at {} is not executable",
+                path.display()
+            )));
+        }
+    }
+
+    Ok(path.to_path_buf())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::ffi::OsStr;
+
+    /// Create a runner configured with a compiler at `ts_path`.
+    fn runner_with_ts_path(ts_path: PathBuf, use_tsgo: bool) -> (tempfile::TempDir, TsRunner) {
+        let dir = tempfile::tempdir().unwrap();
+        let options = TsRunnerOptions {
+            ts_path: Some(ts_path),
+            use_tsgo,
+            ..Default::default()
+        };
+        let runner = TsRunner::new(dir.path(), options).unwrap();
+        (dir, runner)
+    }
+
+    #[cfg(unix)]
+    fn executable(dir: &Path, name: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join(name);
+        std::fs::write(&path, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    fn runner_with_tsconfig(tsconfig: &str) -> (tempfile::TempDir, TsRunner) {
+        // Discovery skips hidden directories, like the default `.tmp` prefix
+        let dir = tempfile::Builder::new()
+            .prefix("ts-runner")
+            .tempdir()
+            .unwrap();
+        let path = dir.path().join("tsconfig.json");
+        std::fs::write(&path, tsconfig).unwrap();
+        let options = TsRunnerOptions {
+            tsconfig: Some(path),
+            ..Default::default()
+        };
+        let runner = TsRunner::new(dir.path(), options).unwrap();
+        (dir, runner)
+    }
+
+    #[test]
+    fn test_tsc_args_with_references_use_build() {
+        let (dir, runner) = runner_with_tsconfig(r#"{ "references": [{ "path": "../lib" }] }"#);
+        let args = runner.tsc_args();
+        assert_eq!(
+            args,
+            vec![
+                OsString::from("--build"),
+                dir.path().join("tsconfig.json").into(),
+                OsString::from("--pretty"),
+                OsString::from("false"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tsc_args_without_references() {
+        let (dir, runner) = runner_with_tsconfig("{}");
+        let args = runner.tsc_args();
+        assert_eq!(
+            args,
+            vec![
+                OsString::from("--noEmit"),
+                OsString::from("--project"),
+                dir.path().join("tsconfig.json").into(),
+                OsString::from("--pretty"),
+                OsString::from("false"),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_configured_ts_path_is_used() {
+        let bin = tempfile::tempdir().unwrap();
+
+        let tsc = executable(bin.path(), "my-tsc");
+        let (_dir, runner) = runner_with_ts_path(tsc.clone(), false);
+        let cmd = runner.tsc_command().unwrap();
+        assert_eq!(cmd.as_std().get_program(), tsc.as_os_str());
+        assert_eq!(
+            cmd.as_std().get_args().collect::<Vec<_>>(),
+            vec![
+                OsStr::new("--noEmit"),
+                OsStr::new("--pretty"),
+                OsStr::new("false")
+            ]
+        );
+
+        let tsgo = executable(bin.path(), "my-tsgo");
+        let (_dir, runner) = runner_with_ts_path(tsgo.clone(), true);
+        let cmd = runner.tsgo_command().unwrap();
+        assert_eq!(cmd.as_std().get_program(), tsgo.as_os_str());
+    }
+
+    #[test]
+    fn test_missing_ts_path_is_an_error() {
+        let bin = tempfile::tempdir().unwrap();
+        let (_dir, runner) = runner_with_ts_path(bin.path().join("missing-tsc"), false);
+        let err = runner.tsc_command().unwrap_err();
+        assert_eq!(err.kind, crate::TsErrorKind::Process);
+        assert!(err.to_string().contains("not found at"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_executable_ts_path_is_an_error() {
+        let bin = tempfile::tempdir().unwrap();
+        let tsc = bin.path().join("tsc");
+        std::fs::write(&tsc, "").unwrap();
+        let (_dir, runner) = runner_with_ts_path(tsc, false);
+        let err = runner.tsc_command().unwrap_err();
+        assert!(err.to_string().contains("is not executable"));
+    }
+
+    fn runner_with_tsc_args(tsc_args: &[&str]) -> (tempfile::TempDir, TsRunner) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tsconfig.json");
+        std::fs::write(&path, "{}").unwrap();
+        let options = TsRunnerOptions {
+            tsconfig: Some(path),
+            tsc_args: tsc_args.iter().map(|arg| arg.to_string()).collect(),
+            ..Default::default()
+        };
+        let runner = TsRunner::new(dir.path(), options).unwrap();
+        (dir, runner)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_forwarded_tsc_args_are_appended() {
+        let bin = tempfile::tempdir().unwrap();
+        let (dir, mut runner) = runner_with_tsc_args(&["--skipLibCheck", "--strict"]);
+        runner.options.ts_path = Some(executable(bin.path(), "tsc"));
+
+        let cmd = runner.tsc_command().unwrap();
+        assert_eq!(
+            cmd.as_std().get_args().collect::<Vec<_>>(),
+            vec![
+                OsStr::new("--noEmit"),
+                OsStr::new("--project"),
+                dir.path().join("tsconfig.json").as_os_str(),
+                OsStr::new("--pretty"),
+                OsStr::new("false"),
+                OsStr::new("--skipLibCheck"),
+                OsStr::new("--strict"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_forwarded_tsc_args_replace_injected_flags() {
+        let (_dir, runner) =
+            runner_with_tsc_args(&["-p", "tsconfig.app.json", "--noemit", "--pretty"]);
+        assert_eq!(
+            runner.tsc_args(),
+            vec![
+                OsString::from("-p"),
+                OsString::from("tsconfig.app.json"),
+                OsString::from("--noemit"),
+                OsString::from("--pretty"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_js_components_are_checked_with_check_js() {
+        for (tsconfig, checked) in [
+            (
+                r#"{ "compilerOptions": { "allowJs": true, "checkJs": true } }"#,
+                true,
+            ),
+            (r#"{ "compilerOptions": { "allowJs": true } }"#, false),
+        ] {
+            let (dir, mut runner) = runner_with_tsconfig(tsconfig);
+            runner.vfs = VirtualFileSystem::with_source_root(
+                dir.path().join("virtual"),
+                dir.path().to_path_buf(),
+            );
+            std::fs::write(
+                dir.path().join("Js.vue"),
+                "<script setup>\nconst n = 1\n</script>\n<template>{{ n }}</template>\n",
+            )
+            .unwrap();
+            std::fs::write(
+                dir.path().join("Ts.vue"),
+                "<script setup lang=\"ts\">\nconst n = 1\n</script>\n",
+            )
+            .unwrap();
+            runner
+                .generate_virtual_files(&mut DiagnosticRemapper::new())
+                .unwrap();
+
+            // Both are TypeScript, so the virtual project includes them
+            let js = runner
+                .vfs
+                .read(&dir.path().join("virtual/Js.vue.ts"))
+                .unwrap();
+            let ts = runner
+                .vfs
+                .read(&dir.path().join("virtual/Ts.vue.ts"))
+                .unwrap();
+            assert_eq!(!js.starts_with("// @ts-nocheck"), checked, "{}", tsconfig);
+            assert!(!ts.starts_with("// @ts-nocheck"));
+        }
+    }
+
+    #[test]
+    fn test_configured_extensions_are_discovered() {
+        let (dir, mut runner) = runner_with_tsconfig(
+            r#"{ "vueCompilerOptions": { "extensions": [".vue", ".md", ".vue.html"] } }"#,
+        );
+        runner.vfs = VirtualFileSystem::with_source_root(
+            dir.path().join("virtual"),
+            dir.path().to_path_buf(),
+        );
+        let component = "<script setup lang=\"ts\">\nconst n = 1\n</script>\n";
+        for file in ["App.vue", "Guide.md", "Page.vue.html", "Other.html"] {
+            std::fs::write(dir.path().join(file), component).unwrap();
+        }
+
+        let mut files = runner.find_vue_files().unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            ["App.vue", "Guide.md", "Page.vue.html"].map(|file| dir.path().join(file))
+        );
+
+        // The language still comes from the script, not the extension
+        runner
+            .generate_virtual_files(&mut DiagnosticRemapper::new())
+            .unwrap();
+        let guide = runner
+            .vfs
+            .read(&dir.path().join("virtual/Guide.md.ts"))
+            .unwrap();
+        assert!(!guide.starts_with("// @ts-nocheck"));
+        assert!(guide.contains("const n = 1"));
+    }
+
+    #[test]
+    fn test_default_temp_dir_is_per_workspace() {
+        let a = default_temp_dir(Path::new("/repo/packages/a"));
+        let b = default_temp_dir(Path::new("/repo/packages/b"));
+        assert_ne!(a, b);
+        assert!(a.starts_with(std::env::temp_dir().join("vue-tsc-rs")));
+        assert_eq!(a, default_temp_dir(Path::new("/repo/packages/a")));
+    }
 
     #[test]
     fn test_runner_options() {
@@ -10,6 +10,8 @@ use std::path::{Path, PathBuf};
 pub struct VirtualFileSystem {
     /// Root directory for virtual files.
     root: PathBuf,
+    /// Directory whose layout the virtual files mirror.
+    source_root: Option<PathBuf>,
     /// Map of original file to virtual file.
     files: HashMap<PathBuf, VirtualFile>,
 }
@@ -33,17 +35,50 @@ impl VirtualFileSystem {
 
         Self {
             root,
+            source_root: None,
             files: HashMap::new(),
         }
     }
 
+    /// Create a virtual file system mirroring the layout of `source_root`.
+    ///
+    /// A virtual file has the same path relative to the root as its original
+    /// has relative to `source_root`, so relative imports between them keep
+    /// working, and resolve back to the sources through `rootDirs`.
+    pub fn with_source_root(root: PathBuf, source_root: PathBuf) -> Self {
+        let mut vfs = Self::new(root);
+        vfs.source_root = Some(source_root);
+        vfs
+    }
+
     /// Get the root directory.
     pub fn root(&self) -> &Path {
         &self.root
     }
 
+    /// Get the directory whose layout the virtual files mirror.
+    pub fn source_root(&self) -> Option<&Path> {
+        self.source_root.as_deref()
+    }
+
     /// Generate a virtual path for an original file.
+    ///
+    /// Files under the source root keep their relative path, with the
+    /// extension appended (`src/App.vue` becomes `src/App.vue.ts`), which is
+    /// also where TypeScript looks when resolving an import of `./App.vue`.
+    /// Other files get a flat name made unique by a hash of their directory.
     pub fn virtual_path(&self, original: &Path, extension: &str) -> PathBuf {
+        if let Some(relative) = self
+            .source_root
+            .as_deref()
+            .and_then(|source_root| original.strip_prefix(source_root).ok())
+        {
+            let mut path = self.root.join(relative).into_os_string();
+            path.push(".");
+            path.push(extension);
+            return PathBuf::from(path);
+        }
+
         // Create a unique path based on the original file
         let file_name = original
             .file_stem()
@@ -170,6 +205,15 @@ pub fn generate_virtual_tsconfig(
         ]
     });
 
+    // Relative imports of plain `.ts` files from a virtual file resolve
+    // against both trees, finding the original
+    if let Some(source_root) = vfs.source_root() {
+        config["compilerOptions"]["rootDirs"] = serde_json::json!([
+            source_root.display().to_string(),
+            vfs.root().display().to_string()
+        ]);
+    }
+
     if let Some(base) = base_tsconfig {
         config["extends"] = serde_json::Value::String(base.to_string_lossy().to_string());
     }
@@ -192,6 +236,50 @@ mod tests {
         assert!(virtual_path.to_string_lossy().ends_with(".ts"));
     }
 
+    #[test]
+    fn test_virtual_path_mirrors_source_root() {
+        let vfs = VirtualFileSystem::with_source_root(
+            env::temp_dir().join("vue-tsc-rs-test-mirror"),
+            PathBuf::from("/home/user/project"),
+        );
+        assert_eq!(
+            vfs.virtual_path(Path::new("/home/user/project/src/App.vue"), "ts"),
+            vfs.root().join("src/App.vue.ts")
+        );
+        // Files outside the source root are kept flat
+        let outside = vfs.virtual_path(Path::new("/elsewhere/Lib.vue"), "ts");
+        assert_eq!(outside.parent(), Some(vfs.root()));
+    }
+
+    #[test]
+    fn test_relative_import_resolves_through_root_dirs() {
+        let source = tempfile::tempdir().unwrap();
+        let virtual_root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(source.path().join("src")).unwrap();
+        fs::write(source.path().join("src/utils.ts"), "export const x = 1;").unwrap();
+        let vfs = VirtualFileSystem::with_source_root(
+            virtual_root.path().to_path_buf(),
+            source.path().to_path_buf(),
+        );
+
+        let tsconfig: serde_json::Value =
+            serde_json::from_str(&generate_virtual_tsconfig(&vfs, None).unwrap()).unwrap();
+        let root_dirs: Vec<PathBuf> = tsconfig["compilerOptions"]["rootDirs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|dir| PathBuf::from(dir.as_str().unwrap()))
+            .collect();
+        assert_eq!(root_dirs, [source.path(), virtual_root.path()]);
+
+        // `./utils` from the virtual `src/App.vue.ts` is `src/utils` under
+        // every root dir, and exists under the source root
+        let virtual_file = vfs.virtual_path(&source.path().join("src/App.vue"), "ts");
+        let import = virtual_file.parent().unwrap().join("utils.ts");
+        let relative = import.strip_prefix(vfs.root()).unwrap();
+        assert!(root_dirs.iter().any(|dir| dir.join(relative).exists()));
+    }
+
     #[test]
     fn test_write_read() {
         let vfs = VirtualFileSystem::new(env::temp_dir().join("vue-tsc-rs-test-rw"));
@@ -4,6 +4,9 @@ use crate::{CodegenError, CodegenOptions, MacroInfo, ScriptLang};
 use rustc_hash::FxHashSet;
 use smol_str::SmolStr;
 
+/// Prefix of every name the generated code declares.
+pub const GENERATED_PREFIX: &str = "__VLS_";
+
 /// Context for code generation.
 #[derive(Debug, Clone)]
 pub struct CodegenContext {
@@ -23,8 +26,10 @@ pub struct CodegenContext {
     pub directives: FxHashSet<SmolStr>,
     /// Errors during code generation.
     pub errors: Vec<CodegenError>,
-    /// Counter for generating unique names.
-    pub counter: u32,
+    /// Offset of the template content within the SFC.
+    pub template_offset: u32,
+    /// Whether script setup uses top-level `await`.
+    pub async_setup: bool,
 }
 
 /// A variable in the current scope.
@@ -65,14 +70,21 @@ impl CodegenContext {
             components: FxHashSet::default(),
             directives: FxHashSet::default(),
             errors: Vec::new(),
-            counter: 0,
+            template_offset: 0,
+            async_setup: false,
         }
     }
 
-    /// Generate a unique identifier.
-    pub fn unique_id(&mut self, prefix: &str) -> String {
-        self.counter += 1;
-        format!("{}{}", prefix, self.counter)
+    /// Generate an identifier unique to the template node at `span`.
+    ///
+    /// Ids come from the node's SFC offset rather than a counter, so the same
+    /// template always generates the same virtual code.
+    pub fn unique_id(&self, prefix: &str, span: source_map::Span) -> String {
+        format!(
+            "{}_{}",
+            prefix,
+            span.shift(self.template_offset.into()).start
+        )
     }
 
     /// Add a scope variable.
@@ -117,7 +129,7 @@ impl CodegenContext {
         self.directives.insert(name.into());
     }
 
-    /// Add an error.
+    /// Add an error, with its span in the SFC.
     pub fn error(&mut self, message: impl Into<String>, span: source_map::Span) {
         self.errors.push(CodegenError {
             message: message.into(),
@@ -125,6 +137,25 @@ impl CodegenContext {
         });
     }
 
+    /// Report a user binding named like the `__VLS_` helpers of the
+    /// generated code, which it would collide with. `span` is in the SFC.
+    pub fn check_binding_name(&mut self, name: &str, span: source_map::Span) {
+        if name.starts_with(GENERATED_PREFIX) {
+            self.error(
+                format!(
+                    "'{}' uses the {} prefix reserved for generated code; rename it",
+                    name, GENERATED_PREFIX
+                ),
+                span,
+            );
+        }
+    }
+
+    /// Add an error with a span relative to the template content.
+    pub fn template_error(&mut self, message: impl Into<String>, span: source_map::Span) {
+        self.error(message, span.shift(self.template_offset.into()));
+    }
+
     /// Check if using TypeScript.
     pub fn is_typescript(&self) -> bool {
         self.lang.is_typescript()
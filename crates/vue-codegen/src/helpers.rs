@@ -24,6 +24,8 @@ type __VLS_TypePropsToOption<T> = {
 
 type __VLS_WithComponent<N, C> = C;
 
+type __VLS_FallthroughAttrs<P> = { [K in keyof P]?: never } & Record<string, unknown>;
+
 type __VLS_IntrinsicElements = {
     [K in keyof HTMLElementTagNameMap]: Partial<HTMLElementTagNameMap[K]>;
 } & {
@@ -47,6 +49,32 @@ declare function __VLS_asFunctionalComponent<T>(
         : never
     : T;
 
+// Props Vue accepts on any component, declared or not.
+type __VLS_ReservedProps = {
+    key?: PropertyKey;
+    ref?: unknown;
+    ref_for?: boolean;
+    ref_key?: string;
+    class?: unknown;
+    style?: unknown;
+};
+
+// With strictTemplates, a component takes exactly its declared props, so an
+// unknown prop is an excess property rather than a fallthrough attribute.
+// Props may still be omitted, as v-model and v-bind can provide them.
+// Functional and generic components keep their type parameters, so each
+// usage infers them from the props it passes.
+declare function __VLS_asStrictFunctionalComponent<P>(
+    t: (props: P, ...args: any[]) => any,
+): (props: Partial<P> & __VLS_ReservedProps) => void;
+declare function __VLS_asStrictFunctionalComponent<T>(
+    t: T,
+): 0 extends 1 & T
+    ? (props: Record<string, unknown>) => void
+    : T extends new (...args: any[]) => { $props: infer P }
+    ? (props: Partial<P> & __VLS_ReservedProps) => void
+    : (props: Record<string, unknown>) => void;
+
 declare function __VLS_getVForSourceType<T>(
     source: T,
 ): T extends number
@@ -63,6 +91,17 @@ declare function __VLS_getSlotParams<T>(
     slot: T,
 ): T extends (...args: any[]) => any ? Parameters<T>[0] : never;
 
+// The slots a component accepts, as functions of their props.
+declare function __VLS_componentSlots<T>(
+    t: T,
+): 0 extends 1 & T
+    ? Record<string, any>
+    : T extends new (...args: any[]) => { $slots: infer S }
+    ? NonNullable<S>
+    : T extends (props: any, ctx: { slots: infer S }, ...args: any[]) => any
+    ? NonNullable<S>
+    : Record<string, any>;
+
 declare function __VLS_elementAsFunction<T extends keyof __VLS_IntrinsicElements>(
     tag: T,
 ): (props: __VLS_IntrinsicElements[T]) => void;
@@ -77,12 +116,95 @@ declare function __VLS_componentAsFunction<T>(
 
 declare function __VLS_resolveComponent<T extends string>(
     name: T,
-): any;
+): T extends keyof import('vue').GlobalComponents
+    ? import('vue').GlobalComponents[T]
+    : any;
 
 declare function __VLS_resolveDirective<T extends string>(
     name: T,
 ): any;
 
+// The binding value type of a directive, `V` in `Directive<El, V>`.
+type __VLS_DirectiveValue<D> = D extends import('vue').ObjectDirective<any, infer V>
+    ? V
+    : D extends import('vue').FunctionDirective<any, infer V>
+    ? V
+    : any;
+
+// `value` is typed from the directive alone, so a binding of the wrong
+// type is reported instead of widening `V`.
+declare function __VLS_directiveValue<D>(directive: D, value: __VLS_DirectiveValue<D>): void;
+
+// Values an interpolation can render. Plain objects and functions are left
+// out, as interpolating them is almost always a mistake.
+type __VLS_Renderable =
+    | string
+    | number
+    | bigint
+    | boolean
+    | symbol
+    | null
+    | undefined
+    | readonly unknown[]
+    | ReadonlyMap<unknown, unknown>
+    | ReadonlySet<unknown>
+    | { toJSON(): unknown };
+
+declare function __VLS_toDisplayString(value: __VLS_Renderable): string;
+
+// A method handler must accept the native event it is bound to. It may
+// ignore it, so this is checked by assignment rather than by a call.
+declare function __VLS_eventHandler<E extends Event>(
+    handler: (event: E) => unknown,
+): void;
+
+// Props of Vue's built-in components. They are checked exactly, whatever
+// `strictTemplates` says.
+type __VLS_TransitionProps = {
+    name?: string;
+    type?: 'transition' | 'animation';
+    css?: boolean;
+    duration?: number | { enter: number; leave: number };
+    mode?: 'in-out' | 'out-in' | 'default';
+    appear?: boolean;
+    persisted?: boolean;
+    enterFromClass?: string;
+    enterActiveClass?: string;
+    enterToClass?: string;
+    appearFromClass?: string;
+    appearActiveClass?: string;
+    appearToClass?: string;
+    leaveFromClass?: string;
+    leaveActiveClass?: string;
+    leaveToClass?: string;
+};
+
+type __VLS_BuiltinProps = {
+    Transition: __VLS_TransitionProps;
+    TransitionGroup: Omit<__VLS_TransitionProps, 'mode'> & {
+        tag?: string;
+        moveClass?: string;
+    };
+    KeepAlive: {
+        include?: string | RegExp | (string | RegExp)[];
+        exclude?: string | RegExp | (string | RegExp)[];
+        max?: number | string;
+    };
+    Suspense: {
+        timeout?: string | number;
+        suspensible?: boolean;
+    };
+    Teleport: {
+        to: string | Element | null | undefined;
+        disabled?: boolean;
+        defer?: boolean;
+    };
+};
+
+declare function __VLS_builtinComponent<T extends keyof __VLS_BuiltinProps>(
+    name: T,
+): (props: __VLS_BuiltinProps[T] & __VLS_ReservedProps) => void;
+
 declare function __VLS_withAsyncContext<T>(
     getAwaitable: () => Promise<T>,
 ): Promise<T>;
@@ -135,11 +257,60 @@ pub fn is_builtin_component(name: &str) -> bool {
         .any(|&builtin| builtin.eq_ignore_ascii_case(name))
 }
 
+/// Check if a directive name (without `v-`) is a built-in directive.
+pub fn is_builtin_directive(name: &str) -> bool {
+    BUILTIN_DIRECTIVES
+        .iter()
+        .any(|builtin| builtin.strip_prefix("v-") == Some(name))
+}
+
+/// Get the built-in component a tag refers to, in PascalCase.
+///
+/// Both `<KeepAlive>` and `<keep-alive>` are `KeepAlive`.
+pub fn builtin_component(tag: &str) -> Option<&'static str> {
+    let name = vue_template_compiler::transforms::pascalize(tag);
+    BUILTIN_COMPONENTS
+        .iter()
+        .copied()
+        .find(|&builtin| builtin == name)
+}
+
 /// Check if a tag is an HTML element.
 pub fn is_html_tag(tag: &str) -> bool {
     HTML_TAGS.contains(&tag.to_lowercase().as_str())
 }
 
+/// Get the DOM event type for a native event name, as in `HTMLElementEventMap`.
+pub fn dom_event_type(event: &str) -> Option<&'static str> {
+    let event_type = match event {
+        "click" | "dblclick" | "auxclick" | "contextmenu" | "mousedown" | "mouseup"
+        | "mousemove" | "mouseover" | "mouseout" | "mouseenter" | "mouseleave" => "MouseEvent",
+        "pointerdown" | "pointerup" | "pointermove" | "pointerover" | "pointerout"
+        | "pointerenter" | "pointerleave" | "pointercancel" | "gotpointercapture"
+        | "lostpointercapture" => "PointerEvent",
+        "keydown" | "keyup" | "keypress" => "KeyboardEvent",
+        "focus" | "blur" | "focusin" | "focusout" => "FocusEvent",
+        "beforeinput" => "InputEvent",
+        "input" | "change" | "reset" | "select" | "scroll" | "load" | "invalid" => "Event",
+        "submit" => "SubmitEvent",
+        "wheel" => "WheelEvent",
+        "drag" | "dragstart" | "dragend" | "dragenter" | "dragleave" | "dragover" | "drop" => {
+            "DragEvent"
+        }
+        "touchstart" | "touchend" | "touchmove" | "touchcancel" => "TouchEvent",
+        "copy" | "cut" | "paste" => "ClipboardEvent",
+        "compositionstart" | "compositionupdate" | "compositionend" => "CompositionEvent",
+        "animationstart" | "animationend" | "animationiteration" | "animationcancel" => {
+            "AnimationEvent"
+        }
+        "transitionstart" | "transitionend" | "transitionrun" | "transitioncancel" => {
+            "TransitionEvent"
+        }
+        _ => return None,
+    };
+    Some(event_type)
+}
+
 /// Check if a tag is an SVG element.
 pub fn is_svg_tag(tag: &str) -> bool {
     SVG_TAGS.contains(&tag.to_lowercase().as_str())
@@ -9,8 +9,10 @@ pub mod helpers;
 pub mod script;
 pub mod template;
 
-use source_map::{CodeBuilder, SourceMap};
-use vue_parser::Sfc;
+use crate::context::VarSource;
+use source_map::{CodeBuilder, SourceMap, Span};
+use vue_parser::{BlockKind, Sfc};
+use vue_template_compiler::{ParseOptions, TemplateAst};
 
 pub use context::CodegenContext;
 pub use script::generate_script;
@@ -27,6 +29,28 @@ pub struct CodegenResult {
     pub lang: ScriptLang,
     /// Errors encountered during code generation.
     pub errors: Vec<CodegenError>,
+    /// The SFC source the code was generated from.
+    pub source: String,
+    /// The SFC file name, from [`CodegenOptions::filename`].
+    pub filename: Option<String>,
+}
+
+impl CodegenResult {
+    /// Get the code with an inline Source Map v3 comment appended, so the
+    /// virtual file can be traced back to the `.vue` file in an editor.
+    pub fn with_inline_source_map(&self) -> String {
+        let name = self.filename.as_deref().unwrap_or("component.vue");
+        let map = self.source_map.to_v3(&self.code, &self.source, name);
+
+        let mut code = self.code.clone();
+        if !code.ends_with('\n') {
+            code.push('\n');
+        }
+        code.push_str("//# sourceMappingURL=data:application/json;base64,");
+        code.push_str(&source_map::base64_encode(map.as_bytes()));
+        code.push('\n');
+        code
+    }
 }
 
 /// Script language.
@@ -54,24 +78,61 @@ impl ScriptLang {
     pub fn is_typescript(&self) -> bool {
         matches!(self, Self::Ts | Self::Tsx)
     }
+
+    /// Get the extension of the virtual file generated for this language.
+    ///
+    /// The generated code is TypeScript whatever the script language, so
+    /// JavaScript components get a TypeScript extension too.
+    pub fn virtual_extension(&self) -> &'static str {
+        match self {
+            Self::Ts | Self::Js => "ts",
+            Self::Tsx | Self::Jsx => "tsx",
+        }
+    }
 }
 
 /// A code generation error.
 #[derive(Debug, Clone)]
 pub struct CodegenError {
+    /// The error message.
     pub message: String,
+    /// The span in the SFC where the error occurred.
     pub span: source_map::Span,
 }
 
+impl std::fmt::Display for CodegenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for CodegenError {}
+
 /// Options for code generation.
 #[derive(Debug, Clone, Default)]
 pub struct CodegenOptions {
     /// The target Vue version (3.0, 3.3, 3.5, etc.).
     pub target: VueTarget,
-    /// Whether to generate strict type checks.
+    /// Whether to generate strict type checks (`strictTemplates`).
+    ///
+    /// Components then reject props they don't declare.
     pub strict: bool,
     /// File name for the SFC.
     pub filename: Option<String>,
+    /// Template parsing options.
+    pub parse_options: ParseOptions,
+    /// Leave out the template type checking function, so only the scripts
+    /// are checked.
+    pub skip_template: bool,
+    /// Type check components whose script is JavaScript, as `checkJs` does
+    /// for `.js` files.
+    ///
+    /// Otherwise their virtual code starts with `// @ts-nocheck`, so they
+    /// only provide types to the files that import them.
+    pub check_js: bool,
+    /// Import the Vue helper types with `import type`, as tsc requires under
+    /// `verbatimModuleSyntax`.
+    pub verbatim_module_syntax: bool,
 }
 
 /// Vue target version.
@@ -86,6 +147,19 @@ pub enum VueTarget {
     V3_5,
 }
 
+impl VueTarget {
+    /// Get the target for a version number, like `vueCompilerOptions.target`.
+    pub fn from_version(version: f32) -> Self {
+        if version < 3.3 {
+            Self::V3_0
+        } else if version < 3.5 {
+            Self::V3_3
+        } else {
+            Self::V3_5
+        }
+    }
+}
+
 /// Generate TypeScript code from a Vue SFC.
 pub fn generate(sfc: &Sfc, options: &CodegenOptions) -> CodegenResult {
     let mut ctx = CodegenContext::new(options.clone());
@@ -95,6 +169,11 @@ pub fn generate(sfc: &Sfc, options: &CodegenOptions) -> CodegenResult {
     let lang = detect_script_lang(sfc);
     ctx.lang = lang;
 
+    // Like `.js` files, JavaScript components are only checked with `checkJs`
+    if !lang.is_typescript() && !options.check_js {
+        builder.push_str("// @ts-nocheck\n");
+    }
+
     // Generate imports and helpers
     generate_helpers(&mut builder, &ctx);
 
@@ -103,16 +182,14 @@ pub fn generate(sfc: &Sfc, options: &CodegenOptions) -> CodegenResult {
         generate_script(&mut builder, script, &mut ctx);
     }
 
-    // Generate script setup content
+    // Generate script setup content, with the template inside it so setup
+    // bindings are in scope
     if let Some(script_setup) = &sfc.script_setup {
         generate_script_setup(&mut builder, script_setup, sfc, &mut ctx);
-    }
-
-    // Generate template type checking code
-    if let Some(template) = &sfc.template {
-        if let Ok(ast) = vue_template_compiler::parse_template(&template.content) {
-            generate_template(&mut builder, &ast, &mut ctx);
-        }
+        generate_template_block(&mut builder, sfc, options, &mut ctx);
+        generate_setup_return(&mut builder, &ctx);
+    } else {
+        generate_template_block(&mut builder, sfc, options, &mut ctx);
     }
 
     // Generate component export
@@ -125,9 +202,70 @@ pub fn generate(sfc: &Sfc, options: &CodegenOptions) -> CodegenResult {
         source_map,
         lang,
         errors: ctx.errors,
+        source: sfc.content.clone(),
+        filename: options.filename.clone(),
     }
 }
 
+/// The result of re-parsing a single block with [`reparse_block`].
+#[derive(Debug, Clone)]
+pub enum BlockParse {
+    /// The compiled template.
+    Template(TemplateAst),
+    /// The ranges found in a regular script.
+    Script(script::ScriptRanges),
+    /// The macros used in script setup.
+    ScriptSetup(MacroInfo),
+    /// Styles and custom blocks have nothing to parse beyond their content.
+    Content,
+}
+
+/// Replace the content of one block and re-parse only that block.
+///
+/// The template is recompiled and script blocks have their macros or ranges
+/// extracted again; the rest of the SFC is kept, with spans after the block
+/// shifted. Errors are reported with spans in the SFC.
+pub fn reparse_block(
+    sfc: &mut Sfc,
+    kind: BlockKind,
+    new_content: &str,
+    options: &CodegenOptions,
+) -> Result<BlockParse, CodegenError> {
+    sfc.replace_block_content(kind, new_content)
+        .map_err(|e| CodegenError {
+            message: e.message.clone(),
+            span: e.span,
+        })?;
+
+    let parse = match kind {
+        BlockKind::Template => {
+            let template = sfc.template.as_ref().expect("block was replaced");
+            let ast = vue_template_compiler::parse_template_with_options(
+                &template.content,
+                &options.parse_options,
+            )
+            .map_err(|e| CodegenError {
+                message: e.message,
+                span: e.span.shift(template.content_span.start.into()),
+            })?;
+            BlockParse::Template(ast)
+        }
+        BlockKind::Script => {
+            let script = sfc.script.as_ref().expect("block was replaced");
+            BlockParse::Script(script::analyze_script(&script.content))
+        }
+        BlockKind::ScriptSetup => {
+            let script_setup = sfc.script_setup.as_ref().expect("block was replaced");
+            BlockParse::ScriptSetup(extract_macros(
+                &script_setup.content,
+                script_setup.content_span.start,
+            ))
+        }
+        BlockKind::Style(_) | BlockKind::Custom(_) => BlockParse::Content,
+    };
+    Ok(parse)
+}
+
 /// Detect the script language from an SFC.
 fn detect_script_lang(sfc: &Sfc) -> ScriptLang {
     let lang_str = sfc.script_lang().unwrap_or("js");
@@ -140,28 +278,81 @@ fn detect_script_lang(sfc: &Sfc) -> ScriptLang {
 }
 
 /// Generate helper types and imports.
-fn generate_helpers(builder: &mut CodeBuilder, _ctx: &CodegenContext) {
-    // Import Vue types
-    builder.push_str("import { ");
-    builder.push_str("defineComponent as __VLS_defineComponent, ");
-    builder.push_str("ref as __VLS_ref, ");
-    builder.push_str("computed as __VLS_computed, ");
-    builder.push_str("reactive as __VLS_reactive, ");
-    builder.push_str("PropType as __VLS_PropType, ");
-    builder.push_str("ExtractPropTypes as __VLS_ExtractPropTypes, ");
-    builder.push_str("ComponentPublicInstance as __VLS_ComponentPublicInstance ");
-    builder.push_str("} from 'vue';\n\n");
+fn generate_helpers(builder: &mut CodeBuilder, ctx: &CodegenContext) {
+    const VALUES: &str = "defineComponent as __VLS_defineComponent, \
+        ref as __VLS_ref, \
+        computed as __VLS_computed, \
+        reactive as __VLS_reactive";
+    const TYPES: &str = "PropType as __VLS_PropType, \
+        ExtractPropTypes as __VLS_ExtractPropTypes, \
+        ComponentPublicInstance as __VLS_ComponentPublicInstance";
+
+    // Import Vue types; under verbatimModuleSyntax a type imported without
+    // `import type` is an error (TS1484)
+    if ctx.options.verbatim_module_syntax {
+        builder.push_str(&format!("import {{ {} }} from 'vue';\n", VALUES));
+        builder.push_str(&format!("import type {{ {} }} from 'vue';\n\n", TYPES));
+    } else {
+        builder.push_str(&format!(
+            "import {{ {}, {} }} from 'vue';\n\n",
+            VALUES, TYPES
+        ));
+    }
 
     // Helper types
     builder.push_str(helpers::VLS_HELPER_TYPES);
     builder.newline();
 }
 
-/// Generate script setup code.
+/// Generate template type checking code, unless templates are skipped.
+fn generate_template_block(
+    builder: &mut CodeBuilder,
+    sfc: &Sfc,
+    options: &CodegenOptions,
+    ctx: &mut CodegenContext,
+) {
+    let Some(template) = sfc.template.as_ref().filter(|_| !options.skip_template) else {
+        return;
+    };
+    if let Ok(ast) = vue_template_compiler::parse_template_with_options(
+        &template.content,
+        &options.parse_options,
+    ) {
+        // Template spans are relative to the template content
+        ctx.template_offset = template.content_span.start;
+        generate_template(builder, &ast, ctx);
+    }
+}
+
+/// Get the SFC span of the first occurrence of a binding in a script block,
+/// or of the whole block content if it can't be found.
+fn binding_span(content: &str, name: &str, content_span: Span) -> Span {
+    let is_ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'$';
+    let bytes = content.as_bytes();
+    content
+        .match_indices(name)
+        .map(|(start, _)| start)
+        .find(|&start| {
+            let end = start + name.len();
+            (start == 0 || !is_ident(bytes[start - 1]))
+                && bytes.get(end).map_or(true, |&b| !is_ident(b))
+        })
+        .map_or(content_span, |start| {
+            let start = content_span.start + start as u32;
+            Span::new(start, start + name.len() as u32)
+        })
+}
+
+/// Generate the opening of the script setup function and its content.
+///
+/// The function is left open for the template; [`generate_setup_return`]
+/// closes it. Setup bindings, and those of a regular `<script>`, become scope
+/// variables so the template reads them directly instead of through
+/// `__VLS_ctx`.
 fn generate_script_setup(
     builder: &mut CodeBuilder,
     script_setup: &vue_parser::ScriptSetupBlock,
-    _sfc: &Sfc,
+    sfc: &Sfc,
     ctx: &mut CodegenContext,
 ) {
     // Handle generics
@@ -170,8 +361,45 @@ fn generate_script_setup(
     }
 
     // Extract macros from script setup
-    let macros = extract_macros(&script_setup.content);
+    let macros = extract_macros(&script_setup.content, script_setup.content_span.start);
     ctx.macros = macros;
+    for name in script::top_level_bindings(&script_setup.content) {
+        let span = binding_span(&script_setup.content, &name, script_setup.content_span);
+        ctx.check_binding_name(&name, span);
+        ctx.add_var(name, VarSource::Setup);
+    }
+    let models: Vec<_> = ctx
+        .macros
+        .define_models
+        .iter()
+        .map(|m| m.name.clone())
+        .collect();
+    for name in models {
+        ctx.add_var(name, VarSource::Setup);
+    }
+    if let Some(script) = &sfc.script {
+        for name in script::top_level_bindings(&script.content) {
+            let span = binding_span(&script.content, &name, script.content_span);
+            ctx.check_binding_name(&name, span);
+            ctx.add_var(name, VarSource::Setup);
+        }
+    }
+
+    // Imports, exports, enums and ambient declarations are only allowed at
+    // module scope, so they go before the setup function
+    let content = &script_setup.content;
+    let content_start = script_setup.content_span.start;
+    let hoisted = script::module_level_ranges(content, ctx.generics.is_none());
+    for &(start, end) in &hoisted {
+        builder.push_mapped(&content[start..end], content_start + start as u32);
+        builder.newline();
+    }
+
+    // Top-level await makes setup async; awaited values keep their types
+    ctx.async_setup = script::has_top_level_await(&script_setup.content);
+    if ctx.async_setup {
+        builder.push_str("async ");
+    }
 
     // Generate the setup function wrapper
     if ctx.generics.is_some() {
@@ -185,12 +413,18 @@ fn generate_script_setup(
     // Generate macro declarations
     generate_macro_declarations(builder, &ctx.macros, ctx);
 
-    // Output the script content with mappings
-    let content_start = script_setup.content_span.start;
-    builder.push_mapped(&script_setup.content, content_start);
+    // Output the rest of the script content with mappings
+    let mut pos = 0;
+    for &(start, end) in &hoisted {
+        builder.push_mapped(&content[pos..start], content_start + pos as u32);
+        pos = end;
+    }
+    builder.push_mapped(&content[pos..], content_start + pos as u32);
     builder.newline();
+}
 
-    // Generate return type
+/// Close the script setup function, returning its bindings.
+fn generate_setup_return(builder: &mut CodeBuilder, ctx: &CodegenContext) {
     builder.push_str("\nreturn {\n");
     for export in &ctx.macros.exposed {
         builder.push_str("  ");
@@ -215,7 +449,15 @@ fn generate_macro_declarations(
             builder.push_str(type_arg);
             builder.push_str(">");
         }
-        builder.push_str("();\n");
+        if props.names.is_empty() {
+            builder.push_str("();\n");
+        } else {
+            // Vue types each name in the array form as an optional `any` prop
+            let names: Vec<_> = props.names.iter().map(|n| format!("'{}'", n)).collect();
+            builder.push_str("([");
+            builder.push_str(&names.join(", "));
+            builder.push_str("]);\n");
+        }
 
         // Destructured props
         if let Some(pattern) = &props.destructure_pattern {
@@ -231,9 +473,20 @@ fn generate_macro_declarations(
         if let Some(type_arg) = &emits.type_arg {
             builder.push_str("<");
             builder.push_str(type_arg);
-            builder.push_str(">");
+            builder.push_str(">();\n");
+        } else if let Some(validators) = &emits.validators {
+            // Validators type the payload of each event
+            builder.push_str("(");
+            builder.push_str(validators);
+            builder.push_str(");\n");
+        } else if emits.names.is_empty() {
+            builder.push_str("();\n");
+        } else {
+            let names: Vec<_> = emits.names.iter().map(|n| format!("'{}'", n)).collect();
+            builder.push_str("([");
+            builder.push_str(&names.join(", "));
+            builder.push_str("]);\n");
         }
-        builder.push_str("();\n");
     }
 
     // defineSlots
@@ -278,7 +531,9 @@ fn generate_macro_declarations(
 fn generate_component_export(builder: &mut CodeBuilder, sfc: &Sfc, ctx: &CodegenContext) {
     builder.push_str("\n// Component definition\n");
 
-    if sfc.has_script_setup() {
+    if let Some(generics) = ctx.generics.as_deref().filter(|_| sfc.has_script_setup()) {
+        generate_generic_component_export(builder, generics, ctx);
+    } else if sfc.has_script_setup() {
         // Export the setup-based component
         builder.push_str("export default __VLS_defineComponent({\n");
 
@@ -292,6 +547,19 @@ fn generate_component_export(builder: &mut CodeBuilder, sfc: &Sfc, ctx: &Codegen
             builder.push_str("  emits: {} as typeof __VLS_emit,\n");
         }
 
+        // Attribute fallthrough
+        let inherit_attrs = ctx
+            .macros
+            .define_options
+            .as_ref()
+            .and_then(|o| o.inherit_attrs);
+        if inherit_attrs == Some(false) {
+            builder.push_str("  inheritAttrs: false,\n");
+        }
+
+        if ctx.async_setup {
+            builder.push_str("  // async setup: the component must be rendered in <Suspense>\n");
+        }
         builder.push_str("  setup: __VLS_setup,\n");
         builder.push_str("});\n");
     } else if sfc.script.is_some() {
@@ -303,38 +571,86 @@ fn generate_component_export(builder: &mut CodeBuilder, sfc: &Sfc, ctx: &Codegen
     }
 }
 
+/// Export a generic component as a function of its props.
+///
+/// A component object can't carry type parameters, so usages couldn't infer
+/// them. As a generic function, each usage infers them from the props it is
+/// given, like a call.
+fn generate_generic_component_export(
+    builder: &mut CodeBuilder,
+    generics: &str,
+    ctx: &CodegenContext,
+) {
+    builder.push_str("export default {} as unknown as <");
+    builder.push_str(generics);
+    builder.push_str(">(\n  props: ");
+    match ctx
+        .macros
+        .define_props
+        .as_ref()
+        .and_then(|props| props.type_arg.as_deref())
+    {
+        Some(type_arg) => {
+            builder.push_str(type_arg);
+            builder.push_str(" & __VLS_ReservedProps");
+        }
+        None => builder.push_str("__VLS_ReservedProps"),
+    }
+    builder.push_str(",\n) => any;\n");
+}
+
 /// Extract macro information from script setup content.
-fn extract_macros(content: &str) -> MacroInfo {
+///
+/// `offset` is where the content starts in the SFC, so the spans of type
+/// arguments are SFC offsets, for editor features like hover and go to type.
+pub fn extract_macros(content: &str, offset: u32) -> MacroInfo {
     let mut info = MacroInfo::default();
 
     // Simple regex-based extraction (a full implementation would use AST parsing)
     // defineProps
-    if let Some(props) = extract_define_props(content) {
+    if let Some(props) = extract_define_props(content, offset) {
         info.define_props = Some(props);
     }
 
     // defineEmits
-    if let Some(emits) = extract_define_emits(content) {
+    if let Some(emits) = extract_define_emits(content, offset) {
         info.define_emits = Some(emits);
     }
 
     // defineSlots
-    if let Some(slots) = extract_define_slots(content) {
+    if let Some(slots) = extract_define_slots(content, offset) {
         info.define_slots = Some(slots);
     }
 
     // defineModel
-    info.define_models = extract_define_models(content);
+    info.define_models = extract_define_models(content, offset);
 
     // defineExpose
     if let Some(expose) = extract_define_expose(content) {
         info.define_expose = Some(expose);
     }
 
+    // defineOptions
+    info.define_options = extract_define_options(content);
+
     info
 }
 
-fn extract_define_props(content: &str) -> Option<DefinePropsInfo> {
+/// Get the text and SFC span of a macro's type argument.
+fn type_arg(m: Option<regex::Match<'_>>, offset: u32) -> (Option<String>, Option<Span>) {
+    match m {
+        Some(m) => (
+            Some(m.as_str().to_string()),
+            Some(Span::new(
+                offset + m.start() as u32,
+                offset + m.end() as u32,
+            )),
+        ),
+        None => (None, None),
+    }
+}
+
+fn extract_define_props(content: &str, offset: u32) -> Option<DefinePropsInfo> {
     // Match: defineProps<Type>() or const { ... } = defineProps<Type>()
     let patterns = [
         r"defineProps\s*<([^>]+)>\s*\(\s*\)",
@@ -344,55 +660,191 @@ fn extract_define_props(content: &str) -> Option<DefinePropsInfo> {
     for pattern in &patterns {
         if let Ok(re) = regex::Regex::new(pattern) {
             if let Some(caps) = re.captures(content) {
+                let (type_arg, type_arg_span) = type_arg(caps.get(1), offset);
                 return Some(DefinePropsInfo {
-                    type_arg: caps.get(1).map(|m| m.as_str().to_string()),
+                    type_arg,
+                    type_arg_span,
                     destructure_pattern: None,
+                    names: Vec::new(),
                 });
             }
         }
     }
 
+    // Match: defineProps(['title', 'count'])
+    if let Ok(re) = regex::Regex::new(r"defineProps\s*\(\s*\[([^\]]*)\]\s*\)") {
+        if let Some(caps) = re.captures(content) {
+            let names = regex::Regex::new(r#"['"]([^'"]+)['"]"#)
+                .map(|name| {
+                    name.captures_iter(&caps[1])
+                        .map(|c| c[1].to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            return Some(DefinePropsInfo {
+                type_arg: None,
+                type_arg_span: None,
+                destructure_pattern: None,
+                names,
+            });
+        }
+    }
+
     // Simple check for presence
     if content.contains("defineProps") {
         return Some(DefinePropsInfo {
             type_arg: None,
+            type_arg_span: None,
             destructure_pattern: None,
+            names: Vec::new(),
         });
     }
 
     None
 }
 
-fn extract_define_emits(content: &str) -> Option<DefineEmitsInfo> {
-    if content.contains("defineEmits") {
-        // Try to extract type argument
-        if let Ok(re) = regex::Regex::new(r"defineEmits\s*<([^>]+)>") {
-            if let Some(caps) = re.captures(content) {
-                return Some(DefineEmitsInfo {
-                    type_arg: caps.get(1).map(|m| m.as_str().to_string()),
-                });
+fn extract_define_emits(content: &str, offset: u32) -> Option<DefineEmitsInfo> {
+    if !content.contains("defineEmits") {
+        return None;
+    }
+    let mut info = DefineEmitsInfo {
+        type_arg: None,
+        type_arg_span: None,
+        names: Vec::new(),
+        validators: None,
+    };
+
+    // Try to extract type argument
+    if let Ok(re) = regex::Regex::new(r"defineEmits\s*<([^>]+)>") {
+        if let Some(caps) = re.captures(content) {
+            (info.type_arg, info.type_arg_span) = type_arg(caps.get(1), offset);
+            return Some(info);
+        }
+    }
+
+    // Match: defineEmits(['save', 'cancel'])
+    if let Ok(re) = regex::Regex::new(r"defineEmits\s*\(\s*\[([^\]]*)\]\s*\)") {
+        if let Some(caps) = re.captures(content) {
+            info.names = regex::Regex::new(r#"['"]([^'"]+)['"]"#)
+                .map(|name| {
+                    name.captures_iter(&caps[1])
+                        .map(|c| c[1].to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            return Some(info);
+        }
+    }
+
+    // Match: defineEmits({ save: (id: number) => true })
+    if let Some(m) = regex::Regex::new(r"defineEmits\s*\(\s*\{")
+        .ok()
+        .and_then(|re| re.find(content))
+    {
+        if let Some(object) = balanced_braces(&content[m.end() - 1..]) {
+            info.names = object_keys(&object[1..object.len() - 1]);
+            info.validators = Some(object.to_string());
+        }
+    }
+
+    Some(info)
+}
+
+/// Get the `{ ... }` block at the start of `s`, including its braces.
+///
+/// Braces in strings are skipped.
+fn balanced_braces(s: &str) -> Option<&str> {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '\'' | '"' | '`' => quote = Some(c),
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&s[..=i]);
+                }
             }
+            _ => {}
         }
-        return Some(DefineEmitsInfo { type_arg: None });
     }
     None
 }
 
-fn extract_define_slots(content: &str) -> Option<DefineSlotsInfo> {
+/// Get the top-level keys of an object literal body, like `save` and
+/// `'update:title'` in `save: (id) => true, 'update:title'(value) { ... }`.
+fn object_keys(body: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut depth = 0;
+    let mut entry_start = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in body.char_indices().chain([(body.len(), ',')]) {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '\'' | '"' | '`' => quote = Some(c),
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                let entry = body[entry_start..i].trim_start();
+                let key = match entry.chars().next() {
+                    Some(q @ ('\'' | '"')) => entry[1..].split(q).next(),
+                    _ => entry
+                        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                        .next(),
+                };
+                if let Some(key) = key.filter(|k| !k.is_empty()) {
+                    keys.push(key.to_string());
+                }
+                entry_start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    keys
+}
+
+fn extract_define_slots(content: &str, offset: u32) -> Option<DefineSlotsInfo> {
     if content.contains("defineSlots") {
         if let Ok(re) = regex::Regex::new(r"defineSlots\s*<([^>]+)>") {
             if let Some(caps) = re.captures(content) {
+                let (type_arg, type_arg_span) = type_arg(caps.get(1), offset);
                 return Some(DefineSlotsInfo {
-                    type_arg: caps.get(1).map(|m| m.as_str().to_string()),
+                    type_arg,
+                    type_arg_span,
                 });
             }
         }
-        return Some(DefineSlotsInfo { type_arg: None });
+        return Some(DefineSlotsInfo {
+            type_arg: None,
+            type_arg_span: None,
+        });
     }
     None
 }
 
-fn extract_define_models(content: &str) -> Vec<DefineModelInfo> {
+fn extract_define_models(content: &str, offset: u32) -> Vec<DefineModelInfo> {
     let mut models = Vec::new();
 
     if let Ok(re) = regex::Regex::new(r#"defineModel\s*(?:<([^>]+)>)?\s*\(\s*['"]?(\w*)['"]?"#) {
@@ -403,8 +855,12 @@ fn extract_define_models(content: &str) -> Vec<DefineModelInfo> {
                 .filter(|s| !s.is_empty())
                 .unwrap_or("modelValue")
                 .to_string();
-            let type_arg = caps.get(1).map(|m| m.as_str().to_string());
-            models.push(DefineModelInfo { name, type_arg });
+            let (type_arg, type_arg_span) = type_arg(caps.get(1), offset);
+            models.push(DefineModelInfo {
+                name,
+                type_arg,
+                type_arg_span,
+            });
         }
     }
 
@@ -425,6 +881,19 @@ fn extract_define_expose(content: &str) -> Option<DefineExposeInfo> {
     None
 }
 
+fn extract_define_options(content: &str) -> Option<DefineOptionsInfo> {
+    if !content.contains("defineOptions") {
+        return None;
+    }
+    let inherit_attrs =
+        regex::Regex::new(r"defineOptions\s*\(\s*\{[^}]*inheritAttrs\s*:\s*(true|false)")
+            .ok()
+            .and_then(|re| re.captures(content))
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str() == "true");
+    Some(DefineOptionsInfo { inherit_attrs })
+}
+
 /// Information about macros in script setup.
 #[derive(Debug, Clone, Default)]
 pub struct MacroInfo {
@@ -433,29 +902,45 @@ pub struct MacroInfo {
     pub define_slots: Option<DefineSlotsInfo>,
     pub define_models: Vec<DefineModelInfo>,
     pub define_expose: Option<DefineExposeInfo>,
+    pub define_options: Option<DefineOptionsInfo>,
     pub exposed: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct DefinePropsInfo {
     pub type_arg: Option<String>,
+    /// The span of the type argument in the SFC.
+    pub type_arg_span: Option<Span>,
     pub destructure_pattern: Option<String>,
+    /// Prop names declared with the array form, `defineProps(['title'])`.
+    pub names: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct DefineEmitsInfo {
     pub type_arg: Option<String>,
+    /// The span of the type argument in the SFC.
+    pub type_arg_span: Option<Span>,
+    /// Event names, from the array form `defineEmits(['save'])` or the keys
+    /// of the object form.
+    pub names: Vec<String>,
+    /// The object of the object form, `defineEmits({ save: (id) => true })`.
+    pub validators: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct DefineSlotsInfo {
     pub type_arg: Option<String>,
+    /// The span of the type argument in the SFC.
+    pub type_arg_span: Option<Span>,
 }
 
 #[derive(Debug, Clone)]
 pub struct DefineModelInfo {
     pub name: String,
     pub type_arg: Option<String>,
+    /// The span of the type argument in the SFC.
+    pub type_arg_span: Option<Span>,
 }
 
 #[derive(Debug, Clone)]
@@ -463,6 +948,11 @@ pub struct DefineExposeInfo {
     pub expression: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct DefineOptionsInfo {
+    pub inherit_attrs: Option<bool>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -484,6 +974,233 @@ const msg = ref('Hello')
         assert!(result.code.contains("__VLS_setup"));
     }
 
+    #[test]
+    fn test_top_level_await_makes_setup_async() {
+        let source = r#"<script setup lang="ts">
+const data = await fetchData()
+</script>
+
+<template>
+  <div>{{ data }}</div>
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        assert!(result.code.contains("async function __VLS_setup() {"));
+        assert!(result.code.contains("// async setup"));
+
+        // The awaited expression is kept as written, so tsc infers its type
+        let generated = result.code.find("const data = await fetchData()").unwrap() as u32;
+        let original = source.find("const data = await fetchData()").unwrap() as u32;
+        assert_eq!(
+            result.source_map.to_source_offset(generated + 13),
+            Some(original + 13)
+        );
+
+        let sfc = parse_sfc("<script setup>\nonMounted(async () => { await load() })\n</script>")
+            .unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        assert!(!result.code.contains("async function __VLS_setup"));
+    }
+
+    #[test]
+    fn test_keyboard_handler_on_click_is_checked_as_mouse_event() {
+        let source = r#"<script setup lang="ts">
+function onClick(event: KeyboardEvent) {}
+</script>
+
+<template>
+  <button @click="onClick">Go</button>
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        // `(event: KeyboardEvent) => void` is not assignable to
+        // `(event: MouseEvent) => unknown`, so tsc reports the handler
+        assert!(result
+            .code
+            .contains("declare function __VLS_eventHandler<E extends Event>("));
+        let call = "__VLS_eventHandler<MouseEvent>(onClick);";
+        let generated = result.code.find(call).unwrap() + call.find("onClick").unwrap();
+        let original = source.find("\"onClick\"").unwrap() + 1;
+        assert_eq!(
+            result.source_map.to_source_offset(generated as u32),
+            Some(original as u32)
+        );
+    }
+
+    #[test]
+    fn test_generate_is_deterministic() {
+        let source = r#"<script setup lang="ts">
+const items = ref([1, 2, 3])
+</script>
+
+<template>
+  <MyList>
+    <MyItem v-for="item in items" :key="item" :value="item" />
+  </MyList>
+  <MyFooter />
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let first = generate(&sfc, &CodegenOptions::default());
+        let second = generate(&parse_sfc(source).unwrap(), &CodegenOptions::default());
+        assert_eq!(first.code, second.code);
+
+        // Ids are absolute SFC offsets
+        let footer = source.find("<MyFooter").unwrap();
+        assert!(first
+            .code
+            .contains(&format!("const __VLS_component_{} = ", footer)));
+    }
+
+    #[test]
+    fn test_strict_templates_check_exact_props() {
+        let source = r#"<script setup lang="ts">
+const msg = ref('Hello')
+</script>
+
+<template>
+  <MyButton :label="msg" :foo="msg" :[dynamic]="msg" />
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let options = CodegenOptions {
+            strict: true,
+            ..Default::default()
+        };
+        let result = generate(&sfc, &options);
+        let component = format!("__VLS_component_{}", source.find("<MyButton").unwrap());
+        assert!(result.code.contains(&format!(
+            "__VLS_asStrictFunctionalComponent({})({{\n      'label': (msg),\n      'foo': (msg),\n    }});",
+            component
+        )));
+        // A dynamic name can't be checked against the props
+        assert!(result.code.contains("// prop: dynamic"));
+
+        // The unknown `:foo` is reported on the binding
+        let generated = result.code.find("'foo'").unwrap() as u32;
+        let original = source.find(":foo").unwrap() as u32;
+        let mapping = result.source_map.find_source(generated).unwrap();
+        assert_eq!(
+            mapping.source_span(),
+            source_map::Span::new(original, original + ":foo=\"msg\"".len() as u32)
+        );
+    }
+
+    #[test]
+    fn test_setup_bindings_are_read_directly() {
+        let source = r#"<script lang="ts">
+const shared = 1
+</script>
+
+<script setup lang="ts">
+import { ref } from 'vue'
+const msg = ref('Hello')
+const model = defineModel<string>()
+</script>
+
+<template>
+  <p>{{ msg }} {{ foo }} {{ shared }} {{ modelValue }} {{ $props }}</p>
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        assert!(result.code.contains("__VLS_toDisplayString(msg);"));
+        assert!(result.code.contains("__VLS_toDisplayString(shared);"));
+        assert!(result.code.contains("__VLS_toDisplayString(modelValue);"));
+        // Undeclared names and instance properties go through the context
+        assert!(result
+            .code
+            .contains("__VLS_toDisplayString(__VLS_ctx.foo);"));
+        assert!(result
+            .code
+            .contains("__VLS_toDisplayString(__VLS_ctx.$props);"));
+
+        // The template is generated inside setup, where its bindings live
+        let setup = result.code.find("function __VLS_setup() {").unwrap();
+        let template = result.code.find("function __VLS_template() {").unwrap();
+        let setup_return = result.code.find("\nreturn {").unwrap();
+        assert!(setup < template && template < setup_return);
+    }
+
+    #[test]
+    fn test_typed_directive_checks_its_value() {
+        let source = r#"<script setup lang="ts">
+import type { Directive } from 'vue'
+const vTooltip: Directive<HTMLElement, string> = {}
+const count = 1
+</script>
+
+<template>
+  <button v-tooltip="count" v-focus="count" v-once>Go</button>
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        assert!(result
+            .code
+            .contains("declare function __VLS_directiveValue<D>(directive: D, value: __VLS_DirectiveValue<D>): void;"));
+        // `V` is `string`, so tsc rejects the number bound to `v-tooltip`
+        let call = "__VLS_directiveValue(vTooltip, (count));";
+        assert!(result
+            .code
+            .contains(&format!("// directive: v-tooltip\n    {}", call)));
+        let generated = result.code.find(call).unwrap() + call.find("count").unwrap();
+        let original = source.find("\"count\"").unwrap() + 1;
+        assert_eq!(
+            result.source_map.to_source_offset(generated as u32),
+            Some(original as u32)
+        );
+
+        // Undeclared directives resolve by name, untyped; built-ins are skipped
+        assert!(result
+            .code
+            .contains("__VLS_directiveValue(__VLS_resolveDirective('focus'), (count));"));
+        assert!(!result.code.contains("v-once"));
+    }
+
+    #[test]
+    fn test_non_strict_templates_allow_unknown_props() {
+        let source = r#"<script setup lang="ts">
+const msg = ref('Hello')
+</script>
+
+<template>
+  <MyButton :foo="msg" />
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        assert!(!result
+            .code
+            .contains("__VLS_asStrictFunctionalComponent(__VLS_component"));
+        assert!(result.code.contains("// prop: foo\n    (msg);"));
+    }
+
+    #[test]
+    fn test_skip_template() {
+        let source = r#"<script setup lang="ts">
+const msg = ref('Hello')
+</script>
+
+<template>
+  <div>{{ msg }}</div>
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let options = CodegenOptions {
+            skip_template: true,
+            ..Default::default()
+        };
+        let result = generate(&sfc, &options);
+        assert!(!result.code.contains("function __VLS_template()"));
+        assert!(result.code.contains("const msg = ref('Hello')"));
+
+        let result = generate(&sfc, &CodegenOptions::default());
+        assert!(result.code.contains("function __VLS_template()"));
+    }
+
     #[test]
     fn test_generate_with_props() {
         let source = r#"<script setup lang="ts">
@@ -495,6 +1212,183 @@ defineProps<{ message: string }>()
         assert!(result.code.contains("defineProps"));
     }
 
+    #[test]
+    fn test_define_props_array() {
+        let props =
+            extract_define_props("const props = defineProps(['title', \"count\"])", 0).unwrap();
+        assert_eq!(props.names, ["title", "count"]);
+        assert!(props.type_arg.is_none());
+
+        let source = r#"<script setup>
+defineProps(['title'])
+</script>
+
+<template>
+  <h1>{{ $props.title }}</h1>
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        // `title` is a known prop of `$props`
+        assert!(result
+            .code
+            .contains("const __VLS_props = defineProps(['title']);"));
+        assert!(result.code.contains("$props: typeof __VLS_props;"));
+    }
+
+    #[test]
+    fn test_define_emits_array() {
+        let emits =
+            extract_define_emits("const emit = defineEmits(['save', \"cancel\"])", 0).unwrap();
+        assert_eq!(emits.names, ["save", "cancel"]);
+        assert!(emits.type_arg.is_none() && emits.validators.is_none());
+
+        let source = r#"<script setup>
+const emit = defineEmits(['save', 'cancel'])
+</script>
+
+<template>
+  <button @click="$emit('save')">Save</button>
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        // `$emit` only takes the declared events
+        assert!(result
+            .code
+            .contains("const __VLS_emit = defineEmits(['save', 'cancel']);"));
+        assert!(result.code.contains("$emit: typeof __VLS_emit;"));
+    }
+
+    #[test]
+    fn test_define_emits_object_validators() {
+        let content = r#"const emit = defineEmits({
+  save: (payload: { id: number }) => payload.id > 0,
+  'update:title'(value: string) { return value !== '}' },
+  cancel: null,
+})"#;
+        let emits = extract_define_emits(content, 0).unwrap();
+        assert_eq!(emits.names, ["save", "update:title", "cancel"]);
+        let validators = emits.validators.unwrap();
+        assert!(validators.starts_with("{\n  save:"));
+        assert!(validators.ends_with("cancel: null,\n}"));
+
+        let source = format!("<script setup lang=\"ts\">\n{}\n</script>\n", content);
+        let sfc = parse_sfc(&source).unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        assert!(result
+            .code
+            .contains("const __VLS_emit = defineEmits({\n  save: (payload"));
+    }
+
+    #[test]
+    fn test_attrs_exclude_declared_props() {
+        let source = r#"<script setup lang="ts">
+defineProps<{ title: string }>()
+</script>
+
+<template>
+  <div>{{ $attrs.id }}</div>
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        assert!(result
+            .code
+            .contains("$attrs: __VLS_FallthroughAttrs<typeof __VLS_props>;"));
+        assert!(!result.code.contains("inheritAttrs"));
+    }
+
+    #[test]
+    fn test_define_options_inherit_attrs_false() {
+        let source = r#"<script setup lang="ts">
+defineOptions({ inheritAttrs: false })
+</script>
+
+<template>
+  <div v-bind="$attrs" />
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        assert!(result.code.contains("inheritAttrs: false,"));
+        assert!(result.code.contains("$attrs: Record<string, unknown>;"));
+    }
+
+    #[test]
+    fn test_template_mappings_use_sfc_offsets() {
+        let source = r#"<script setup lang="ts">
+const count = ref(0)
+</script>
+
+<template>
+  <div>{{ count }}</div>
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+
+        let template_start = sfc.template.as_ref().unwrap().content_span.start;
+        let mapping = result
+            .source_map
+            .mappings()
+            .iter()
+            .find(|m| m.source_offset >= template_start)
+            .unwrap();
+        let mapped = &source[mapping.source_offset as usize..];
+        assert!(mapped.trim_start().starts_with("count }}"));
+    }
+
+    fn base64_decode(encoded: &str) -> Vec<u8> {
+        const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let digits: Vec<u32> = encoded
+            .bytes()
+            .filter(|&b| b != b'=')
+            .map(|b| CHARS.iter().position(|&c| c == b).unwrap() as u32)
+            .collect();
+        let mut bytes = Vec::new();
+        for chunk in digits.chunks(4) {
+            let n = chunk
+                .iter()
+                .enumerate()
+                .fold(0, |n, (i, d)| n | d << (18 - 6 * i));
+            bytes.extend(&n.to_be_bytes()[1..chunk.len()]);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_inline_source_map() {
+        let source = r#"<script setup lang="ts">
+const count = ref(0)
+</script>
+
+<template>
+  <div>{{ count }}</div>
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let options = CodegenOptions {
+            filename: Some("App.vue".to_string()),
+            ..Default::default()
+        };
+        let result = generate(&sfc, &options);
+
+        let code = result.with_inline_source_map();
+        assert!(code.starts_with(&result.code));
+        let comment = code.lines().last().unwrap();
+        let encoded = comment
+            .strip_prefix("//# sourceMappingURL=data:application/json;base64,")
+            .unwrap();
+        let map = String::from_utf8(base64_decode(encoded)).unwrap();
+        assert_eq!(
+            map,
+            result.source_map.to_v3(&result.code, source, "App.vue")
+        );
+        assert!(map.starts_with(r#"{"version":3,"sources":["App.vue"],"#));
+        assert!(!map.ends_with(r#""mappings":""}"#));
+    }
+
     #[test]
     fn test_detect_typescript() {
         let source = r#"<script setup lang="ts">
@@ -505,4 +1399,370 @@ const x = 1
         let result = generate(&sfc, &CodegenOptions::default());
         assert_eq!(result.lang, ScriptLang::Ts);
     }
+
+    #[test]
+    fn test_reparse_template_block() {
+        let source = "<script setup lang=\"ts\">\nconst a = 1\n</script>\n<template><div>{{ a }}</div></template>\n";
+        let mut sfc = parse_sfc(source).unwrap();
+        let script_before = sfc.script_setup.as_ref().unwrap().content.clone();
+
+        let parse = reparse_block(
+            &mut sfc,
+            BlockKind::Template,
+            "<span>{{ a + 1 }}</span>",
+            &CodegenOptions::default(),
+        )
+        .unwrap();
+
+        let BlockParse::Template(ast) = parse else {
+            panic!("expected a template parse");
+        };
+        assert_eq!(ast.children.len(), 1);
+        assert_eq!(sfc.script_setup.as_ref().unwrap().content, script_before);
+    }
+
+    #[test]
+    fn test_reparse_script_setup_extracts_macros() {
+        let source =
+            "<script setup lang=\"ts\">\nconst a = 1\n</script>\n<template><div /></template>\n";
+        let mut sfc = parse_sfc(source).unwrap();
+
+        let parse = reparse_block(
+            &mut sfc,
+            BlockKind::ScriptSetup,
+            "\nconst props = defineProps<{ msg: string }>()\n",
+            &CodegenOptions::default(),
+        )
+        .unwrap();
+
+        let BlockParse::ScriptSetup(macros) = parse else {
+            panic!("expected script setup macros");
+        };
+        assert!(macros.define_props.is_some());
+        assert!(sfc.template.is_some());
+    }
+
+    #[test]
+    fn test_reparse_template_error_span_is_sfc_relative() {
+        let source = "<template><div /></template>\n";
+        let mut sfc = parse_sfc(source).unwrap();
+
+        let err = reparse_block(
+            &mut sfc,
+            BlockKind::Template,
+            "<li v-for=\"items\"></li>",
+            &CodegenOptions::default(),
+        )
+        .unwrap_err();
+
+        let content_span = sfc.template.as_ref().unwrap().content_span;
+        assert!(err.span.start >= content_span.start);
+        assert!(err.span.end <= content_span.end);
+        assert!(sfc.content[err.span.to_range()].contains("items"));
+        assert_eq!(err.message, "Invalid v-for expression");
+    }
+
+    #[test]
+    fn test_deepest_template_fits_default_stack() {
+        // The template element is one level, each component with its slot two
+        let depth = (vue_template_compiler::DEFAULT_MAX_DEPTH - 1) / 2;
+        let source = format!(
+            "<template>{}{{{{ x }}}}{}</template>\n",
+            r#"<MyList v-for="x in items" :key="x"><template #item="{ row }">"#.repeat(depth),
+            "</template></MyList>".repeat(depth),
+        );
+        let sfc = parse_sfc(&source).unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    fn test_malformed_template_reports_codegen_errors() {
+        let source = r#"<script setup lang="ts">
+const items = [1]
+</script>
+
+<template>
+  <p v-if></p>
+  <MyList v-slot="{ row "></MyList>
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+
+        let messages: Vec<_> = result.errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "v-if needs a condition",
+                "Slot props `{ row` are not a valid destructuring pattern",
+            ]
+        );
+        // Spans are in the SFC
+        assert_eq!(&source[result.errors[0].span.to_range()], "v-if");
+        assert_eq!(&source[result.errors[1].span.to_range()], "{ row ");
+
+        // The virtual file stays well-formed
+        assert!(!result.code.contains("if ()"));
+        assert!(!result.code.contains("const { row"));
+    }
+
+    #[test]
+    fn test_js_component_needs_check_js() {
+        let sfc = vue_parser::parse("<script setup>\nconst n = 1\n</script>\n").unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        assert_eq!(result.lang, ScriptLang::Js);
+        assert_eq!(result.lang.virtual_extension(), "ts");
+        assert!(result.code.starts_with("// @ts-nocheck\n"));
+
+        let options = CodegenOptions {
+            check_js: true,
+            ..Default::default()
+        };
+        let result = generate(&sfc, &options);
+        assert!(!result.code.contains("@ts-nocheck"));
+
+        // TypeScript components are always checked
+        let sfc =
+            vue_parser::parse("<script setup lang=\"ts\">\nconst n = 1\n</script>\n").unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        assert!(!result.code.contains("@ts-nocheck"));
+    }
+
+    #[test]
+    fn test_generic_component_is_a_generic_function() {
+        let source = r#"<script setup lang="ts" generic="T extends string | number">
+defineProps<{ items: T[]; selected?: T }>()
+</script>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        assert!(result.code.contains(
+            "export default {} as unknown as <T extends string | number>(\n  props: { items: T[]; selected?: T } & __VLS_ReservedProps,\n) => any;"
+        ));
+        assert!(!result.code.contains("__VLS_defineComponent({\n"));
+
+        // Components without type parameters are still component objects
+        let sfc =
+            parse_sfc("<script setup lang=\"ts\">\ndefineProps<{ a: string }>()\n</script>\n")
+                .unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        assert!(result
+            .code
+            .contains("export default __VLS_defineComponent({"));
+    }
+
+    #[test]
+    fn test_generic_component_usage_infers_type_params() {
+        let source = r#"<script setup lang="ts">
+const strings = ['a', 'b']
+</script>
+
+<template>
+  <MyList :items="strings" />
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let options = CodegenOptions {
+            strict: true,
+            ..Default::default()
+        };
+        let result = generate(&sfc, &options);
+        // The props are passed in one call, from which tsc infers `T`
+        let component = format!("__VLS_component_{}", source.find("<MyList").unwrap());
+        assert!(result.code.contains(&format!(
+            "__VLS_asStrictFunctionalComponent({})({{\n      'items': (strings),\n    }});",
+            component
+        )));
+
+        // Function components go through an overload that keeps their type
+        // parameters, rather than a conditional type that would lose them
+        let helpers = crate::helpers::VLS_HELPER_TYPES;
+        let overload = helpers
+            .find("declare function __VLS_asStrictFunctionalComponent<P>(\n    t: (props: P, ...args: any[]) => any,\n): (props: Partial<P> & __VLS_ReservedProps) => void;")
+            .unwrap();
+        let conditional = helpers
+            .find("declare function __VLS_asStrictFunctionalComponent<T>(")
+            .unwrap();
+        assert!(overload < conditional);
+    }
+
+    #[test]
+    fn test_verbatim_module_syntax_imports_types_with_import_type() {
+        let source = "<script setup lang=\"ts\">\nconst n = 1\n</script>\n";
+        let sfc = parse_sfc(source).unwrap();
+
+        let result = generate(&sfc, &CodegenOptions::default());
+        assert!(result.code.starts_with("import { defineComponent as __VLS_defineComponent, ref as __VLS_ref, computed as __VLS_computed, reactive as __VLS_reactive, PropType as __VLS_PropType, "));
+
+        let options = CodegenOptions {
+            verbatim_module_syntax: true,
+            ..Default::default()
+        };
+        let result = generate(&sfc, &options);
+        let value_import = result
+            .code
+            .lines()
+            .find(|line| line.starts_with("import {"))
+            .unwrap();
+        assert!(!value_import.contains("PropType"));
+        assert!(!value_import.contains("ExtractPropTypes"));
+        assert!(!value_import.contains("ComponentPublicInstance"));
+        assert!(result.code.contains("import type { PropType as __VLS_PropType, ExtractPropTypes as __VLS_ExtractPropTypes, ComponentPublicInstance as __VLS_ComponentPublicInstance } from 'vue';\n"));
+    }
+
+    #[test]
+    fn test_macro_type_arg_spans() {
+        let source = r#"<script setup lang="ts">
+interface Props { title: string }
+const props = defineProps<Props>()
+const emit = defineEmits<{ save: [] }>()
+const model = defineModel<string>('title')
+</script>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let script_setup = sfc.script_setup.as_ref().unwrap();
+        let macros = extract_macros(&script_setup.content, script_setup.content_span.start);
+        let text = |span: Option<Span>| {
+            let span = span.unwrap();
+            &source[span.start as usize..span.end as usize]
+        };
+
+        let props = macros.define_props.unwrap();
+        assert_eq!(text(props.type_arg_span), "Props");
+        assert_eq!(
+            props.type_arg_span.unwrap().start as usize,
+            source.find("Props>").unwrap()
+        );
+        assert_eq!(
+            text(macros.define_emits.unwrap().type_arg_span),
+            "{ save: [] }"
+        );
+        assert_eq!(text(macros.define_models[0].type_arg_span), "string");
+    }
+
+    #[test]
+    fn test_reparsed_macros_have_sfc_spans() {
+        let source =
+            "<template><div /></template>\n<script setup lang=\"ts\">\ndefineProps()\n</script>\n";
+        let mut sfc = parse_sfc(source).unwrap();
+        let parse = reparse_block(
+            &mut sfc,
+            BlockKind::ScriptSetup,
+            "\ndefineProps<{ a: number }>()\n",
+            &CodegenOptions::default(),
+        )
+        .unwrap();
+        let BlockParse::ScriptSetup(macros) = parse else {
+            panic!("expected script setup macros");
+        };
+        let span = macros.define_props.unwrap().type_arg_span.unwrap();
+        assert_eq!(
+            &sfc.content[span.start as usize..span.end as usize],
+            "{ a: number }"
+        );
+    }
+
+    #[test]
+    fn test_reserved_names_are_reported() {
+        let source = r#"<script setup lang="ts">
+const __VLS_props = 1
+</script>
+
+<template>
+  <li v-for="__VLS_item in items">{{ __VLS_item }}</li>
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        let messages: Vec<_> = result.errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "'__VLS_props' uses the __VLS_ prefix reserved for generated code; rename it",
+                "'__VLS_item' uses the __VLS_ prefix reserved for generated code; rename it",
+            ]
+        );
+        let declaration = source.find("__VLS_props").unwrap();
+        assert_eq!(
+            result.errors[0].span.to_range(),
+            declaration..declaration + "__VLS_props".len()
+        );
+        assert_eq!(&source[result.errors[1].span.to_range()], "__VLS_item");
+    }
+
+    #[test]
+    fn test_invalid_v_for_alias_is_left_out() {
+        let source = r#"<template>
+  <li v-for="({ id /* x */ }, index) in items" :key="index">{{ index }}</li>
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0]
+            .message
+            .starts_with("v-for alias `{ id /* x */ }` is not a valid destructuring pattern"));
+
+        // The alias becomes a hole, so the loop still parses
+        assert!(result.code.contains("for (const [, index] of "));
+        assert!(!result.code.contains("/* x */"));
+    }
+
+    #[test]
+    fn test_module_level_declarations_are_hoisted() {
+        let source = r#"<script setup lang="ts">
+import { ref } from 'vue'
+enum Status { Idle, Busy }
+export function label(status: Status) {
+  return Status[status]
+}
+declare const __APP_VERSION__: string
+const status = ref(Status.Idle)
+</script>
+
+<template>
+  <p>{{ label(status) }} {{ __APP_VERSION__ }}</p>
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        assert!(result.errors.is_empty());
+
+        let code = &result.code;
+        let setup = code.find("function __VLS_setup() {").unwrap();
+        for declaration in [
+            "import { ref } from 'vue'",
+            "enum Status { Idle, Busy }",
+            "export function label(status: Status) {",
+            "declare const __APP_VERSION__: string",
+        ] {
+            let at = code.find(declaration).unwrap();
+            assert!(at < setup, "{declaration} is inside setup");
+        }
+        // Executable code stays in setup, and everything keeps its mapping
+        let status = code.find("const status = ref(Status.Idle)").unwrap();
+        assert!(status > setup);
+        let enum_at = code.find("enum Status").unwrap() as u32;
+        let original = source.find("enum Status").unwrap() as u32;
+        assert_eq!(
+            result.source_map.map_span(Span::new(enum_at, enum_at + 4)),
+            Some(Span::new(original, original + 4))
+        );
+    }
+
+    #[test]
+    fn test_generic_component_keeps_types_in_setup() {
+        let source = r#"<script setup lang="ts" generic="T">
+import { ref } from 'vue'
+type Items = T[]
+const items = ref<Items>([])
+</script>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let result = generate(&sfc, &CodegenOptions::default());
+        let setup = result.code.find("function __VLS_setup<T>() {").unwrap();
+        assert!(result.code.find("import { ref } from 'vue'").unwrap() < setup);
+        assert!(result.code.find("type Items = T[]").unwrap() > setup);
+    }
 }
@@ -68,6 +68,441 @@ pub fn analyze_script(content: &str) -> ScriptRanges {
     ranges
 }
 
+/// A script token, as far as brace matching needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Ident(&'a str),
+    Punct(char),
+    Arrow,
+    Newline,
+}
+
+/// Tokenize script content, skipping strings, template literals and comments.
+///
+/// Non-ASCII characters are taken as identifier characters, so `größe` is a
+/// single identifier.
+fn tokenize(content: &str) -> Vec<Token<'_>> {
+    let bytes = content.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let is_ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || !b.is_ascii();
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        if is_ident(c) && !c.is_ascii_digit() {
+            let start = i;
+            while i < bytes.len() && is_ident(bytes[i]) {
+                i += 1;
+            }
+            tokens.push(Token::Ident(&content[start..i]));
+        } else if bytes[i..].starts_with(b"//") {
+            i = content[i..].find('\n').map_or(bytes.len(), |n| i + n);
+        } else if bytes[i..].starts_with(b"/*") {
+            i = content[i + 2..]
+                .find("*/")
+                .map_or(bytes.len(), |n| i + n + 4);
+        } else if matches!(c, b'"' | b'\'' | b'`') {
+            i += 1;
+            while i < bytes.len() && bytes[i] != c {
+                i += if bytes[i] == b'\\' { 2 } else { 1 };
+            }
+            i += 1;
+        } else if bytes[i..].starts_with(b"=>") {
+            tokens.push(Token::Arrow);
+            i += 2;
+        } else if c == b'\n' {
+            tokens.push(Token::Newline);
+            i += 1;
+        } else {
+            if c.is_ascii_punctuation() {
+                tokens.push(Token::Punct(c as char));
+            }
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+/// Collect the names script content declares at its top level.
+///
+/// Covers variable, function, class and enum declarations and value
+/// imports; type-only imports are left out, as templates can't use them.
+/// Names are in declaration order, without duplicates.
+pub fn top_level_bindings(content: &str) -> Vec<String> {
+    let tokens = tokenize(content);
+    let mut bindings: Vec<String> = Vec::new();
+    let mut add = |name: &str| {
+        if !bindings.iter().any(|b| b == name) {
+            bindings.push(name.to_string());
+        }
+    };
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        match tokens[i] {
+            Token::Punct('{' | '(' | '[') => depth += 1,
+            Token::Punct('}' | ')' | ']') => depth = depth.saturating_sub(1),
+            Token::Ident("const" | "let" | "var") if depth == 0 => {
+                i = declared_names(&tokens, i + 1, &mut add);
+                continue;
+            }
+            Token::Ident("function" | "class" | "enum") if depth == 0 => {
+                let name = match tokens.get(i + 1) {
+                    Some(Token::Punct('*')) => tokens.get(i + 2),
+                    name => name,
+                };
+                if let Some(Token::Ident(name)) = name {
+                    add(name);
+                }
+            }
+            Token::Ident("import") if depth == 0 => {
+                i = imported_names(&tokens, i + 1, &mut add);
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    bindings
+}
+
+/// Collect the names of a declaration pattern, returning the index after it.
+fn declared_names(tokens: &[Token<'_>], start: usize, add: &mut impl FnMut(&str)) -> usize {
+    match tokens.get(start) {
+        Some(Token::Ident(name)) => {
+            add(name);
+            start + 1
+        }
+        Some(Token::Punct('{' | '[')) => {
+            let mut depth = 0;
+            let mut in_default = false;
+            let mut i = start;
+            while let Some(token) = tokens.get(i) {
+                match token {
+                    Token::Punct('{' | '[') => depth += 1,
+                    Token::Punct('}' | ']') => {
+                        depth -= 1;
+                        in_default = false;
+                        if depth == 0 {
+                            return i + 1;
+                        }
+                    }
+                    Token::Punct(',') => in_default = false,
+                    Token::Punct('=') => in_default = true,
+                    // `{ key: alias }` binds the alias, not the key
+                    Token::Ident(name)
+                        if !in_default && tokens.get(i + 1) != Some(&Token::Punct(':')) =>
+                    {
+                        add(name);
+                    }
+                    _ => {}
+                }
+                i += 1;
+            }
+            i
+        }
+        _ => start,
+    }
+}
+
+/// Collect the value names of an import clause, returning the index after it.
+fn imported_names(tokens: &[Token<'_>], start: usize, add: &mut impl FnMut(&str)) -> usize {
+    // `import(...)` is an expression, and `import type` imports only types
+    match (tokens.get(start), tokens.get(start + 1)) {
+        (Some(Token::Punct('(')), _) => return start,
+        (Some(Token::Ident("type")), Some(Token::Ident(_) | Token::Punct('{' | '*'))) => {
+            return start + 1;
+        }
+        _ => {}
+    }
+
+    let mut in_braces = false;
+    let mut i = start;
+    while let Some(&token) = tokens.get(i) {
+        match token {
+            Token::Ident("from") | Token::Punct(';') => return i + 1,
+            Token::Newline if !in_braces => return i + 1,
+            Token::Punct('{') => in_braces = true,
+            Token::Punct('}') => in_braces = false,
+            // `type Name` inside braces imports only a type
+            Token::Ident("type") if matches!(tokens.get(i + 1), Some(Token::Ident(_))) => {
+                i += 2;
+                continue;
+            }
+            // `name as alias` binds the alias
+            Token::Ident(name)
+                if name != "as" && tokens.get(i + 1) != Some(&Token::Ident("as")) =>
+            {
+                add(name);
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    i
+}
+
+/// An open bracket while scanning for top-level `await`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frame<'a> {
+    /// `(`, with the token before it (`if` in `if (`).
+    Paren(Option<Token<'a>>),
+    /// `[` or a non-function `{`.
+    Block,
+    /// A function body.
+    Function,
+    /// The expression body of an arrow function without braces, with the
+    /// index of its `=>`.
+    ArrowBody(usize),
+}
+
+/// Check if script content uses `await` outside of any function.
+///
+/// Script setup with a top-level `await` compiles to an async `setup()`.
+pub fn has_top_level_await(content: &str) -> bool {
+    const CONTROL_KEYWORDS: &[&str] = &["if", "for", "while", "switch", "catch", "with"];
+
+    let tokens = tokenize(content);
+    let mut stack: Vec<Frame> = Vec::new();
+    // The last token that isn't a newline, and its index
+    let mut prev: Option<(usize, Token)> = None;
+    // The token before the `(` that the last `)` closed
+    let mut closed_paren: Option<Option<Token>> = None;
+
+    for (i, &token) in tokens.iter().enumerate() {
+        let prev_token = prev.map(|(_, t)| t);
+        match token {
+            Token::Ident("await")
+                if !stack
+                    .iter()
+                    .any(|f| matches!(f, Frame::Function | Frame::ArrowBody(_))) =>
+            {
+                return true;
+            }
+            Token::Arrow
+                if tokens[i + 1..].iter().find(|t| **t != Token::Newline)
+                    != Some(&Token::Punct('{')) =>
+            {
+                stack.push(Frame::ArrowBody(i));
+            }
+            // An arrow body ends at the end of its line, once it has started
+            Token::Newline => {
+                if let (Some(Frame::ArrowBody(arrow)), Some((last, _))) = (stack.last(), prev) {
+                    if last > *arrow {
+                        stack.pop();
+                    }
+                }
+                continue;
+            }
+            Token::Punct('(') => stack.push(Frame::Paren(prev_token)),
+            Token::Punct('[') => stack.push(Frame::Block),
+            Token::Punct('{') => {
+                let is_function = match prev_token {
+                    Some(Token::Arrow) => true,
+                    Some(Token::Punct(')')) => !matches!(
+                        closed_paren,
+                        Some(Some(Token::Ident(keyword))) if CONTROL_KEYWORDS.contains(&keyword)
+                    ),
+                    _ => false,
+                };
+                stack.push(if is_function {
+                    Frame::Function
+                } else {
+                    Frame::Block
+                });
+            }
+            Token::Punct(')' | ']' | '}') => {
+                // Closing a bracket also ends any arrow body inside it
+                while matches!(stack.last(), Some(Frame::ArrowBody(_))) {
+                    stack.pop();
+                }
+                if let Some(Frame::Paren(before)) = stack.pop() {
+                    closed_paren = Some(before);
+                }
+            }
+            Token::Punct(';' | ',') if matches!(stack.last(), Some(Frame::ArrowBody(_))) => {
+                stack.pop();
+            }
+            _ => {}
+        }
+        prev = Some((i, token));
+    }
+
+    false
+}
+
+/// Find the top-level statements of script setup that belong at module scope.
+///
+/// Setup content is generated inside a function, where imports, exports,
+/// enums and ambient declarations aren't allowed, so these are hoisted out
+/// of it. Interfaces and type aliases are hoisted with `hoist_types`, unless
+/// they use `typeof`, which may refer to setup variables; a generic
+/// component passes `false`, as its types may refer to the type parameters.
+///
+/// Returns byte ranges of the statements, in order.
+pub fn module_level_ranges(content: &str, hoist_types: bool) -> Vec<(usize, usize)> {
+    top_level_statements(content)
+        .into_iter()
+        .filter(|&(start, end)| is_module_level(&content[start..end], hoist_types))
+        .collect()
+}
+
+/// Check if a statement can only appear at module scope.
+fn is_module_level(statement: &str, hoist_types: bool) -> bool {
+    let starts_with_ident =
+        |text: &str| text.starts_with(|c: char| c.is_alphabetic() || c == '_' || c == '$');
+
+    // `import(...)` and `import.meta` are expressions
+    if let Some(rest) = strip_keyword(statement, "import") {
+        return !rest.starts_with(['(', '.']);
+    }
+    if strip_keyword(statement, "export").is_some()
+        || strip_keyword(statement, "enum").is_some()
+        || strip_keyword(statement, "const")
+            .is_some_and(|rest| strip_keyword(rest, "enum").is_some())
+        || strip_keyword(statement, "declare").is_some_and(starts_with_ident)
+    {
+        return true;
+    }
+
+    hoist_types
+        && !statement.contains("typeof")
+        && (strip_keyword(statement, "interface").is_some_and(starts_with_ident)
+            || strip_keyword(statement, "type").is_some_and(starts_with_ident))
+}
+
+/// Strip `keyword` and the whitespace after it from the start of `text`.
+fn strip_keyword<'a>(text: &'a str, keyword: &str) -> Option<&'a str> {
+    let rest = text.strip_prefix(keyword)?;
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    (!rest.starts_with(is_ident)).then(|| rest.trim_start())
+}
+
+/// Split script content into its top-level statements, as byte ranges.
+///
+/// A statement ends at a `;` or, as with automatic semicolon insertion, at
+/// a line break after a token that can end it, unless the next line
+/// continues it (`.then()`, `| 'b'`, `extends Base`).
+fn top_level_statements(content: &str) -> Vec<(usize, usize)> {
+    let bytes = content.as_bytes();
+    let mut statements = Vec::new();
+    let mut depth = 0usize;
+    // Start of the current statement, and the end of its last token
+    let mut start = None;
+    let mut end = 0;
+    let mut last = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        if bytes[i..].starts_with(b"//") || bytes[i..].starts_with(b"/*") {
+            i = skip_comment(bytes, i);
+            continue;
+        }
+        if c.is_ascii_whitespace() {
+            if c == b'\n' && depth == 0 && can_end_statement(last) && !continues_statement(bytes, i)
+            {
+                if let Some(start) = start.take() {
+                    statements.push((start, end));
+                }
+                last = None;
+            }
+            i += 1;
+            continue;
+        }
+
+        start.get_or_insert(i);
+        match c {
+            b'"' | b'\'' | b'`' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != c {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+            b';' if depth == 0 => {
+                if let Some(start) = start.take() {
+                    statements.push((start, i + 1));
+                }
+                last = None;
+                i += 1;
+                continue;
+            }
+            _ => {}
+        }
+        i = (i + 1).min(bytes.len());
+        end = i;
+        last = Some(c);
+    }
+    if let Some(start) = start {
+        statements.push((start, end));
+    }
+
+    statements
+}
+
+/// Get the index after a `//` or `/* */` comment starting at `i`.
+fn skip_comment(bytes: &[u8], i: usize) -> usize {
+    let (terminator, skip): (&[u8], usize) = if bytes[i + 1] == b'/' {
+        (b"\n", 0)
+    } else {
+        (b"*/", 2)
+    };
+    bytes[i + 2..]
+        .windows(terminator.len())
+        .position(|w| w == terminator)
+        .map_or(bytes.len(), |n| i + 2 + n + skip)
+}
+
+/// Check if a statement can end after a token ending with `last`.
+fn can_end_statement(last: Option<u8>) -> bool {
+    last.is_some_and(|c| {
+        c.is_ascii_alphanumeric()
+            || matches!(c, b'_' | b'$' | b')' | b']' | b'}' | b'"' | b'\'' | b'`')
+    })
+}
+
+/// Check if the line after the line break at `i` continues the statement.
+fn continues_statement(bytes: &[u8], mut i: usize) -> bool {
+    const KEYWORDS: &[&[u8]] = &[
+        b"as",
+        b"catch",
+        b"else",
+        b"extends",
+        b"finally",
+        b"from",
+        b"implements",
+        b"in",
+        b"instanceof",
+        b"satisfies",
+    ];
+
+    loop {
+        match bytes.get(i) {
+            Some(c) if c.is_ascii_whitespace() => i += 1,
+            Some(b'/') if matches!(bytes.get(i + 1), Some(b'/' | b'*')) => {
+                i = skip_comment(bytes, i);
+            }
+            Some(b'.' | b',' | b'?' | b':' | b'=' | b'|' | b'&' | b'*' | b'%' | b'<' | b'>') => {
+                return true;
+            }
+            Some(_) => {
+                let rest = &bytes[i..];
+                let word_len = rest
+                    .iter()
+                    .position(|c| !(c.is_ascii_alphanumeric() || *c == b'_' || *c == b'$'))
+                    .unwrap_or(rest.len());
+                return KEYWORDS.contains(&&rest[..word_len]);
+            }
+            None => return false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,6 +524,46 @@ export default defineComponent({
         assert!(ranges.is_options_api);
     }
 
+    #[test]
+    fn test_top_level_await() {
+        assert!(has_top_level_await("const data = await fetchData()"));
+        assert!(has_top_level_await("if (ready) {\n  await load()\n}"));
+        assert!(has_top_level_await("for await (const chunk of stream) {}"));
+        assert!(has_top_level_await(
+            "const f = async () => await a()\nconst b = await c()"
+        ));
+    }
+
+    #[test]
+    fn test_non_ascii_script() {
+        assert!(has_top_level_await("const größe = await f()"));
+        assert!(!has_top_level_await("const s = `→` // ≥\nconst größe = 1"));
+        assert_eq!(
+            top_level_bindings("const größe = 1\nfunction zähle() {}"),
+            ["größe", "zähle"]
+        );
+    }
+
+    #[test]
+    fn test_await_inside_functions() {
+        assert!(!has_top_level_await(
+            "async function load() { await fetchData() }"
+        ));
+        assert!(!has_top_level_await(
+            "const load = async () => { await fetchData() }"
+        ));
+        assert!(!has_top_level_await(
+            "const load = async () => await fetchData();"
+        ));
+        assert!(!has_top_level_await(
+            "onMounted(async () => {\n  await load()\n})"
+        ));
+        assert!(!has_top_level_await(
+            "const obj = { async load() { await x } }"
+        ));
+        assert!(!has_top_level_await("// await later\nconst s = 'await'"));
+    }
+
     #[test]
     fn test_analyze_not_options_api() {
         let content = r#"
@@ -97,4 +572,98 @@ export const foo = 'bar'
         let ranges = analyze_script(content);
         assert!(!ranges.is_options_api);
     }
+
+    #[test]
+    fn test_top_level_bindings() {
+        let content = r#"
+import { ref, computed as c, type Ref } from 'vue'
+import Child, * as utils from './utils'
+import type { Props } from './types'
+import './style.css'
+const msg = ref('hi')
+const { a, b: renamed, d = 1 } = useThing()
+let [x, , y] = pair
+function greet() { const inner = 1 }
+async function load() {}
+class Store {}
+enum Color { Red }
+if (msg) { var hoisted = 1 }
+"#;
+        assert_eq!(
+            top_level_bindings(content),
+            vec![
+                "ref", "c", "Child", "utils", "msg", "a", "renamed", "d", "x", "y", "greet",
+                "load", "Store", "Color"
+            ]
+        );
+    }
+
+    fn hoisted(content: &str, hoist_types: bool) -> Vec<&str> {
+        module_level_ranges(content, hoist_types)
+            .into_iter()
+            .map(|(start, end)| &content[start..end])
+            .collect()
+    }
+
+    #[test]
+    fn test_module_level_ranges() {
+        let content = r#"import { ref } from 'vue'
+import {
+  computed,
+} from 'vue';
+const count = ref(0)
+export function double(n: number) {
+  return n * 2
+}
+enum Color { Red, Green }
+const enum Size {
+  Small,
+}
+declare const __APP_VERSION__: string
+type Mode =
+  | 'light'
+  | 'dark'
+interface Item
+  extends Base {
+  id: number
+}
+const lazy = import('./Lazy.vue')
+const url = import.meta.url
+type Counter = typeof count
+"#;
+        assert_eq!(
+            hoisted(content, true),
+            [
+                "import { ref } from 'vue'",
+                "import {\n  computed,\n} from 'vue';",
+                "export function double(n: number) {\n  return n * 2\n}",
+                "enum Color { Red, Green }",
+                "const enum Size {\n  Small,\n}",
+                "declare const __APP_VERSION__: string",
+                "type Mode =\n  | 'light'\n  | 'dark'",
+                "interface Item\n  extends Base {\n  id: number\n}",
+            ]
+        );
+
+        // A generic component's types may use its type parameters
+        assert_eq!(hoisted(content, false).len(), 6);
+    }
+
+    #[test]
+    fn test_top_level_statements_skip_strings_and_comments() {
+        let content =
+            "const a = '{' // }\nconst b = `;\n`\n/* ; */ const c = fn(\n  1,\n)\n  .then()\n";
+        let statements: Vec<_> = top_level_statements(content)
+            .into_iter()
+            .map(|(start, end)| &content[start..end])
+            .collect();
+        assert_eq!(
+            statements,
+            [
+                "const a = '{'",
+                "const b = `;\n`",
+                "const c = fn(\n  1,\n)\n  .then()"
+            ]
+        );
+    }
 }
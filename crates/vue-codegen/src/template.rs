@@ -4,11 +4,15 @@
 //! that enables type checking of template expressions.
 
 use crate::context::{CodegenContext, VarSource};
-use crate::helpers::{is_html_tag, is_svg_tag};
-use source_map::CodeBuilder;
+use crate::helpers::{
+    builtin_component, dom_event_type, is_builtin_directive, is_html_tag, is_svg_tag,
+};
+use source_map::{CodeBuilder, Span};
+use vue_template_compiler::transforms::{camelize, pascalize};
 use vue_template_compiler::{
-    Attribute, ElementNode, EventListener, Expression, ForNode, IfBranch, IfNode,
-    InterpolationNode, Prop, SlotOutletNode, TemplateAst, TemplateNode,
+    Attribute, Directive, ElementNode, EventListener, Expression, ForAlias, ForNode, IfBranch,
+    IfBranchType, IfNode, InterpolationNode, Prop, SlotOutletNode, TemplateAst,
+    TemplateElementNode, TemplateNode,
 };
 
 /// Generate type checking code for a template.
@@ -17,9 +21,15 @@ pub fn generate_template(builder: &mut CodeBuilder, ast: &TemplateAst, ctx: &mut
     builder.push_str("function __VLS_template() {\n");
 
     // Add template context
-    builder.push_str("  const __VLS_ctx = {} as __VLS_TemplateContext & {\n");
+    builder.push_str("  const __VLS_ctx = {} as Omit<__VLS_TemplateContext, '$attrs'> & {\n");
     builder.push_str("    $props: typeof __VLS_props;\n");
     builder.push_str("    $emit: typeof __VLS_emit;\n");
+    // Declared props never fall through
+    if ctx.macros.define_props.is_some() {
+        builder.push_str("    $attrs: __VLS_FallthroughAttrs<typeof __VLS_props>;\n");
+    } else {
+        builder.push_str("    $attrs: Record<string, unknown>;\n");
+    }
     builder.push_str("  };\n\n");
 
     // Generate code for children
@@ -43,11 +53,7 @@ fn generate_node(
         TemplateNode::If(if_node) => generate_if(builder, if_node, ctx, indent),
         TemplateNode::For(for_node) => generate_for(builder, for_node, ctx, indent),
         TemplateNode::SlotOutlet(slot) => generate_slot_outlet(builder, slot, ctx, indent),
-        TemplateNode::Template(tmpl) => {
-            for child in &tmpl.children {
-                generate_node(builder, child, ctx, indent);
-            }
-        }
+        TemplateNode::Template(tmpl) => generate_fragment(builder, tmpl, ctx, indent),
         TemplateNode::Text(_) | TemplateNode::Comment(_) => {
             // Text and comments don't need type checking
         }
@@ -61,88 +67,462 @@ fn generate_element(
     ctx: &mut CodegenContext,
     indent: usize,
 ) {
-    let ind = "  ".repeat(indent);
     let tag = &el.tag;
 
-    // Determine if this is a component or HTML element
-    let is_component = el.is_component;
+    if let Some(builtin) = builtin_component(tag) {
+        return generate_builtin_component(builder, el, builtin, ctx, indent);
+    }
 
-    if is_component {
-        // Component
-        ctx.use_component(tag.clone());
+    if tag == "component" {
+        // `<component is="...">` with a static string resolves by name,
+        // while `:is` is a dynamic expression checked along with the props
+        match static_is_target(el) {
+            Some(target) if is_html_tag(target) || is_svg_tag(target) => {
+                generate_native_element(builder, el, target, ctx, indent);
+            }
+            Some(target) => return generate_component(builder, el, target, ctx, indent),
+            None => return generate_dynamic_component(builder, el, ctx, indent),
+        }
+    } else if el.is_component {
+        // A component's children are slot content, generated with it
+        return generate_component(builder, el, el.component_name(), ctx, indent);
+    } else if is_html_tag(tag) || is_svg_tag(tag) {
+        generate_native_element(builder, el, tag, ctx, indent);
+    }
 
-        builder.push_str(&ind);
-        builder.push_str("{\n");
+    // Generate children
+    for child in &el.children {
+        generate_node(builder, child, ctx, indent);
+    }
+}
 
-        // Resolve component
-        builder.push_str(&ind);
-        builder.push_str("  const __VLS_");
-        builder.push_str(&ctx.unique_id("component"));
-        builder.push_str(" = __VLS_resolveComponent('");
-        builder.push_str(tag);
-        builder.push_str("');\n");
-
-        // Check props
-        generate_props_check(builder, &el.props, ctx, indent + 1);
-
-        // Check events
-        generate_events_check(builder, &el.events, ctx, indent + 1);
-
-        // Check slots
-        for (_name, slot) in &el.slots {
-            let scope_marker = ctx.enter_scope();
-
-            // Add slot props to scope
-            if let Some(props) = &slot.props {
-                for name in extract_binding_names(&props.pattern) {
-                    ctx.add_var(name, VarSource::SlotProps);
-                }
+/// Generate code for a `<template>` fragment.
+///
+/// `v-if` and `v-for` on a fragment are already lifted into the enclosing
+/// `If`/`For` node, so only `v-slot` props need to be brought into scope.
+/// Away from its component, a slot's props can't be typed.
+fn generate_fragment(
+    builder: &mut CodeBuilder,
+    tmpl: &TemplateElementNode,
+    ctx: &mut CodegenContext,
+    indent: usize,
+) {
+    match slot_directive(&tmpl.directives) {
+        Some(slot) => {
+            let (name, props) = slot_name_and_props(slot);
+            generate_slot(builder, name, props, &tmpl.children, None, ctx, indent);
+        }
+        None => {
+            for child in &tmpl.children {
+                generate_node(builder, child, ctx, indent);
             }
+        }
+    }
+}
 
-            for child in &slot.children {
-                generate_node(builder, child, ctx, indent + 1);
-            }
+/// Find the `v-slot` directive among an element's directives.
+fn slot_directive(directives: &[Directive]) -> Option<&Directive> {
+    directives.iter().find(|d| d.name == "slot")
+}
 
-            ctx.exit_scope(scope_marker);
-        }
+/// Get the slot name and props pattern of a `v-slot` directive.
+///
+/// A dynamic slot name gives `None`, as the slot it fills isn't known.
+fn slot_name_and_props(directive: &Directive) -> (Option<&str>, Option<(&str, Span)>) {
+    let name = match &directive.arg {
+        None => Some("default"),
+        Some(arg) => arg.as_static(),
+    };
+    let props = directive
+        .value
+        .as_ref()
+        .map(|value| (value.content.as_str(), value.span));
+    (name, props)
+}
 
-        builder.push_str(&ind);
-        builder.push_str("}\n");
+/// Get the target of a static `is` attribute on `<component>`.
+///
+/// The `vue:` prefix is stripped, as it only forces component resolution.
+fn static_is_target(el: &ElementNode) -> Option<&str> {
+    let value = el.attrs.iter().find(|a| a.name == "is")?.value.as_deref()?;
+    let value = value.trim();
+    if let Some(name) = value.strip_prefix("vue:") {
+        return Some(name);
+    }
+    (!value.is_empty()).then_some(value)
+}
+
+/// Generate code for a component resolved by name.
+fn generate_component(
+    builder: &mut CodeBuilder,
+    el: &ElementNode,
+    name: &str,
+    ctx: &mut CodegenContext,
+    indent: usize,
+) {
+    let ind = "  ".repeat(indent);
+    ctx.use_component(name);
+
+    builder.push_str(&ind);
+    builder.push_str("{\n");
+
+    // Resolve component
+    let component = format!("__VLS_{}", ctx.unique_id("component", el.span));
+    builder.push_str(&ind);
+    builder.push_str("  const ");
+    builder.push_str(&component);
+    builder.push_str(" = __VLS_resolveComponent('");
+    // `GlobalComponents` (e.g. auto-imported components) is keyed by PascalCase name
+    builder.push_str(&pascalize(name));
+    builder.push_str("');\n");
+
+    // Check props
+    if ctx.options.strict {
+        generate_strict_props_check(builder, &el.props, &component, ctx, indent + 1);
     } else {
-        // HTML/SVG element
-        let is_svg = is_svg_tag(tag);
-        let is_html = is_html_tag(tag);
+        generate_props_check(builder, &el.props, true, ctx, indent + 1);
+    }
 
-        if is_html || is_svg {
-            builder.push_str(&ind);
-            builder.push_str("{\n");
+    // Check events
+    generate_events_check(builder, &el.events, true, ctx, indent + 1);
+    generate_directives_check(builder, &el.directives, ctx, indent + 1);
 
-            // Check attributes
-            for attr in &el.attrs {
-                generate_attr_check(builder, attr, tag, ctx, indent + 1);
-            }
+    // Check slots
+    generate_slots(builder, el, Some(&component), ctx, indent + 1);
 
-            // Check props (dynamic attributes)
-            generate_props_check(builder, &el.props, ctx, indent + 1);
+    builder.push_str(&ind);
+    builder.push_str("}\n");
+}
+
+/// Generate code for a built-in component, like `<Teleport>` or `<Transition>`.
+///
+/// Their props are known, so they are passed as one object literal and
+/// checked exactly, like components under `strictTemplates`. Static
+/// attributes are props too, except `data-*` and `aria-*` ones, which fall
+/// through. Children are the default slot and are checked in place.
+fn generate_builtin_component(
+    builder: &mut CodeBuilder,
+    el: &ElementNode,
+    builtin: &str,
+    ctx: &mut CodegenContext,
+    indent: usize,
+) {
+    let ind = "  ".repeat(indent);
+    let (checked, lenient): (Vec<Prop>, Vec<Prop>) = el
+        .props
+        .iter()
+        .cloned()
+        .partition(|prop| !prop.is_dynamic && !prop.is_dom_binding());
 
-            // Check events
-            generate_events_check(builder, &el.events, ctx, indent + 1);
+    builder.push_str(&ind);
+    builder.push_str("{\n");
 
+    builder.push_str(&ind);
+    builder.push_str("  __VLS_builtinComponent('");
+    builder.push_str(builtin);
+    builder.push_str("')({\n");
+    let offset = ctx.template_offset;
+    for attr in &el.attrs {
+        if attr.name.starts_with("data-") || attr.name.starts_with("aria-") {
+            continue;
+        }
+        builder.push_str(&ind);
+        builder.push_str("    ");
+        let span = attr.span.shift(offset.into());
+        builder.push_with_mapping(
+            &format!("'{}'", camelize(&attr.name)),
+            span.start,
+            span.len(),
+        );
+        builder.push_str(": ");
+        // A valueless attribute sets a boolean prop
+        match &attr.value {
+            Some(value) => builder.push_str(&string_literal(value)),
+            None => builder.push_str("true"),
+        }
+        builder.push_str(",\n");
+    }
+    for prop in &checked {
+        builder.push_str(&ind);
+        builder.push_str("    ");
+        let span = prop.span.shift(offset.into());
+        builder.push_with_mapping(
+            &format!("'{}'", prop.component_prop_name()),
+            span.start,
+            span.len(),
+        );
+        builder.push_str(": (");
+        generate_expression(builder, &prop.value, ctx);
+        builder.push_str("),\n");
+    }
+    // `v-bind="object"` passes every property of the object as a prop
+    for dir in el
+        .directives
+        .iter()
+        .filter(|d| d.is_bind() && d.arg.is_none())
+    {
+        if let Some(value) = &dir.value {
             builder.push_str(&ind);
-            builder.push_str("}\n");
+            builder.push_str("    ...(");
+            generate_expression(builder, value, ctx);
+            builder.push_str("),\n");
         }
     }
+    builder.push_str(&ind);
+    builder.push_str("  });\n");
+
+    generate_props_check(builder, &lenient, true, ctx, indent + 1);
+    generate_events_check(builder, &el.events, true, ctx, indent + 1);
+    generate_directives_check(builder, &el.directives, ctx, indent + 1);
 
-    // Generate children
     for child in &el.children {
-        generate_node(builder, child, ctx, indent);
+        generate_node(builder, child, ctx, indent + 1);
+    }
+
+    builder.push_str(&ind);
+    builder.push_str("}\n");
+}
+
+/// Quote a static value as a JavaScript string literal.
+fn string_literal(value: &str) -> String {
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push('\'');
+    for c in value.chars() {
+        match c {
+            '\'' | '\\' => {
+                literal.push('\\');
+                literal.push(c);
+            }
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            _ => literal.push(c),
+        }
+    }
+    literal.push('\'');
+    literal
+}
+
+/// Generate code for a `<component :is>` whose target is only known at runtime.
+fn generate_dynamic_component(
+    builder: &mut CodeBuilder,
+    el: &ElementNode,
+    ctx: &mut CodegenContext,
+    indent: usize,
+) {
+    let ind = "  ".repeat(indent);
+
+    builder.push_str(&ind);
+    builder.push_str("{\n");
+
+    // The `:is` binding is checked as a regular prop expression
+    generate_props_check(builder, &el.props, true, ctx, indent + 1);
+    generate_events_check(builder, &el.events, true, ctx, indent + 1);
+    generate_slots(builder, el, None, ctx, indent + 1);
+
+    builder.push_str(&ind);
+    builder.push_str("}\n");
+}
+
+/// Generate code for an HTML/SVG element.
+fn generate_native_element(
+    builder: &mut CodeBuilder,
+    el: &ElementNode,
+    tag: &str,
+    ctx: &mut CodegenContext,
+    indent: usize,
+) {
+    let ind = "  ".repeat(indent);
+
+    builder.push_str(&ind);
+    builder.push_str("{\n");
+
+    // Check attributes
+    for attr in &el.attrs {
+        generate_attr_check(builder, attr, tag, ctx, indent + 1);
+    }
+
+    // Check props (dynamic attributes)
+    generate_props_check(builder, &el.props, false, ctx, indent + 1);
+
+    // Check events
+    generate_events_check(builder, &el.events, false, ctx, indent + 1);
+    generate_directives_check(builder, &el.directives, ctx, indent + 1);
+
+    builder.push_str(&ind);
+    builder.push_str("}\n");
+}
+
+/// Generate code for the slots passed to a component.
+///
+/// Children in a `<template v-slot>` fill named slots. The rest form the
+/// default slot, with the props of a `v-slot` on the component itself.
+/// `component` is the variable holding the resolved component, if known.
+fn generate_slots(
+    builder: &mut CodeBuilder,
+    el: &ElementNode,
+    component: Option<&str>,
+    ctx: &mut CodegenContext,
+    indent: usize,
+) {
+    for (name, slot) in &el.slots {
+        let props = slot.props.as_ref().map(|p| (p.pattern.as_str(), p.span));
+        generate_slot(
+            builder,
+            Some(name),
+            props,
+            &slot.children,
+            component,
+            ctx,
+            indent,
+        );
     }
+
+    // Each slot is its own block, so props of a nested slot shadow those of
+    // the slots around it
+    let mut default = Vec::new();
+    for child in &el.children {
+        match child {
+            TemplateNode::Template(tmpl) => match slot_directive(&tmpl.directives) {
+                Some(slot) => {
+                    let (name, props) = slot_name_and_props(slot);
+                    generate_slot(builder, name, props, &tmpl.children, component, ctx, indent);
+                }
+                None => default.push(child),
+            },
+            _ => default.push(child),
+        }
+    }
+
+    let (name, props) = match slot_directive(&el.directives) {
+        Some(slot) => slot_name_and_props(slot),
+        None => (Some("default"), None),
+    };
+    generate_slot(builder, name, props, default, component, ctx, indent);
+}
+
+/// Generate code for the content of one slot, with its props in scope.
+///
+/// The props are typed from the component's slots when the component and
+/// slot name are known.
+fn generate_slot<'a>(
+    builder: &mut CodeBuilder,
+    name: Option<&str>,
+    props: Option<(&str, Span)>,
+    children: impl IntoIterator<Item = &'a TemplateNode>,
+    component: Option<&str>,
+    ctx: &mut CodegenContext,
+    indent: usize,
+) {
+    let children: Vec<&TemplateNode> = children
+        .into_iter()
+        .filter(|child| !matches!(child, TemplateNode::Text(_) | TemplateNode::Comment(_)))
+        .collect();
+    if children.is_empty() && props.is_none() {
+        return;
+    }
+
+    let ind = "  ".repeat(indent);
+    let scope_marker = ctx.enter_scope();
+
+    builder.push_str(&ind);
+    builder.push_str("{\n");
+
+    // An unclosed pattern would break the rest of the virtual file
+    let props = props.filter(|&(pattern, span)| {
+        let valid = is_valid_pattern(pattern);
+        if !valid {
+            ctx.template_error(
+                format!(
+                    "Slot props `{}` are not a valid destructuring pattern",
+                    pattern.trim()
+                ),
+                span,
+            );
+        }
+        valid
+    });
+    if let Some((pattern, span)) = props {
+        builder.push_str(&ind);
+        builder.push_str("  const ");
+        builder.push_mapped(pattern.trim(), span.shift(ctx.template_offset.into()).start);
+        match component.zip(name) {
+            Some((component, name)) => {
+                builder.push_str(" = __VLS_getSlotParams(__VLS_componentSlots(");
+                builder.push_str(component);
+                builder.push_str(")['");
+                builder.push_str(name);
+                builder.push_str("']);\n");
+            }
+            None => builder.push_str(": any = undefined;\n"),
+        }
+
+        for name in extract_binding_names(pattern) {
+            ctx.check_binding_name(name, span.shift(ctx.template_offset.into()));
+            ctx.add_var(name, VarSource::SlotProps);
+        }
+    }
+
+    for child in children {
+        generate_node(builder, child, ctx, indent + 1);
+    }
+
+    builder.push_str(&ind);
+    builder.push_str("}\n");
+
+    ctx.exit_scope(scope_marker);
+}
+
+/// Check that a pattern can be pasted into the generated code as is: every
+/// bracket is closed in order, and there are no comments, which could
+/// swallow the code after it.
+///
+/// Brackets and slashes in strings, like default values, don't count.
+fn is_valid_pattern(pattern: &str) -> bool {
+    let mut open = Vec::new();
+    let mut quote = None;
+    let mut escaped = false;
+    let mut prev = None;
+    for c in pattern.chars() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        if prev == Some('/') && matches!(c, '/' | '*') {
+            return false;
+        }
+        prev = Some(c);
+        match c {
+            '\'' | '"' | '`' => quote = Some(c),
+            '{' | '[' | '(' => open.push(c),
+            '}' | ']' | ')' => {
+                let expected = match c {
+                    '}' => '{',
+                    ']' => '[',
+                    _ => '(',
+                };
+                if open.pop() != Some(expected) {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+    }
+    open.is_empty()
 }
 
 /// Generate code for props type checking.
+///
+/// Component prop names are camelized to match their declarations.
 fn generate_props_check(
     builder: &mut CodeBuilder,
     props: &[Prop],
+    is_component: bool,
     ctx: &mut CodegenContext,
     indent: usize,
 ) {
@@ -150,8 +530,21 @@ fn generate_props_check(
 
     for prop in props {
         builder.push_str(&ind);
-        builder.push_str("// prop: ");
-        builder.push_str(&prop.name);
+        // `.prop`/`.attr` bypass component props and go to the root element
+        if prop.has_modifier("prop") {
+            builder.push_str("// dom prop: ");
+            builder.push_str(&prop.element_prop_name());
+        } else if prop.has_modifier("attr") {
+            builder.push_str("// attr: ");
+            builder.push_str(&prop.element_prop_name());
+        } else {
+            builder.push_str("// prop: ");
+            if is_component {
+                builder.push_str(&prop.component_prop_name());
+            } else {
+                builder.push_str(&prop.element_prop_name());
+            }
+        }
         builder.push_str("\n");
 
         builder.push_str(&ind);
@@ -161,10 +554,101 @@ fn generate_props_check(
     }
 }
 
+/// Generate code checking the values bound to custom directives.
+///
+/// A directive declared in script setup as `vMyDir` is used directly, so a
+/// `Directive<El, V>` type checks the value of `v-my-dir` against `V`. Other
+/// directives resolve by name, untyped.
+fn generate_directives_check(
+    builder: &mut CodeBuilder,
+    directives: &[Directive],
+    ctx: &mut CodegenContext,
+    indent: usize,
+) {
+    let ind = "  ".repeat(indent);
+
+    for dir in directives.iter().filter(|d| !is_builtin_directive(&d.name)) {
+        ctx.use_directive(dir.name.as_str());
+        let Some(value) = &dir.value else {
+            continue;
+        };
+
+        let local = format!("v{}", pascalize(&dir.name));
+        let directive = if ctx.has_var(&local) {
+            local
+        } else {
+            format!("__VLS_resolveDirective('{}')", camelize(&dir.name))
+        };
+
+        builder.push_str(&ind);
+        builder.push_str("// directive: v-");
+        builder.push_str(&dir.name);
+        builder.push_str("\n");
+        builder.push_str(&ind);
+        builder.push_str("__VLS_directiveValue(");
+        builder.push_str(&directive);
+        builder.push_str(", (");
+        generate_expression(builder, value, ctx);
+        builder.push_str("));\n");
+    }
+}
+
+/// Generate code for component props type checking under `strictTemplates`.
+///
+/// The props are passed to the component as one object literal typed with its
+/// exact props, so tsc reports any prop the component doesn't declare instead
+/// of letting it fall through as an attribute. Bindings with a dynamic name or
+/// a `.prop`/`.attr` modifier can't be checked this way and stay lenient.
+fn generate_strict_props_check(
+    builder: &mut CodeBuilder,
+    props: &[Prop],
+    component: &str,
+    ctx: &mut CodegenContext,
+    indent: usize,
+) {
+    let ind = "  ".repeat(indent);
+    let (checked, lenient): (Vec<Prop>, Vec<Prop>) = props
+        .iter()
+        .cloned()
+        .partition(|prop| !prop.is_dynamic && !prop.is_dom_binding());
+
+    if !checked.is_empty() {
+        builder.push_str(&ind);
+        builder.push_str("__VLS_asStrictFunctionalComponent(");
+        builder.push_str(component);
+        builder.push_str(")({\n");
+        for prop in &checked {
+            builder.push_str(&ind);
+            builder.push_str("  ");
+            // An unknown prop is reported on the key, so it maps to the whole
+            // binding in the template
+            let span = prop.span.shift(ctx.template_offset.into());
+            builder.push_with_mapping(
+                &format!("'{}'", prop.component_prop_name()),
+                span.start,
+                span.len(),
+            );
+            builder.push_str(": (");
+            generate_expression(builder, &prop.value, ctx);
+            builder.push_str("),\n");
+        }
+        builder.push_str(&ind);
+        builder.push_str("});\n");
+    }
+
+    generate_props_check(builder, &lenient, true, ctx, indent);
+}
+
 /// Generate code for events type checking.
+///
+/// A method handler for a native DOM event, like `@click="onClick"`, is
+/// checked against the event type, so a handler expecting a different event
+/// is an error. An inline handler using `$event` gets it as a parameter of
+/// that type, or `any` when it isn't known.
 fn generate_events_check(
     builder: &mut CodeBuilder,
     events: &[EventListener],
+    is_component: bool,
     ctx: &mut CodegenContext,
     indent: usize,
 ) {
@@ -176,11 +660,60 @@ fn generate_events_check(
         builder.push_str(&event.name);
         builder.push_str("\n");
 
+        let event_type = (!is_component && !event.is_dynamic)
+            .then(|| dom_event_type(&event.name).or_else(|| modifier_event_type(event)))
+            .flatten();
+        let handler = event.handler.content.trim();
+
         builder.push_str(&ind);
-        builder.push_str("(");
-        generate_expression(builder, &event.handler, ctx);
-        builder.push_str(");\n");
+        if let Some(event_type) = event_type.filter(|_| is_member_path(handler)) {
+            builder.push_str("__VLS_eventHandler<");
+            builder.push_str(event_type);
+            builder.push_str(">(");
+            generate_expression(builder, &event.handler, ctx);
+            builder.push_str(");\n");
+        } else if handler.contains("$event") {
+            builder.push_str("(($event: ");
+            builder.push_str(event_type.unwrap_or("any"));
+            builder.push_str(") => { ");
+            generate_expression(builder, &event.handler, ctx);
+            builder.push_str("; });\n");
+        } else {
+            builder.push_str("(");
+            generate_expression(builder, &event.handler, ctx);
+            builder.push_str(");\n");
+        }
+    }
+}
+
+/// Get the event type implied by an event's modifiers, for events whose name
+/// doesn't give it.
+///
+/// As in Vue's compiler, any modifier that isn't an event option, a system
+/// modifier or a mouse button is a key name, so the handler only runs for
+/// keyboard events. `.left` and `.right` are mouse buttons except on key
+/// events, so they imply nothing here.
+fn modifier_event_type(event: &EventListener) -> Option<&'static str> {
+    const NON_KEY_MODIFIERS: &[&str] = &[
+        "stop", "prevent", "self", "capture", "once", "passive", "ctrl", "shift", "alt", "meta",
+        "exact", "left", "right",
+    ];
+
+    let mut event_type = None;
+    for modifier in &event.modifiers {
+        match modifier.as_str() {
+            "middle" => return Some("MouseEvent"),
+            m if NON_KEY_MODIFIERS.contains(&m) => {}
+            _ => event_type = Some("KeyboardEvent"),
+        }
     }
+    event_type
+}
+
+/// Check if a handler is a method reference like `onClick` or `form.submit`,
+/// rather than an inline statement.
+fn is_member_path(s: &str) -> bool {
+    s.split('.').all(|part| is_simple_identifier(part.trim()))
 }
 
 /// Generate code for attribute type checking.
@@ -211,7 +744,7 @@ fn generate_interpolation(
     builder.push_str(" }}\n");
 
     builder.push_str(&ind);
-    builder.push_str("(");
+    builder.push_str("__VLS_toDisplayString(");
     generate_expression(builder, &interp.expression, ctx);
     builder.push_str(");\n");
 }
@@ -246,16 +779,12 @@ fn generate_if_branch(
     if is_first {
         builder.push_str(&ind);
         builder.push_str("if (");
-        if let Some(condition) = &branch.condition {
-            generate_expression(builder, condition, ctx);
-        }
+        generate_condition(builder, branch, ctx);
         builder.push_str(") {\n");
-    } else if branch.condition.is_some() {
+    } else if branch.branch_type == IfBranchType::ElseIf {
         builder.push_str(&ind);
         builder.push_str("} else if (");
-        if let Some(condition) = &branch.condition {
-            generate_expression(builder, condition, ctx);
-        }
+        generate_condition(builder, branch, ctx);
         builder.push_str(") {\n");
     } else {
         builder.push_str(&ind);
@@ -267,6 +796,31 @@ fn generate_if_branch(
     }
 }
 
+/// Generate the condition of a `v-if` or `v-else-if` branch.
+///
+/// A missing condition is reported and replaced with `true`, so the rest of
+/// the template is still checked.
+fn generate_condition(builder: &mut CodeBuilder, branch: &IfBranch, ctx: &mut CodegenContext) {
+    match branch
+        .condition
+        .as_ref()
+        .filter(|condition| !condition.content.trim().is_empty())
+    {
+        Some(condition) => generate_expression(builder, condition, ctx),
+        None => {
+            let directive = match branch.branch_type {
+                IfBranchType::ElseIf => "v-else-if",
+                _ => "v-if",
+            };
+            ctx.template_error(
+                format!("{} needs a condition", directive),
+                branch.directive_span,
+            );
+            builder.push_str("true");
+        }
+    }
+}
+
 /// Generate code for a loop (v-for).
 fn generate_for(
     builder: &mut CodeBuilder,
@@ -281,26 +835,30 @@ fn generate_for(
     builder.push_str("for (const [");
 
     // Add loop variables to scope
-    let value_name = &for_node.value.pattern;
-    ctx.add_var(value_name.as_str(), VarSource::VFor);
-    builder.push_str(value_name);
-
-    if let Some(key) = &for_node.key {
-        ctx.add_var(key.pattern.as_str(), VarSource::VFor);
-        builder.push_str(", ");
-        builder.push_str(&key.pattern);
-    }
-
-    if let Some(index) = &for_node.index {
-        ctx.add_var(index.pattern.as_str(), VarSource::VFor);
+    generate_for_alias(builder, &for_node.value, ctx);
+    for alias in [&for_node.key, &for_node.index].into_iter().flatten() {
         builder.push_str(", ");
-        builder.push_str(&index.pattern);
+        generate_for_alias(builder, alias, ctx);
     }
 
     builder.push_str("] of __VLS_getVForSourceType(");
-    generate_expression(builder, &for_node.source, ctx);
+    if for_node.source.content.trim().is_empty() {
+        ctx.template_error("v-for needs a source to iterate over", for_node.span);
+        builder.push_str("[] as any[]");
+    } else {
+        generate_expression(builder, &for_node.source, ctx);
+    }
     builder.push_str(")) {\n");
 
+    if let Some(key) = &for_node.key_attr {
+        builder.push_str(&ind);
+        builder.push_str("  // key\n");
+        builder.push_str(&ind);
+        builder.push_str("  (");
+        generate_expression(builder, key, ctx);
+        builder.push_str(");\n");
+    }
+
     for child in &for_node.children {
         generate_node(builder, child, ctx, indent + 1);
     }
@@ -311,6 +869,28 @@ fn generate_for(
     ctx.exit_scope(scope_marker);
 }
 
+/// Generate a v-for alias and add its names to the scope.
+///
+/// An alias that isn't a valid pattern is reported and left as a hole in the
+/// destructuring, so the rest of the virtual file stays well-formed.
+fn generate_for_alias(builder: &mut CodeBuilder, alias: &ForAlias, ctx: &mut CodegenContext) {
+    if !is_valid_pattern(&alias.pattern) {
+        ctx.template_error(
+            format!(
+                "v-for alias `{}` is not a valid destructuring pattern",
+                alias.pattern.trim()
+            ),
+            alias.span,
+        );
+        return;
+    }
+    for name in extract_binding_names(&alias.pattern) {
+        ctx.check_binding_name(name, alias.span.shift(ctx.template_offset.into()));
+    }
+    ctx.add_var(alias.pattern.as_str(), VarSource::VFor);
+    builder.push_str(&alias.pattern);
+}
+
 /// Generate code for a slot outlet.
 fn generate_slot_outlet(
     builder: &mut CodeBuilder,
@@ -351,34 +931,27 @@ fn generate_slot_outlet(
 
 /// Generate code for an expression.
 fn generate_expression(builder: &mut CodeBuilder, expr: &Expression, ctx: &mut CodegenContext) {
-    let content = &expr.content;
+    let trimmed = expr.content.trim_start();
+    let leading = (expr.content.len() - trimmed.len()) as u32;
+    let content = trimmed.trim_end();
+    let source_offset = expr.span.shift(ctx.template_offset.into()).start + leading;
 
     // Wrap identifiers with context access
     // This is a simplified version - a full implementation would parse the expression
-    let wrapped = wrap_expression_identifiers(content, ctx);
-
-    builder.push_mapped(&wrapped, expr.span.start);
-}
-
-/// Wrap identifiers in an expression with context access.
-fn wrap_expression_identifiers(expr: &str, ctx: &CodegenContext) -> String {
-    let expr = expr.trim();
-
-    // Very simple identifier detection
-    // A proper implementation would use AST parsing
-    if is_simple_identifier(expr) {
-        if is_js_builtin(expr) || ctx.has_var(expr) {
-            expr.to_string()
-        } else {
-            format!("__VLS_ctx.{}", expr)
-        }
+    if needs_context_access(content, ctx) {
+        builder.push_prefixed("__VLS_ctx.", content, source_offset);
     } else {
-        // For complex expressions, return as-is
-        // A full implementation would walk the AST
-        expr.to_string()
+        builder.push_mapped(content, source_offset);
     }
 }
 
+/// Check if an expression is an identifier that must be read from the context.
+fn needs_context_access(expr: &str, ctx: &CodegenContext) -> bool {
+    // Very simple identifier detection; complex expressions are emitted as-is
+    // A full implementation would walk the AST
+    is_simple_identifier(expr) && !is_js_builtin(expr) && !ctx.has_var(expr)
+}
+
 /// Check if a string is a simple identifier.
 fn is_simple_identifier(s: &str) -> bool {
     let mut chars = s.chars();
@@ -485,6 +1058,16 @@ mod tests {
         assert!(!is_simple_identifier("123foo"));
     }
 
+    #[test]
+    fn test_is_valid_pattern() {
+        assert!(is_valid_pattern("{ row, meta: { id } }"));
+        assert!(is_valid_pattern("{ url = 'http://a/{b' }"));
+        assert!(!is_valid_pattern("{ row"));
+        assert!(!is_valid_pattern("{ row } }"));
+        assert!(!is_valid_pattern("{ row /* } */ }"));
+        assert!(!is_valid_pattern("item // trailing"));
+    }
+
     #[test]
     fn test_is_js_builtin() {
         assert!(is_js_builtin("true"));
@@ -493,6 +1076,327 @@ mod tests {
         assert!(!is_js_builtin("myVar"));
     }
 
+    #[test]
+    fn test_component_prop_names_are_camelized() {
+        let ast = vue_template_compiler::parse_template(
+            r#"<MyComp :my-prop="a" /><input :aria-label="b" />"#,
+        )
+        .unwrap();
+        let mut builder = CodeBuilder::new();
+        let mut ctx = CodegenContext::new(Default::default());
+        generate_template(&mut builder, &ast, &mut ctx);
+
+        let code = builder.code();
+        assert!(code.contains("// prop: myProp"));
+        assert!(code.contains("// prop: aria-label"));
+    }
+
+    #[test]
+    fn test_wrapped_identifier_maps_to_identifier_start() {
+        let source = "<div>{{  count }}</div>";
+        let ast = vue_template_compiler::parse_template(source).unwrap();
+        let mut builder = CodeBuilder::new();
+        let mut ctx = CodegenContext::new(Default::default());
+        generate_template(&mut builder, &ast, &mut ctx);
+        let (code, map) = builder.finish();
+
+        let ident = source.find("count").unwrap() as u32;
+        let wrapped = code.find("__VLS_ctx.count").unwrap() as u32;
+        // An error reported at the start of `__VLS_ctx.count`
+        assert_eq!(map.to_source_offset(wrapped), Some(ident));
+        assert_eq!(map.to_source_offset(wrapped + 4), Some(ident));
+        assert_eq!(map.to_source_offset(wrapped + 12), Some(ident + 2));
+    }
+
+    #[test]
+    fn test_prop_modifiers() {
+        let code = template_code(
+            r#"<div :some-attr.camel="x" :inner-html.prop="h" /><MyComp :data-id.attr="i" />"#,
+        );
+        assert!(code.contains("// prop: someAttr"));
+        assert!(code.contains("// dom prop: inner-html"));
+        assert!(code.contains("// attr: data-id"));
+    }
+
+    #[test]
+    fn test_same_name_shorthand_binds_from_scope() {
+        let source = "<Comp :count />";
+        let ast = vue_template_compiler::parse_template(source).unwrap();
+        let mut builder = CodeBuilder::new();
+        let mut ctx = CodegenContext::new(Default::default());
+        generate_template(&mut builder, &ast, &mut ctx);
+        let (code, map) = builder.finish();
+
+        assert!(code.contains("// prop: count"));
+        let binding = code.find("__VLS_ctx.count").unwrap() as u32;
+        assert_eq!(map.to_source_offset(binding), Some(7));
+    }
+
+    fn template_code(source: &str) -> String {
+        let ast = vue_template_compiler::parse_template(source).unwrap();
+        let mut builder = CodeBuilder::new();
+        let mut ctx = CodegenContext::new(Default::default());
+        generate_template(&mut builder, &ast, &mut ctx);
+        builder.code().to_string()
+    }
+
+    #[test]
+    fn test_static_component_is_resolves_by_name() {
+        let code = template_code(r#"<component is="MyComp" :title="t" />"#);
+        assert!(code.contains("__VLS_resolveComponent('MyComp')"));
+        assert!(code.contains("// prop: title"));
+        assert!(!code.contains("// prop: is"));
+
+        let code = template_code(r#"<component is="vue:MyComp" />"#);
+        assert!(code.contains("__VLS_resolveComponent('MyComp')"));
+    }
+
+    #[test]
+    fn test_vue_is_attribute_resolves_component() {
+        let code = template_code(r#"<table><tr is="vue:MyRow" :item="row"></tr></table>"#);
+        assert!(code.contains("__VLS_resolveComponent('MyRow')"));
+        assert!(code.contains("// prop: item"));
+        assert!(!code.contains("__VLS_resolveComponent('tr')"));
+    }
+
+    #[test]
+    fn test_native_event_method_handler_is_typed() {
+        let code = template_code(
+            r#"<button @click="onClick" @keydown.enter="form.submit" @foo="onFoo"></button>"#,
+        );
+        assert!(code.contains("__VLS_eventHandler<MouseEvent>(__VLS_ctx.onClick);"));
+        assert!(code.contains("__VLS_eventHandler<KeyboardEvent>(form.submit);"));
+        // Unknown events keep the plain expression check
+        assert!(code.contains("(__VLS_ctx.onFoo);"));
+    }
+
+    #[test]
+    fn test_key_modifiers_imply_keyboard_event() {
+        let code = template_code(
+            r#"<input @keyup.enter="onEnter" @confirm.enter="onConfirm" @dismiss.once.capture="onDismiss" />"#,
+        );
+        assert!(code.contains("__VLS_eventHandler<KeyboardEvent>(__VLS_ctx.onEnter);"));
+        assert!(code.contains("__VLS_eventHandler<KeyboardEvent>(__VLS_ctx.onConfirm);"));
+        // Event options don't say anything about the event
+        assert!(code.contains("(__VLS_ctx.onDismiss);"));
+        assert!(!code.contains("__VLS_eventHandler<KeyboardEvent>(__VLS_ctx.onDismiss)"));
+
+        // The event name wins over modifiers
+        let code = template_code(r#"<button @click.prevent.enter="onClick"></button>"#);
+        assert!(code.contains("__VLS_eventHandler<MouseEvent>(__VLS_ctx.onClick);"));
+    }
+
+    #[test]
+    fn test_inline_handler_event_is_typed() {
+        let code = template_code(
+            r#"<input @keyup.enter.stop="submit($event)" @update="save($event)" /><MyComp @change="set($event)" />"#,
+        );
+        assert!(code.contains("(($event: KeyboardEvent) => { submit($event); });"));
+        assert!(code.contains("(($event: any) => { save($event); });"));
+        assert!(code.contains("(($event: any) => { set($event); });"));
+    }
+
+    #[test]
+    fn test_inline_and_component_event_handlers_are_not_typed() {
+        let code =
+            template_code(r#"<button @click="count++"></button><MyComp @click="onClick" />"#);
+        assert!(!code.contains("__VLS_eventHandler"));
+        assert!(code.contains("(count++);"));
+        assert!(code.contains("(__VLS_ctx.onClick);"));
+    }
+
+    #[test]
+    fn test_component_ids_use_source_offsets() {
+        let source = r#"<MyComp /><div><MyComp :a="b" /></div>"#;
+        let code = template_code(source);
+        assert!(code.contains("const __VLS_component_0 = "));
+        let second = source.rfind("<MyComp").unwrap();
+        assert!(code.contains(&format!("const __VLS_component_{} = ", second)));
+    }
+
+    #[test]
+    fn test_kebab_component_resolves_by_pascal_name() {
+        let code = template_code(r#"<hello-world />"#);
+        assert!(code.contains("__VLS_resolveComponent('HelloWorld')"));
+    }
+
+    #[test]
+    fn test_static_component_is_native_element() {
+        let code = template_code(r#"<component is="div" :id="id" />"#);
+        assert!(!code.contains("__VLS_resolveComponent"));
+        assert!(code.contains("// prop: id"));
+    }
+
+    #[test]
+    fn test_dynamic_component_is_checks_expression() {
+        let code = template_code(r#"<component :is="tab" />"#);
+        assert!(!code.contains("__VLS_resolveComponent"));
+        assert!(code.contains("// prop: is"));
+        assert!(code.contains("(__VLS_ctx.tab);"));
+    }
+
+    #[test]
+    fn test_interpolation_must_be_renderable() {
+        let code = template_code("<p>Hello {{ name }}, you are {{ user }}</p>");
+        assert!(code.contains("__VLS_toDisplayString(__VLS_ctx.name);"));
+        assert!(code.contains("__VLS_toDisplayString(__VLS_ctx.user);"));
+
+        // A plain object matches no member of the renderable union
+        let helpers = crate::helpers::VLS_HELPER_TYPES;
+        assert!(helpers.contains("declare function __VLS_toDisplayString(value: __VLS_Renderable)"));
+        let renderable = &helpers[helpers.find("type __VLS_Renderable").unwrap()..];
+        let renderable = &renderable[..renderable.find(';').unwrap()];
+        assert!(!renderable.contains("object"));
+        assert!(!renderable.contains("Record<"));
+    }
+
+    #[test]
+    fn test_template_v_for_binds_alias() {
+        let code = template_code(r#"<template v-for="i in items">{{ i }}</template>"#);
+        assert!(code.contains("for (const [i] of __VLS_getVForSourceType(__VLS_ctx.items)) {"));
+        assert!(code.contains("(i);"));
+        assert!(!code.contains("__VLS_ctx.i)"));
+    }
+
+    #[test]
+    fn test_v_for_key_is_checked_in_loop_scope() {
+        let code = template_code(r#"<MyItem v-for="item in items" :key="item" :item="item" />"#);
+        let key = code.find("  // key\n").unwrap();
+        assert!(code[key..].contains("(item);"));
+        assert!(!code.contains("// prop: key"));
+        assert!(code.contains("// prop: item"));
+    }
+
+    #[test]
+    fn test_template_v_if_wraps_children() {
+        let code = template_code(r#"<template v-if="ok"><span>{{ msg }}</span></template>"#);
+        let condition = code.find("if (__VLS_ctx.ok) {").unwrap();
+        let interpolation = code.find("(__VLS_ctx.msg);").unwrap();
+        assert!(condition < interpolation);
+    }
+
+    #[test]
+    fn test_template_slot_props_are_scoped() {
+        let code = template_code(
+            r#"<MyList><template v-if="ok" #item="{ row }">{{ row }}</template></MyList>{{ row }}"#,
+        );
+        assert!(code.contains("(row);"));
+        assert!(code.contains("(__VLS_ctx.row);"));
+    }
+
+    #[test]
+    fn test_default_slot_props_are_scoped() {
+        let code = template_code(
+            r#"<MyList v-slot="{ item }"><span :title="item">{{ item }}</span></MyList>{{ item }}"#,
+        );
+        assert!(code.contains(
+            "const { item } = __VLS_getSlotParams(__VLS_componentSlots(__VLS_component_0)['default']);"
+        ));
+        assert!(code.contains("(item);"));
+        // Outside the component the name comes from the context again
+        assert!(code.contains("(__VLS_ctx.item);"));
+
+        // The slot content is checked inside the component's block
+        let declaration = code.find("const { item }").unwrap();
+        let interpolation = code.find("(item);").unwrap();
+        assert!(declaration < interpolation);
+    }
+
+    #[test]
+    fn test_named_slot_props_are_typed_from_component() {
+        let code =
+            template_code(r#"<MyList><template #row="{ item }">{{ item }}</template></MyList>"#);
+        assert!(code.contains(
+            "const { item } = __VLS_getSlotParams(__VLS_componentSlots(__VLS_component_0)['row']);"
+        ));
+        assert!(code.contains("(item);"));
+    }
+
+    #[test]
+    fn test_nested_slot_props_shadow_outer() {
+        let source = r#"<Outer><template #default="{ item }"><Inner><template #default="{ item }">{{ item }}</template></Inner>{{ item }}</template></Outer>"#;
+        let code = template_code(source);
+        let inner_id = source.find("<Inner>").unwrap();
+        let outer = code
+            .find("const { item } = __VLS_getSlotParams(__VLS_componentSlots(__VLS_component_0)['default']);")
+            .unwrap();
+        let inner = code
+            .find(&format!(
+                "const {{ item }} = __VLS_getSlotParams(__VLS_componentSlots(__VLS_component_{})['default']);",
+                inner_id
+            ))
+            .unwrap();
+        assert!(outer < inner);
+
+        // The inner use reads the inner slot's binding, in a block nested in
+        // the outer slot's; the later use is back in the outer block
+        let uses: Vec<_> = code.match_indices("(item);").map(|(i, _)| i).collect();
+        assert_eq!(uses.len(), 2);
+        assert!(inner < uses[0]);
+        assert!(code[uses[0]..uses[1]].contains("}\n"));
+        assert!(!code.contains("__VLS_ctx.item"));
+    }
+
+    #[test]
+    fn test_dynamic_slot_props_are_untyped() {
+        let code =
+            template_code(r#"<MyList><template #[name]="{ item }">{{ item }}</template></MyList>"#);
+        assert!(code.contains("const { item }: any = undefined;"));
+        assert!(code.contains("(item);"));
+    }
+
+    #[test]
+    fn test_default_slot_children_without_props() {
+        let code = template_code(r#"<MyButton #default>{{ label }}</MyButton>"#);
+        assert!(!code.contains("__VLS_componentSlots"));
+        let component = code.find("__VLS_resolveComponent('MyButton')").unwrap();
+        let interpolation = code.find("(__VLS_ctx.label);").unwrap();
+        assert!(component < interpolation);
+    }
+
+    #[test]
+    fn test_teleport_props_are_checked_exactly() {
+        let code =
+            template_code(r##"<Teleport to="#app" :disabled="off"><p>{{ msg }}</p></Teleport>"##);
+        assert!(!code.contains("__VLS_resolveComponent('Teleport')"));
+        let call = code.find("__VLS_builtinComponent('Teleport')({").unwrap();
+        assert!(code[call..].contains("'to': '#app',"));
+        assert!(code[call..].contains("'disabled': (__VLS_ctx.off),"));
+        // Children are checked inside the builtin's block
+        assert!(code.find("(__VLS_ctx.msg);").unwrap() > call);
+
+        // An unknown prop is an excess property of the literal
+        let code = template_code(r#"<teleport tO></teleport>"#);
+        let call = code.find("__VLS_builtinComponent('Teleport')({").unwrap();
+        assert!(code[call..].contains("'tO': true,"));
+    }
+
+    #[test]
+    fn test_transition_props_are_checked_exactly() {
+        let code = template_code(
+            r#"<transition name="fade" mode="out-in" enter-from-class="hidden" data-test="t" :duration="ms" @after-enter="done"><div /></transition>"#,
+        );
+        let call = code.find("__VLS_builtinComponent('Transition')({").unwrap();
+        let literal = &code[call..code[call..].find("});").unwrap() + call];
+        assert!(literal.contains("'name': 'fade',"));
+        assert!(literal.contains("'mode': 'out-in',"));
+        assert!(literal.contains("'enterFromClass': 'hidden',"));
+        assert!(literal.contains("'duration': (__VLS_ctx.ms),"));
+        // `data-*` attributes fall through rather than being props
+        assert!(!literal.contains("data"));
+        assert!(code.contains("// event: after-enter"));
+
+        let helpers = crate::helpers::VLS_HELPER_TYPES;
+        assert!(helpers.contains("mode?: 'in-out' | 'out-in' | 'default';"));
+        assert!(helpers.contains("to: string | Element | null | undefined;"));
+    }
+
+    #[test]
+    fn test_string_literal() {
+        assert_eq!(string_literal("#app"), "'#app'");
+        assert_eq!(string_literal("it's \\ \n"), r"'it\'s \\ \n'");
+    }
+
     #[test]
     fn test_extract_binding_names() {
         assert_eq!(extract_binding_names("item"), vec!["item"]);
@@ -0,0 +1,174 @@
+//! Comment diagnostics.
+//!
+//! `TODO`/`FIXME` comments are reported as hints, and `@vue-expect-error`
+//! works like `@ts-expect-error`: it suppresses errors on the next node, and
+//! is itself an error when that node has none.
+
+use crate::{Diagnostic, DiagnosticCode, Severity};
+use source_map::Span;
+use vue_parser::Sfc;
+use vue_template_compiler::{TemplateAst, TemplateNode};
+
+/// The marker that expects an error on the next node.
+const EXPECT_ERROR: &str = "@vue-expect-error";
+
+/// Report `TODO`/`FIXME` comments at the root of an SFC.
+pub fn check_sfc_comments(sfc: &Sfc) -> Vec<Diagnostic> {
+    sfc.comments
+        .iter()
+        .filter_map(|comment| todo_hint(&comment.content, comment.span))
+        .collect()
+}
+
+/// Report `TODO`/`FIXME` comments in a template.
+pub fn check_template_comments(ast: &TemplateAst) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    visit_siblings(&ast.children, &mut |nodes| {
+        for node in nodes {
+            if let TemplateNode::Comment(comment) = node {
+                diagnostics.extend(todo_hint(&comment.content, comment.span));
+            }
+        }
+    });
+    diagnostics
+}
+
+/// Apply `@vue-expect-error` comments to a template's diagnostics.
+///
+/// Errors inside the node following the comment are dropped. If that node has
+/// no errors, an [`DiagnosticCode::UnusedExpectError`] is reported instead.
+pub fn apply_expect_errors(ast: &TemplateAst, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+    let mut suppressed = vec![false; diagnostics.len()];
+    let mut unused = Vec::new();
+
+    visit_siblings(&ast.children, &mut |nodes| {
+        for (i, node) in nodes.iter().enumerate() {
+            let TemplateNode::Comment(comment) = node else {
+                continue;
+            };
+            if !comment.content.trim_start().starts_with(EXPECT_ERROR) {
+                continue;
+            }
+
+            let target = nodes[i + 1..].iter().find(|next| match next {
+                TemplateNode::Text(text) => !text.content.trim().is_empty(),
+                TemplateNode::Comment(_) => false,
+                _ => true,
+            });
+
+            let mut used = false;
+            if let Some(target) = target {
+                let span = target.span();
+                for (d, hidden) in diagnostics.iter().zip(suppressed.iter_mut()) {
+                    if d.severity == Severity::Error && span.contains(d.span) {
+                        *hidden = true;
+                        used = true;
+                    }
+                }
+            }
+
+            if !used {
+                unused.push(Diagnostic::error(
+                    format!("Unused '{}' directive", EXPECT_ERROR),
+                    comment.span,
+                    DiagnosticCode::UnusedExpectError,
+                ));
+            }
+        }
+    });
+
+    diagnostics
+        .into_iter()
+        .zip(suppressed)
+        .filter_map(|(d, hidden)| (!hidden).then_some(d))
+        .chain(unused)
+        .collect()
+}
+
+/// Create a hint for a comment containing a `TODO` or `FIXME` marker.
+fn todo_hint(content: &str, span: Span) -> Option<Diagnostic> {
+    let text = content.trim();
+    let marker = ["TODO", "FIXME"]
+        .into_iter()
+        .find(|marker| contains_word(text, marker))?;
+    let first_line = text.lines().next().unwrap_or(text);
+
+    Some(Diagnostic::hint(
+        format!("{} comment: {}", marker, first_line),
+        span,
+        DiagnosticCode::TodoComment,
+    ))
+}
+
+/// Check if `text` contains `word` delimited by non-identifier characters.
+fn contains_word(text: &str, word: &str) -> bool {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    text.match_indices(word).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + word.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
+
+/// Call `f` with every list of sibling nodes in the tree.
+pub(crate) fn visit_siblings(nodes: &[TemplateNode], f: &mut impl FnMut(&[TemplateNode])) {
+    f(nodes);
+    for node in nodes {
+        match node {
+            TemplateNode::Element(el) => visit_siblings(&el.children, f),
+            TemplateNode::If(i) => {
+                for branch in &i.branches {
+                    visit_siblings(&branch.children, f);
+                }
+            }
+            TemplateNode::For(node) => visit_siblings(&node.children, f),
+            TemplateNode::Template(t) => visit_siblings(&t.children, f),
+            TemplateNode::SlotOutlet(s) => visit_siblings(&s.fallback, f),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{template, DiagnosticOptions};
+    use vue_template_compiler::parse_template;
+
+    fn check(source: &str) -> Vec<Diagnostic> {
+        let ast = parse_template(source).unwrap();
+        let diagnostics = template::check_template(&ast, &DiagnosticOptions::default());
+        apply_expect_errors(&ast, diagnostics)
+    }
+
+    #[test]
+    fn test_todo_comment_hint() {
+        let ast = parse_template("<div><!-- TODO: add a footer --><!-- TODOS --></div>").unwrap();
+        let diagnostics = check_template_comments(&ast);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Hint);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::TodoComment);
+        assert!(diagnostics[0].message.contains("add a footer"));
+    }
+
+    #[test]
+    fn test_expect_error_suppresses_error() {
+        let diagnostics = check("<!-- @vue-expect-error -->\n<div v-model=\"value\" />");
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_unused_expect_error() {
+        let source = "<!-- @vue-expect-error -->\n<div />\n<div v-model=\"value\" />";
+        let diagnostics = check(source);
+        let codes: Vec<_> = diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                DiagnosticCode::InvalidVModel,
+                DiagnosticCode::UnusedExpectError
+            ]
+        );
+        assert_eq!(diagnostics[1].span.start, 0);
+    }
+}
@@ -1,15 +1,17 @@
 //! Component-level diagnostics.
 
+use crate::style::{tokenize_with_offsets, Token};
 use crate::{Diagnostic, DiagnosticCode, DiagnosticOptions};
 use source_map::Span;
-use vue_parser::Sfc;
+use vue_parser::{Sfc, SfcBlock};
 
 /// Check an SFC for component-level issues.
-pub fn check_sfc(sfc: &Sfc, _options: &DiagnosticOptions) -> Vec<Diagnostic> {
+pub fn check_sfc(sfc: &Sfc, options: &DiagnosticOptions) -> Vec<Diagnostic> {
     let mut diagnostics = Vec::new();
 
     // Check for multiple script setup blocks (already caught by parser)
     // Check for conflicting script types
+    diagnostics.extend(check_script_langs(sfc));
 
     // Check script setup content
     if let Some(script_setup) = &sfc.script_setup {
@@ -17,6 +19,13 @@ pub fn check_sfc(sfc: &Sfc, _options: &DiagnosticOptions) -> Vec<Diagnostic> {
             &script_setup.content,
             script_setup.content_span,
         ));
+        if let Some(target) = options.target {
+            diagnostics.extend(check_macro_versions(
+                &script_setup.content,
+                script_setup.content_span.start,
+                target,
+            ));
+        }
     }
 
     // Check for proper component structure
@@ -27,6 +36,80 @@ pub fn check_sfc(sfc: &Sfc, _options: &DiagnosticOptions) -> Vec<Diagnostic> {
     diagnostics
 }
 
+/// Check if the component keeps the default `inheritAttrs: true`.
+pub fn inherits_attrs(sfc: &Sfc) -> bool {
+    let scripts = [
+        sfc.script.as_ref().map(|s| s.content.as_str()),
+        sfc.script_setup.as_ref().map(|s| s.content.as_str()),
+    ];
+    !scripts.into_iter().flatten().any(disables_inherit_attrs)
+}
+
+/// Check if script content sets `inheritAttrs: false`.
+fn disables_inherit_attrs(content: &str) -> bool {
+    content.match_indices("inheritAttrs").any(|(i, m)| {
+        content[i + m.len()..]
+            .trim_start()
+            .strip_prefix(':')
+            .is_some_and(|rest| rest.trim_start().starts_with("false"))
+    })
+}
+
+/// Check that both script blocks use the same language, and that a generic
+/// component is written in TypeScript.
+fn check_script_langs(sfc: &Sfc) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let Some(setup) = &sfc.script_setup else {
+        return diagnostics;
+    };
+    let setup_lang = normalize_lang(setup.lang.as_deref());
+
+    if let Some(script) = &sfc.script {
+        let script_lang = normalize_lang(script.lang.as_deref());
+        if script_lang != setup_lang {
+            diagnostics.push(Diagnostic::error(
+                format!(
+                    "<script> and <script setup> must use the same language, found '{}' and '{}'",
+                    script_lang, setup_lang
+                ),
+                lang_span(&setup.block)
+                    .or_else(|| lang_span(&script.block))
+                    .unwrap_or(setup.span),
+                DiagnosticCode::ScriptLangMismatch,
+            ));
+        }
+    }
+
+    if setup.generic.is_some() && !matches!(setup_lang, "ts" | "tsx") {
+        diagnostics.push(Diagnostic::error(
+            "The generic attribute requires <script setup lang=\"ts\">",
+            setup.generic_span.unwrap_or(setup.span),
+            DiagnosticCode::GenericWithoutTs,
+        ));
+    }
+
+    diagnostics
+}
+
+/// Normalize a `lang` attribute, which defaults to JavaScript.
+fn normalize_lang(lang: Option<&str>) -> &str {
+    match lang {
+        None => "js",
+        Some("typescript") => "ts",
+        Some("javascript") => "js",
+        Some(lang) => lang,
+    }
+}
+
+/// Get the span of a block's `lang` attribute.
+fn lang_span(block: &SfcBlock) -> Option<Span> {
+    block
+        .attrs
+        .iter()
+        .find(|a| a.name == "lang")
+        .map(|a| a.span)
+}
+
 /// Check script setup content for issues.
 fn check_script_setup(content: &str, span: Span) -> Vec<Diagnostic> {
     let mut diagnostics = Vec::new();
@@ -81,6 +164,104 @@ fn check_script_setup(content: &str, span: Span) -> Vec<Diagnostic> {
         ));
     }
 
+    diagnostics.extend(check_reactivity_transform(content, span.start));
+
+    diagnostics
+}
+
+/// Macros added after Vue 3.0, with the version that added them.
+const MACRO_VERSIONS: &[(&str, f32)] = &[
+    ("defineOptions", 3.3),
+    ("defineSlots", 3.3),
+    ("defineModel", 3.4),
+];
+
+/// Report macros the target Vue version doesn't have yet.
+///
+/// Only calls count, like `defineModel()` or `defineSlots<...>()`; names in
+/// comments and strings, longer names and property accesses don't.
+/// `offset` is where the content starts in the SFC.
+fn check_macro_versions(content: &str, offset: u32, target: f32) -> Vec<Diagnostic> {
+    let tokens = tokenize_with_offsets(content);
+    let mut diagnostics = Vec::new();
+    for (i, &(start, token)) in tokens.iter().enumerate() {
+        let Token::Ident(name) = token else {
+            continue;
+        };
+        let Some(&(_, version)) = MACRO_VERSIONS
+            .iter()
+            .find(|(macro_name, _)| *macro_name == name)
+        else {
+            continue;
+        };
+        let is_call = matches!(tokens.get(i + 1), Some((_, Token::Punct('(' | '<'))));
+        let is_member = i > 0 && tokens[i - 1].1 == Token::Punct('.');
+        if target >= version || !is_call || is_member {
+            continue;
+        }
+        let end = start + name.len();
+        diagnostics.push(Diagnostic::error(
+            format!(
+                "{}() requires Vue {} or later, but the target is {}",
+                name, version, target
+            ),
+            Span::new(offset + start as u32, offset + end as u32),
+            DiagnosticCode::InvalidMacroUsage,
+        ));
+    }
+    diagnostics
+}
+
+/// Reactivity Transform macros, with what to use instead.
+const REACTIVITY_TRANSFORM_MACROS: &[(&str, &str)] = &[
+    ("$ref", "ref()"),
+    ("$shallowRef", "shallowRef()"),
+    ("$computed", "computed()"),
+    ("$customRef", "customRef()"),
+    ("$toRef", "toRef()"),
+    ("$", "toRefs() or reactive props destructure"),
+    ("$$", "the ref itself"),
+];
+
+/// Warn about Reactivity Transform macros, which Vue removed.
+///
+/// Only calls count, like `$ref(0)` or `$ref<number>()`, so other uses of `$`
+/// names don't warn. `offset` is where the content starts in the SFC.
+fn check_reactivity_transform(content: &str, offset: u32) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let bytes = content.as_bytes();
+    let is_ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'$';
+
+    for (start, _) in content.match_indices('$') {
+        // Only at the start of a name that isn't a property access
+        if start > 0 && (is_ident(bytes[start - 1]) || bytes[start - 1] == b'.') {
+            continue;
+        }
+        let end = start
+            + content[start..]
+                .bytes()
+                .take_while(|&b| is_ident(b))
+                .count();
+        let name = &content[start..end];
+        let Some((_, replacement)) = REACTIVITY_TRANSFORM_MACROS
+            .iter()
+            .find(|(macro_name, _)| *macro_name == name)
+        else {
+            continue;
+        };
+        if !content[end..].trim_start().starts_with(['(', '<']) {
+            continue;
+        }
+        diagnostics.push(Diagnostic::warning(
+            format!(
+                "Reactivity Transform was removed from Vue; use {} instead of {}()",
+                replacement, name
+            ),
+            Span::new(offset + start as u32, offset + end as u32),
+            DiagnosticCode::DeprecatedSyntax,
+        ));
+    }
+
     diagnostics
 }
 
@@ -152,6 +333,56 @@ mod tests {
         assert_eq!(diag.severity, Severity::Error);
     }
 
+    #[test]
+    fn test_inherits_attrs() {
+        let sfc = vue_parser::parse_sfc("<script setup>\nconst a = 1\n</script>").unwrap();
+        assert!(inherits_attrs(&sfc));
+
+        let source = "<script setup>\ndefineOptions({ inheritAttrs : false })\n</script>";
+        let sfc = vue_parser::parse_sfc(source).unwrap();
+        assert!(!inherits_attrs(&sfc));
+    }
+
+    fn diagnostics_for(source: &str) -> Vec<Diagnostic> {
+        let sfc = vue_parser::parse_sfc(source).unwrap();
+        check_sfc(&sfc, &DiagnosticOptions::default())
+    }
+
+    #[test]
+    fn test_script_lang_mismatch() {
+        let source = "<script lang=\"ts\">\nexport default {}\n</script>\n<script setup lang=\"js\">\nconst a = 1\n</script>";
+        let diagnostics = diagnostics_for(source);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::ScriptLangMismatch);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        let span = diagnostics[0].span;
+        assert_eq!(
+            &source[span.start as usize..span.end as usize],
+            "lang=\"js\""
+        );
+
+        // A missing lang is JavaScript
+        let source =
+            "<script lang=\"ts\">\nexport default {}\n</script>\n<script setup>\n</script>";
+        assert_eq!(diagnostics_for(source).len(), 1);
+
+        let source = "<script lang=\"ts\">\nexport default {}\n</script>\n<script setup lang=\"ts\">\n</script>";
+        assert!(diagnostics_for(source).is_empty());
+    }
+
+    #[test]
+    fn test_generic_without_ts() {
+        let source = "<script setup generic=\"T\">\ndefineProps()\n</script>";
+        let diagnostics = diagnostics_for(source);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::GenericWithoutTs);
+        let span = diagnostics[0].span;
+        assert_eq!(&source[span.start as usize..span.end as usize], "T");
+
+        let source = "<script setup lang=\"ts\" generic=\"T extends string\">\n</script>";
+        assert!(diagnostics_for(source).is_empty());
+    }
+
     #[test]
     fn test_multiple_define_props() {
         let content = "defineProps<{}>(); defineProps<{}>();";
@@ -160,4 +391,80 @@ mod tests {
             .iter()
             .any(|d| d.code == DiagnosticCode::DuplicateMacro));
     }
+
+    #[test]
+    fn test_reactivity_transform_warns() {
+        let source = "<script setup>\nlet count = $ref(0)\nconst double = $computed(() => count * 2)\nconst { a } = $(useThing())\n</script>";
+        let diagnostics = diagnostics_for(source);
+        let names: Vec<_> = diagnostics
+            .iter()
+            .map(|d| &source[d.span.start as usize..d.span.end as usize])
+            .collect();
+        assert_eq!(names, ["$ref", "$computed", "$"]);
+        assert!(
+            diagnostics
+                .iter()
+                .all(|d| d.severity == Severity::Warning
+                    && d.code == DiagnosticCode::DeprecatedSyntax)
+        );
+        assert!(diagnostics[0]
+            .message
+            .contains("use ref() instead of $ref()"));
+    }
+
+    #[test]
+    fn test_reactivity_transform_needs_a_call() {
+        let source = "<script setup>\nconst $ref = 1\nconst total = `$${price}`\nwatch(() => store.$ref(0))\nconst x = my$ref(1)\n</script>";
+        assert!(diagnostics_for(source).is_empty());
+    }
+
+    #[test]
+    fn test_macro_versions() {
+        let source = "<script setup lang=\"ts\">\nconst model = defineModel<string>()\ndefineSlots<{}>()\n</script>";
+        let sfc = vue_parser::parse_sfc(source).unwrap();
+        let check = |target| {
+            let options = DiagnosticOptions {
+                target,
+                ..Default::default()
+            };
+            check_sfc(&sfc, &options)
+        };
+
+        let diagnostics = check(Some(3.3));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::InvalidMacroUsage);
+        let span = diagnostics[0].span;
+        assert_eq!(
+            &source[span.start as usize..span.end as usize],
+            "defineModel"
+        );
+
+        assert_eq!(check(Some(3.0)).len(), 2);
+        assert!(check(Some(3.5)).is_empty());
+        assert!(check(None).is_empty());
+    }
+
+    #[test]
+    fn test_macro_versions_need_a_call() {
+        let content = "// defineModel() needs 3.4\nconst hint = 'defineSlots<'\n/* defineOptions({}) */\nconst m = mydefineModel()\nutils.defineModel()\nconst defineSlotsCount = 1\n";
+        assert!(check_macro_versions(content, 0, 3.0).is_empty());
+        assert_eq!(check_macro_versions("defineModel ()", 0, 3.0).len(), 1);
+    }
+
+    #[test]
+    fn test_vue_target_comment_overrides_target() {
+        let options = DiagnosticOptions {
+            target: Some(3.5),
+            ..Default::default()
+        };
+        let source = "<script setup lang=\"ts\">\nconst model = defineModel()\n</script>";
+        let sfc = vue_parser::parse_sfc(source).unwrap();
+        assert!(crate::diagnose_sfc(&sfc, &options).is_empty());
+
+        let pinned = format!("<!-- @vue-target 3.3 -->\n{}", source);
+        let sfc = vue_parser::parse_sfc(&pinned).unwrap();
+        let diagnostics = crate::diagnose_sfc(&sfc, &options);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::InvalidMacroUsage);
+    }
 }
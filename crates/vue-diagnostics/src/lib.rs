@@ -7,12 +7,22 @@
 //! - Event validation
 //! - Slot validation
 
+pub mod comments;
 pub mod component;
+pub mod registry;
+#[cfg(feature = "miette")]
+pub mod report;
+pub mod resolution;
+pub mod style;
 pub mod template;
 
-use source_map::Span;
+pub use registry::{ComponentInfo, ComponentRegistry, ComponentSource, SlotInfo};
+
+use source_map::{LineCol, LineIndex, Span};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use vue_parser::Sfc;
-use vue_template_compiler::TemplateAst;
+use vue_template_compiler::{CompileError, CompileErrorCode, ParseOptions, TemplateAst};
 
 /// A diagnostic message.
 #[derive(Debug, Clone)]
@@ -57,6 +67,27 @@ impl Diagnostic {
             code,
         }
     }
+
+    /// Create an error diagnostic from a template compile error.
+    pub fn from_compile_error(error: &CompileError) -> Self {
+        Self::error(error.message.clone(), error.span, error.code.into())
+    }
+
+    /// Move the diagnostic's span forward by `offset` bytes.
+    pub fn shift(mut self, offset: u32) -> Self {
+        self.span = self.span.shift(offset.into());
+        self
+    }
+
+    /// Get the start and end line/column of the diagnostic.
+    ///
+    /// The end is exclusive, like `span.end`.
+    pub fn location(&self, index: &LineIndex) -> (LineCol, LineCol) {
+        (
+            index.line_col(self.span.start),
+            index.line_col(self.span.end),
+        )
+    }
 }
 
 /// Diagnostic severity levels.
@@ -85,6 +116,8 @@ impl Severity {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DiagnosticCode {
     // Template diagnostics
+    /// Template syntax error.
+    InvalidTemplate,
     /// Unknown component.
     UnknownComponent,
     /// Unknown directive.
@@ -93,8 +126,14 @@ pub enum DiagnosticCode {
     InvalidVFor,
     /// Invalid v-model syntax.
     InvalidVModel,
+    /// v-model bound directly to a `v-for` alias or slot prop.
+    VModelOnScopeVar,
+    /// `v-else`/`v-else-if` without an adjacent `v-if`.
+    InvalidVIfChain,
     /// Missing required prop.
     MissingProp,
+    /// Unknown prop passed to a component.
+    UnknownProp,
     /// Invalid prop type.
     InvalidPropType,
     /// Unknown event.
@@ -105,6 +144,22 @@ pub enum DiagnosticCode {
     DuplicateKey,
     /// Missing key in v-for.
     MissingKey,
+    /// `:key` on the wrong side of a `<template v-for>` for the Vue version.
+    MisplacedKey,
+    /// The loop index used as the v-for `:key`.
+    IndexAsKey,
+    /// Two elements with the same static `ref` outside `v-for`.
+    DuplicateRef,
+    /// `:ref` bound to an undeclared identifier.
+    UnknownRef,
+    /// `{ expr }` in text, likely meant as `{{ expr }}`.
+    SingleBraceInterpolation,
+    /// `{{ expr }}` in a static attribute value, which Vue renders literally.
+    InterpolationInAttribute,
+    /// `TODO`/`FIXME` comment.
+    TodoComment,
+    /// `@vue-expect-error` on a node without errors.
+    UnusedExpectError,
 
     // Component diagnostics
     /// Invalid component name.
@@ -115,42 +170,110 @@ pub enum DiagnosticCode {
     InvalidPropsDefinition,
     /// Invalid emits definition.
     InvalidEmitsDefinition,
+    /// Attributes can't fall through to a multi-root template.
+    MultiRootAttrs,
 
     // Script diagnostics
     /// Invalid macro usage.
     InvalidMacroUsage,
     /// Duplicate macro.
     DuplicateMacro,
+    /// `<script>` and `<script setup>` declare different languages.
+    ScriptLangMismatch,
+    /// `generic` on a `<script setup>` that isn't TypeScript.
+    GenericWithoutTs,
+    /// Syntax Vue no longer supports, such as Reactivity Transform macros.
+    DeprecatedSyntax,
 
     // Style diagnostics
+    /// Unknown binding in a style `v-bind()`.
+    UnknownStyleBinding,
     /// Unused CSS selector.
     UnusedSelector,
     /// Invalid deep selector.
     InvalidDeepSelector,
+
+    // Code generation diagnostics
+    /// Part of the SFC couldn't be turned into virtual TypeScript, so it
+    /// isn't type checked.
+    CodegenError,
 }
 
 impl DiagnosticCode {
     /// Get the code as a string.
     pub fn as_str(&self) -> &'static str {
         match self {
+            Self::InvalidTemplate => "invalid-template",
             Self::UnknownComponent => "unknown-component",
             Self::UnknownDirective => "unknown-directive",
             Self::InvalidVFor => "invalid-v-for",
             Self::InvalidVModel => "invalid-v-model",
+            Self::VModelOnScopeVar => "v-model-on-scope-var",
+            Self::InvalidVIfChain => "invalid-v-if-chain",
             Self::MissingProp => "missing-prop",
+            Self::UnknownProp => "unknown-prop",
             Self::InvalidPropType => "invalid-prop-type",
             Self::UnknownEvent => "unknown-event",
             Self::InvalidSlot => "invalid-slot",
             Self::DuplicateKey => "duplicate-key",
             Self::MissingKey => "missing-key",
+            Self::MisplacedKey => "misplaced-key",
+            Self::IndexAsKey => "index-as-key",
+            Self::DuplicateRef => "duplicate-ref",
+            Self::UnknownRef => "unknown-ref",
+            Self::SingleBraceInterpolation => "single-brace-interpolation",
+            Self::InterpolationInAttribute => "interpolation-in-attribute",
+            Self::TodoComment => "todo-comment",
+            Self::UnusedExpectError => "unused-expect-error",
             Self::InvalidComponentName => "invalid-component-name",
             Self::MissingOption => "missing-option",
             Self::InvalidPropsDefinition => "invalid-props-definition",
             Self::InvalidEmitsDefinition => "invalid-emits-definition",
+            Self::MultiRootAttrs => "multi-root-attrs",
             Self::InvalidMacroUsage => "invalid-macro-usage",
             Self::DuplicateMacro => "duplicate-macro",
+            Self::ScriptLangMismatch => "script-lang-mismatch",
+            Self::GenericWithoutTs => "generic-without-ts",
+            Self::DeprecatedSyntax => "deprecated-syntax",
+            Self::UnknownStyleBinding => "unknown-style-binding",
             Self::UnusedSelector => "unused-selector",
             Self::InvalidDeepSelector => "invalid-deep-selector",
+            Self::CodegenError => "codegen-error",
+        }
+    }
+
+    /// Get the TypeScript error codes that report the same problem.
+    ///
+    /// The generated code makes tsc report some template problems too, so a
+    /// TypeScript error with one of these codes at the same place is a
+    /// duplicate of this diagnostic.
+    pub fn ts_codes(&self) -> &'static [u32] {
+        match self {
+            // Property '{0}' is missing in type '{1}' but required in type '{2}'
+            Self::MissingProp => &[2741, 2739],
+            // Object literal may only specify known properties
+            Self::UnknownProp => &[2353, 2561],
+            // Type '{0}' is not assignable to type '{1}'
+            Self::InvalidPropType => &[2322, 2345],
+            _ => &[],
+        }
+    }
+}
+
+impl From<CompileErrorCode> for DiagnosticCode {
+    fn from(code: CompileErrorCode) -> Self {
+        match code {
+            CompileErrorCode::InvalidVFor => Self::InvalidVFor,
+            CompileErrorCode::InvalidVModel => Self::InvalidVModel,
+            CompileErrorCode::InvalidVIfChain => Self::InvalidVIfChain,
+            CompileErrorCode::InvalidSlot => Self::InvalidSlot,
+            CompileErrorCode::ComponentResolution => Self::UnknownComponent,
+            CompileErrorCode::InvalidDirective
+            | CompileErrorCode::InvalidExpression
+            | CompileErrorCode::UnexpectedToken
+            | CompileErrorCode::UnclosedElement
+            | CompileErrorCode::MissingAttribute
+            | CompileErrorCode::NestingTooDeep => Self::InvalidTemplate,
         }
     }
 }
@@ -164,27 +287,119 @@ pub struct DiagnosticOptions {
     pub check_unknown_directives: bool,
     /// Check for missing keys in v-for.
     pub check_v_for_keys: bool,
-    /// Known component names.
-    pub known_components: Vec<String>,
+    /// Check for props not declared by known components.
+    pub check_unknown_props: bool,
+    /// Known components, with their declared props and events.
+    pub components: ComponentRegistry,
     /// Known directive names.
     pub known_directives: Vec<String>,
+    /// Generated `components.d.ts` declaring auto-imported global components.
+    pub global_components_dts: Option<PathBuf>,
+    /// Template parsing options (custom element tags).
+    pub parse_options: ParseOptions,
+    /// Target Vue version (`vueCompilerOptions.target`), Vue 3 if unset.
+    pub target: Option<f32>,
+    /// Run accessibility checks.
+    pub check_a11y: bool,
+    /// Severities by code name (`missing-key`); `None` turns a code off.
+    pub severity_overrides: HashMap<String, Option<Severity>>,
+}
+
+impl DiagnosticOptions {
+    /// Check if the target is Vue 2.
+    pub fn is_vue2(&self) -> bool {
+        self.target.is_some_and(|target| target < 3.0)
+    }
+
+    /// Apply `severity_overrides`, dropping diagnostics whose code is off.
+    pub fn apply_severity_overrides(&self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        if self.severity_overrides.is_empty() {
+            return diagnostics;
+        }
+        diagnostics
+            .into_iter()
+            .filter_map(|mut d| {
+                if let Some(&severity) = self.severity_overrides.get(d.code.as_str()) {
+                    d.severity = severity?;
+                }
+                Some(d)
+            })
+            .collect()
+    }
+
+    /// Register the components declared in `global_components_dts`.
+    ///
+    /// The names are also parsed as components, even when lowercase.
+    pub fn load_global_components(&mut self) -> std::io::Result<()> {
+        if let Some(path) = &self.global_components_dts {
+            let global = ComponentRegistry::load_global_components(path)?;
+            for info in global.iter() {
+                self.parse_options.component_tags.push(info.name.clone());
+                self.components.insert(info.clone());
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Run diagnostics on an SFC.
+///
+/// A `<!-- @vue-target 3.3 -->` comment at the root of the SFC overrides
+/// `options.target` for it.
 pub fn diagnose_sfc(sfc: &Sfc, options: &DiagnosticOptions) -> Vec<Diagnostic> {
+    let pinned;
+    let options = match sfc.vue_target() {
+        Some(target) => {
+            pinned = DiagnosticOptions {
+                target: Some(target),
+                ..options.clone()
+            };
+            &pinned
+        }
+        None => options,
+    };
     let mut diagnostics = Vec::new();
 
     // Component-level diagnostics
     diagnostics.extend(component::check_sfc(sfc, options));
 
+    // Style diagnostics
+    diagnostics.extend(style::check_styles(sfc));
+    diagnostics.extend(style::check_use_css_vars(sfc));
+
+    // Root-level comments
+    diagnostics.extend(comments::check_sfc_comments(sfc));
+
     // Template diagnostics
     if let Some(template) = &sfc.template {
-        if let Ok(ast) = vue_template_compiler::parse_template(&template.content) {
-            diagnostics.extend(template::check_template(&ast, options));
+        let mut template_diagnostics = Vec::new();
+        match vue_template_compiler::parse_template_with_options(
+            &template.content,
+            &options.parse_options,
+        ) {
+            Ok(ast) => {
+                let bindings = style::setup_bindings(sfc);
+                template_diagnostics.extend(template::check_template_with_bindings(
+                    &ast,
+                    options,
+                    bindings.as_ref(),
+                ));
+                template_diagnostics.extend(template::check_refs(&ast, bindings.as_ref()));
+                if component::inherits_attrs(sfc) {
+                    template_diagnostics.extend(template::check_attrs_fallthrough(&ast));
+                }
+                template_diagnostics.extend(comments::check_template_comments(&ast));
+                template_diagnostics = comments::apply_expect_errors(&ast, template_diagnostics);
+            }
+            Err(error) => template_diagnostics.push(Diagnostic::from_compile_error(&error)),
         }
+
+        // Template spans are relative to the template content
+        let offset = template.content_span.start;
+        diagnostics.extend(template_diagnostics.into_iter().map(|d| d.shift(offset)));
     }
 
-    diagnostics
+    options.apply_severity_overrides(diagnostics)
 }
 
 /// Run diagnostics on a template AST.
@@ -204,6 +419,144 @@ mod tests {
         assert!(diagnostics.is_empty());
     }
 
+    #[test]
+    fn test_invalid_v_for_is_reported() {
+        let source = r#"<script setup>
+const items = []
+</script>
+
+<template>
+  <li v-for="(a, b, c, d) in items">{{ a }}</li>
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let diagnostics = diagnose_sfc(&sfc, &DiagnosticOptions::default());
+        let v_for: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.code == DiagnosticCode::InvalidVFor)
+            .collect();
+        assert_eq!(v_for.len(), 1);
+        assert_eq!(v_for[0].severity, Severity::Error);
+
+        let start = source.find("(a, b, c, d)").unwrap() as u32;
+        assert_eq!(v_for[0].span, Span::new(start, start + 12));
+    }
+
+    #[test]
+    fn test_template_diagnostic_uses_sfc_offset() {
+        let source = r#"<script setup>
+const items = [1, 2]
+</script>
+
+<template>
+  <ul>
+    <li v-for="item in items">{{ item }}</li>
+  </ul>
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let options = DiagnosticOptions {
+            check_v_for_keys: true,
+            ..Default::default()
+        };
+        let diagnostics = diagnose_sfc(&sfc, &options);
+        let missing_key = diagnostics
+            .iter()
+            .find(|d| d.code == DiagnosticCode::MissingKey)
+            .unwrap();
+
+        let template_start = sfc.template.as_ref().unwrap().content_span.start;
+        assert!(missing_key.span.start >= template_start);
+        assert!(source[missing_key.span.to_range()].starts_with("<li"));
+    }
+
+    #[test]
+    fn test_global_components_are_known() {
+        let dir = tempfile::tempdir().unwrap();
+        let dts = dir.path().join("components.d.ts");
+        std::fs::write(
+            &dts,
+            r#"declare module 'vue' {
+  export interface GlobalComponents {
+    AppHeader: typeof import('./src/components/AppHeader.vue')['default']
+  }
+}
+"#,
+        )
+        .unwrap();
+
+        let mut options = DiagnosticOptions {
+            check_unknown_components: true,
+            global_components_dts: Some(dts),
+            ..Default::default()
+        };
+        options.load_global_components().unwrap();
+
+        let sfc = parse_sfc("<template><app-header /><AppFooter /></template>").unwrap();
+        let unknown: Vec<_> = diagnose_sfc(&sfc, &options)
+            .into_iter()
+            .filter(|d| d.code == DiagnosticCode::UnknownComponent)
+            .collect();
+        assert_eq!(unknown.len(), 1);
+        assert!(unknown[0].message.contains("<AppFooter>"));
+    }
+
+    #[test]
+    fn test_expect_error_in_sfc() {
+        let source = r#"<!-- TODO: split this component -->
+<template>
+  <main>
+    <!-- @vue-expect-error -->
+    <div v-model="value" />
+    <!-- @vue-expect-error -->
+    <span />
+  </main>
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let diagnostics = diagnose_sfc(&sfc, &DiagnosticOptions::default());
+        let codes: Vec<_> = diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                DiagnosticCode::TodoComment,
+                DiagnosticCode::UnusedExpectError
+            ]
+        );
+
+        let unused = &diagnostics[1];
+        let second = source.rfind("<!-- @vue-expect-error -->").unwrap() as u32;
+        assert_eq!(unused.span.start, second);
+    }
+
+    #[test]
+    fn test_compile_error_code_mapping() {
+        assert_eq!(
+            DiagnosticCode::from(CompileErrorCode::InvalidVFor),
+            DiagnosticCode::InvalidVFor
+        );
+        assert_eq!(
+            DiagnosticCode::from(CompileErrorCode::UnexpectedToken),
+            DiagnosticCode::InvalidTemplate
+        );
+    }
+
+    #[test]
+    fn test_location_on_line_three() {
+        let source = "<div>\n  <p>\n    {{ missing }}\n  </p>\n</div>";
+        let start = source.find("missing").unwrap() as u32;
+        let diagnostic = Diagnostic::error(
+            "Unknown identifier",
+            Span::new(start, start + "missing".len() as u32),
+            DiagnosticCode::UnknownComponent,
+        );
+
+        let (start, end) = diagnostic.location(&LineIndex::new(source));
+        assert_eq!(start, LineCol::new(2, 7));
+        assert_eq!(end, LineCol::new(2, 14));
+        assert_eq!(end.to_display(), (3, 15));
+    }
+
     #[test]
     fn test_diagnose_valid_sfc() {
         let source = r#"<script setup>
@@ -219,4 +572,154 @@ const msg = 'Hello'
         // Should have no errors for a valid SFC
         assert!(diagnostics.iter().all(|d| d.severity != Severity::Error));
     }
+
+    #[test]
+    fn test_severity_overrides() {
+        let source = r#"<template>
+  <!-- TODO: add a caption -->
+  <li v-for="item in items">{{ item }}</li>
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let options = DiagnosticOptions {
+            check_v_for_keys: true,
+            severity_overrides: HashMap::from([
+                ("missing-key".to_string(), None),
+                ("todo-comment".to_string(), Some(Severity::Warning)),
+            ]),
+            ..Default::default()
+        };
+        let diagnostics = diagnose_sfc(&sfc, &options);
+        assert!(!diagnostics
+            .iter()
+            .any(|d| d.code == DiagnosticCode::MissingKey));
+        let todo = diagnostics
+            .iter()
+            .find(|d| d.code == DiagnosticCode::TodoComment)
+            .unwrap();
+        assert_eq!(todo.severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_duplicate_ref() {
+        let source = r#"<template>
+  <input ref="field" />
+  <textarea ref="field" />
+  <span v-if="a" ref="label" />
+  <span v-else ref="label" />
+  <li v-for="item in items" ref="rows" />
+  <li v-for="item in items" ref="rows" />
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let duplicates: Vec<_> = diagnose_sfc(&sfc, &DiagnosticOptions::default())
+            .into_iter()
+            .filter(|d| d.code == DiagnosticCode::DuplicateRef)
+            .collect();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].severity, Severity::Warning);
+        assert_eq!(&source[duplicates[0].span.to_range()], r#"ref="field""#);
+        assert!(source[..duplicates[0].span.start as usize].contains("<textarea"));
+    }
+
+    #[test]
+    fn test_undeclared_ref_binding() {
+        let source = r#"<script setup>
+import { ref } from 'vue'
+const input = ref()
+</script>
+
+<template>
+  <input :ref="input" />
+  <input :ref="inptu" />
+  <input :ref="(el) => input = el" />
+  <li v-for="item in items" :ref="item" />
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let unknown: Vec<_> = diagnose_sfc(&sfc, &DiagnosticOptions::default())
+            .into_iter()
+            .filter(|d| d.code == DiagnosticCode::UnknownRef)
+            .collect();
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(unknown[0].severity, Severity::Error);
+        assert_eq!(&source[unknown[0].span.to_range()], r#":ref="inptu""#);
+    }
+
+    fn unknown_components(source: &str, options: &DiagnosticOptions) -> Vec<Diagnostic> {
+        let sfc = parse_sfc(source).unwrap();
+        diagnose_sfc(&sfc, options)
+            .into_iter()
+            .filter(|d| d.code == DiagnosticCode::UnknownComponent)
+            .collect()
+    }
+
+    #[test]
+    fn test_unimported_component_in_script_setup() {
+        let source = r#"<script setup>
+const msg = 'hi'
+</script>
+
+<template>
+  <Foo :msg="msg" />
+</template>
+"#;
+        // Runs without `check_unknown_components`
+        let unknown = unknown_components(source, &DiagnosticOptions::default());
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(
+            unknown[0].message,
+            "Component <Foo> is used but not imported in script setup"
+        );
+        assert_eq!(&source[unknown[0].span.to_range()], "Foo");
+    }
+
+    #[test]
+    fn test_imported_components_are_known() {
+        let source = r#"<script setup>
+import Foo from './Foo.vue'
+import FooBar from './FooBar.vue'
+import * as Form from './form'
+</script>
+
+<template>
+  <Foo />
+  <foo-bar />
+  <Form.Input />
+  <Transition><KeepAlive /></Transition>
+</template>
+"#;
+        assert!(unknown_components(source, &DiagnosticOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_global_components_need_no_import() {
+        let dir = tempfile::tempdir().unwrap();
+        let dts = dir.path().join("components.d.ts");
+        std::fs::write(
+            &dts,
+            r#"declare module 'vue' {
+  export interface GlobalComponents {
+    AppHeader: typeof import('./src/components/AppHeader.vue')['default']
+  }
+}
+"#,
+        )
+        .unwrap();
+        let mut options = DiagnosticOptions {
+            global_components_dts: Some(dts),
+            ..Default::default()
+        };
+        options.load_global_components().unwrap();
+
+        let source = r#"<script setup>
+</script>
+
+<template>
+  <AppHeader />
+  <app-header />
+</template>
+"#;
+        assert!(unknown_components(source, &options).is_empty());
+    }
 }
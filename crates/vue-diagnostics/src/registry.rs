@@ -0,0 +1,364 @@
+//! Known component registration.
+//!
+//! Components can be referenced as `<my-comp>`, `<MyComp>` or `<myComp>` in a
+//! template, so the registry normalizes names once at insertion and lookups
+//! are a single hash probe.
+
+use rustc_hash::FxHashMap;
+use std::path::Path;
+
+/// Metadata about a known component.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComponentInfo {
+    /// The name the component was registered with.
+    pub name: String,
+    /// Declared (camelCase) props, if known.
+    pub props: Option<Vec<String>>,
+    /// Declared events, if known.
+    pub emits: Option<Vec<String>>,
+    /// Declared slots, if known.
+    pub slots: Option<Vec<SlotInfo>>,
+    /// Where the component is imported from, for auto-imported components.
+    pub source: Option<ComponentSource>,
+}
+
+/// A slot declared by a component.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotInfo {
+    /// The slot name.
+    pub name: String,
+    /// Whether the slot must be provided.
+    pub required: bool,
+}
+
+impl SlotInfo {
+    /// Create an optional slot.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            required: false,
+        }
+    }
+
+    /// Create a required slot.
+    pub fn required(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            required: true,
+        }
+    }
+
+    /// Parse the slots declared by a `defineSlots` type argument.
+    ///
+    /// `{ header(props: { title: string }): any; default?: () => any }`
+    /// declares a required `header` slot and an optional `default` slot.
+    pub fn parse_define_slots(type_arg: &str) -> Vec<Self> {
+        let Some(body) = interface_body(type_arg) else {
+            return Vec::new();
+        };
+
+        let mut slots = Vec::new();
+        let mut depth = 0;
+        let mut member_start = 0;
+        let mut prev = ' ';
+        for (i, c) in body.char_indices().chain([(body.len(), ';')]) {
+            match c {
+                '{' | '(' | '[' | '<' => depth += 1,
+                // `=>` doesn't close anything
+                '>' if prev == '=' => {}
+                '}' | ')' | ']' | '>' => depth -= 1,
+                ';' | ',' | '\n' if depth == 0 => {
+                    slots.extend(parse_slot_member(&body[member_start..i]));
+                    member_start = i + 1;
+                }
+                _ => {}
+            }
+            prev = c;
+        }
+        slots
+    }
+}
+
+/// The module export a component resolves to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentSource {
+    /// The module specifier, as written in the declaration.
+    pub module: String,
+    /// The exported name (`default` for SFCs).
+    pub export: String,
+}
+
+impl ComponentInfo {
+    /// Create component info without prop or emit metadata.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the declared props.
+    pub fn with_props<S: Into<String>>(mut self, props: impl IntoIterator<Item = S>) -> Self {
+        self.props = Some(props.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the declared events.
+    pub fn with_emits<S: Into<String>>(mut self, emits: impl IntoIterator<Item = S>) -> Self {
+        self.emits = Some(emits.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the declared slots.
+    pub fn with_slots(mut self, slots: impl IntoIterator<Item = SlotInfo>) -> Self {
+        self.slots = Some(slots.into_iter().collect());
+        self
+    }
+}
+
+/// Known components, looked up case- and hyphen-insensitively.
+#[derive(Debug, Clone, Default)]
+pub struct ComponentRegistry {
+    components: FxHashMap<String, ComponentInfo>,
+}
+
+impl ComponentRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a component, replacing any previous entry with the same name.
+    pub fn insert(&mut self, info: ComponentInfo) {
+        self.components.insert(normalize(&info.name), info);
+    }
+
+    /// Check if a component is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.components.contains_key(&normalize(name))
+    }
+
+    /// Get a registered component.
+    pub fn get(&self, name: &str) -> Option<&ComponentInfo> {
+        self.components.get(&normalize(name))
+    }
+
+    /// Parse the `GlobalComponents` interface of a generated `components.d.ts`.
+    ///
+    /// Each member looks like
+    /// `HelloWorld: typeof import('./src/HelloWorld.vue')['default']`.
+    pub fn parse_global_components(content: &str) -> Self {
+        let mut registry = Self::new();
+
+        let Some(body) = content
+            .find("interface GlobalComponents")
+            .and_then(|start| interface_body(&content[start..]))
+        else {
+            return registry;
+        };
+
+        for line in body.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let name = key.trim().trim_matches(|c| c == '\'' || c == '"');
+            if name.is_empty() || name.starts_with("//") {
+                continue;
+            }
+            let mut info = ComponentInfo::new(name);
+            info.source = parse_type_import(value);
+            registry.insert(info);
+        }
+
+        registry
+    }
+
+    /// Read and parse a `components.d.ts` file.
+    pub fn load_global_components(path: &Path) -> std::io::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(Self::parse_global_components(&content))
+    }
+
+    /// Iterate over the registered components.
+    pub fn iter(&self) -> impl Iterator<Item = &ComponentInfo> {
+        self.components.values()
+    }
+
+    /// Get the number of registered components.
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Check if the registry is empty.
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for ComponentRegistry {
+    fn from_iter<I: IntoIterator<Item = S>>(names: I) -> Self {
+        let mut registry = Self::new();
+        registry.extend(names);
+        registry
+    }
+}
+
+impl<S: Into<String>> Extend<S> for ComponentRegistry {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, names: I) {
+        for name in names {
+            self.insert(ComponentInfo::new(name));
+        }
+    }
+}
+
+/// Get the text between the first `{` and its matching `}`.
+fn interface_body(content: &str) -> Option<&str> {
+    let open = content.find('{')?;
+    let mut depth = 0;
+    for (i, c) in content[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&content[open + 1..open + i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parse `typeof import('module')['export']`.
+fn parse_type_import(value: &str) -> Option<ComponentSource> {
+    let rest = &value[value.find("import(")? + "import(".len()..];
+    let (module, rest) = quoted(rest.trim_start())?;
+    let rest = rest.trim_start().strip_prefix(')')?.trim_start();
+    let export = match rest.strip_prefix('[') {
+        Some(rest) => quoted(rest.trim_start())?.0,
+        None => "default",
+    };
+    Some(ComponentSource {
+        module: module.to_string(),
+        export: export.to_string(),
+    })
+}
+
+/// Parse a `defineSlots` member such as `header?: (props: P) => any`.
+fn parse_slot_member(member: &str) -> Option<SlotInfo> {
+    let member = member.trim();
+    let (name, rest) = quoted(member).unwrap_or_else(|| {
+        let end = member
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '-')))
+            .unwrap_or(member.len());
+        member.split_at(end)
+    });
+    let rest = rest.trim_start();
+    if name.is_empty() || !rest.starts_with(['?', ':', '(']) {
+        return None;
+    }
+    Some(SlotInfo {
+        name: name.to_string(),
+        required: !rest.starts_with('?'),
+    })
+}
+
+/// Split a leading quoted string from the rest of the input.
+fn quoted(input: &str) -> Option<(&str, &str)> {
+    let quote = input.chars().next().filter(|c| matches!(c, '\'' | '"'))?;
+    let end = input[1..].find(quote)? + 1;
+    Some((&input[1..end], &input[end + 1..]))
+}
+
+/// Normalize a component name (`my-comp`, `MyComp` and `myComp` are equal).
+pub(crate) fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|&c| c != '-')
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kebab_and_pascal_are_equivalent() {
+        let registry = ComponentRegistry::from_iter(["MyButton", "base-card"]);
+        assert!(registry.contains("my-button"));
+        assert!(registry.contains("MyButton"));
+        assert!(registry.contains("myButton"));
+        assert!(registry.contains("BaseCard"));
+        assert!(!registry.contains("MyCard"));
+        assert_eq!(registry.len(), 2);
+    }
+
+    #[test]
+    fn test_metadata_lookup() {
+        let mut registry = ComponentRegistry::new();
+        registry.insert(
+            ComponentInfo::new("UserCard")
+                .with_props(["userName"])
+                .with_emits(["select"]),
+        );
+
+        let info = registry.get("user-card").unwrap();
+        assert_eq!(info.name, "UserCard");
+        assert_eq!(info.props.as_deref(), Some(&["userName".to_string()][..]));
+        assert_eq!(info.emits.as_deref(), Some(&["select".to_string()][..]));
+    }
+
+    #[test]
+    fn test_parse_global_components() {
+        let dts = r#"/* eslint-disable */
+// Generated by unplugin-vue-components
+export {}
+
+declare module 'vue' {
+  export interface GlobalComponents {
+    HelloWorld: typeof import('./src/components/HelloWorld.vue')['default']
+    RouterLink: typeof import('vue-router')['RouterLink']
+    'IconHome': typeof import("~icons/mdi/home")["default"];
+  }
+}
+"#;
+        let registry = ComponentRegistry::parse_global_components(dts);
+        assert_eq!(registry.len(), 3);
+        assert!(registry.contains("hello-world"));
+        assert!(registry.contains("icon-home"));
+
+        let link = registry.get("router-link").unwrap();
+        assert_eq!(
+            link.source,
+            Some(ComponentSource {
+                module: "vue-router".to_string(),
+                export: "RouterLink".to_string(),
+            })
+        );
+        let hello = registry.get("HelloWorld").unwrap().source.as_ref().unwrap();
+        assert_eq!(hello.module, "./src/components/HelloWorld.vue");
+        assert_eq!(hello.export, "default");
+    }
+
+    #[test]
+    fn test_parse_define_slots() {
+        let slots = SlotInfo::parse_define_slots(
+            "{\n  header(props: { title: string }): any\n  default?: (props: { item: T }) => any; 'list-item'?(): any }",
+        );
+        assert_eq!(
+            slots,
+            vec![
+                SlotInfo::required("header"),
+                SlotInfo::new("default"),
+                SlotInfo::new("list-item"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_without_global_components() {
+        let registry = ComponentRegistry::parse_global_components("export {}");
+        assert!(registry.is_empty());
+    }
+}
@@ -0,0 +1,98 @@
+//! Rendering Vue diagnostics through miette.
+
+use crate::{Diagnostic, Severity};
+use miette::{LabeledSpan, NamedSource, SourceCode, SourceSpan};
+use std::fmt;
+
+/// A [`Diagnostic`] paired with its file source, renderable by miette.
+#[derive(Debug)]
+pub struct DiagnosticReport {
+    /// The underlying diagnostic.
+    pub diagnostic: Diagnostic,
+    /// The named source the diagnostic's span points into.
+    source: NamedSource<String>,
+}
+
+impl DiagnosticReport {
+    /// Create a report for a diagnostic in the given file.
+    pub fn new(diagnostic: Diagnostic, file_name: impl AsRef<str>, source: String) -> Self {
+        Self {
+            diagnostic,
+            source: NamedSource::new(file_name, source),
+        }
+    }
+
+    /// Get the span of the diagnostic as a miette span.
+    pub fn source_span(&self) -> SourceSpan {
+        let span = self.diagnostic.span;
+        SourceSpan::from((span.start as usize, span.len() as usize))
+    }
+}
+
+impl fmt::Display for DiagnosticReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.diagnostic.message)
+    }
+}
+
+impl std::error::Error for DiagnosticReport {}
+
+impl miette::Diagnostic for DiagnosticReport {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new(self.diagnostic.code.as_str()))
+    }
+
+    fn severity(&self) -> Option<miette::Severity> {
+        Some(match self.diagnostic.severity {
+            Severity::Error => miette::Severity::Error,
+            Severity::Warning => miette::Severity::Warning,
+            Severity::Hint => miette::Severity::Advice,
+        })
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.source)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        Some(Box::new(std::iter::once(LabeledSpan::underline(
+            self.source_span(),
+        ))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DiagnosticCode;
+    use source_map::Span;
+
+    #[test]
+    fn test_report_maps_code_and_severity() {
+        let diagnostic = Diagnostic::warning(
+            "v-for is missing a :key attribute",
+            Span::new(4, 9),
+            DiagnosticCode::MissingKey,
+        );
+        let report = DiagnosticReport::new(diagnostic, "App.vue", "abc\nhello".to_string());
+
+        let code = miette::Diagnostic::code(&report).unwrap().to_string();
+        assert_eq!(code, "missing-key");
+        assert_eq!(
+            miette::Diagnostic::severity(&report),
+            Some(miette::Severity::Warning)
+        );
+        assert_eq!(report.source_span(), SourceSpan::from((4, 5)));
+        assert_eq!(report.to_string(), "v-for is missing a :key attribute");
+    }
+
+    #[test]
+    fn test_hint_maps_to_advice() {
+        let diagnostic = Diagnostic::hint("hint", Span::empty(0), DiagnosticCode::MissingOption);
+        let report = DiagnosticReport::new(diagnostic, "App.vue", String::new());
+        assert_eq!(
+            miette::Diagnostic::severity(&report),
+            Some(miette::Severity::Advice)
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! Component resolution traces.
+//!
+//! When a `<Foo>` doesn't resolve, it isn't obvious whether it's missing an
+//! import, isn't registered globally, or is a typo. A trace walks the same
+//! lookups as the unknown component check and records why each one failed,
+//! like tsc's `--traceResolution` does for modules.
+
+use crate::comments::visit_siblings;
+use crate::registry::normalize;
+use crate::template::{is_builtin_component, is_imported_component};
+use crate::{style, DiagnosticOptions};
+use rustc_hash::FxHashSet;
+use std::fmt;
+use vue_parser::Sfc;
+use vue_template_compiler::transforms::pascalize;
+use vue_template_compiler::TemplateNode;
+
+/// Known names at most this many edits away are suggested.
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// One lookup made while resolving a component.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolutionStep {
+    /// Where the component was looked up, such as `script setup imports`.
+    pub source: &'static str,
+    /// Whether the component was found there.
+    pub found: bool,
+    /// What matched, or why nothing did.
+    pub detail: String,
+}
+
+/// The lookups made to resolve a component tag, in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolutionTrace {
+    /// The component name as written in the template.
+    pub component: String,
+    /// The lookups, up to the first one that found the component.
+    pub steps: Vec<ResolutionStep>,
+    /// Known names close to the component's, for unresolved components.
+    pub suggestions: Vec<String>,
+}
+
+impl ResolutionTrace {
+    /// Check if any lookup found the component.
+    pub fn is_resolved(&self) -> bool {
+        self.steps.iter().any(|step| step.found)
+    }
+}
+
+impl fmt::Display for ResolutionTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "======== Resolving component <{}> ========",
+            self.component
+        )?;
+        for step in &self.steps {
+            writeln!(f, "{}: {}", step.source, step.detail)?;
+        }
+        if !self.suggestions.is_empty() {
+            let names: Vec<String> = self
+                .suggestions
+                .iter()
+                .map(|name| format!("<{}>", name))
+                .collect();
+            writeln!(f, "Did you mean {}?", names.join(" or "))?;
+        }
+        let outcome = if self.is_resolved() {
+            "was resolved"
+        } else {
+            "was not resolved"
+        };
+        write!(
+            f,
+            "======== Component <{}> {} ========",
+            self.component, outcome
+        )
+    }
+}
+
+/// Trace how `component` resolves, given the bindings of the component's
+/// script setup.
+///
+/// The lookups are the ones the unknown component check makes, in the same
+/// order: custom elements, Vue built-ins, global components, then script
+/// setup imports.
+pub fn trace_component(
+    component: &str,
+    options: &DiagnosticOptions,
+    bindings: Option<&FxHashSet<String>>,
+) -> ResolutionTrace {
+    let mut trace = ResolutionTrace {
+        component: component.to_string(),
+        steps: Vec::new(),
+        suggestions: Vec::new(),
+    };
+
+    let custom = options.parse_options.is_custom_element(component);
+    trace.steps.push(ResolutionStep {
+        source: "custom elements",
+        found: custom,
+        detail: if custom {
+            "listed in nativeTags, checked as a native element".to_string()
+        } else {
+            "not listed in nativeTags".to_string()
+        },
+    });
+
+    if !custom {
+        let builtin = is_builtin_component(component);
+        trace.steps.push(ResolutionStep {
+            source: "built-in components",
+            found: builtin,
+            detail: if builtin {
+                "a Vue built-in component".to_string()
+            } else {
+                "not a Vue built-in component".to_string()
+            },
+        });
+    }
+
+    if !trace.is_resolved() {
+        trace.steps.push(match options.components.get(component) {
+            Some(info) => ResolutionStep {
+                source: "global components",
+                found: true,
+                detail: match &info.source {
+                    Some(source) => {
+                        format!("registered as `{}`, from '{}'", info.name, source.module)
+                    }
+                    None => format!("registered as `{}`", info.name),
+                },
+            },
+            None => ResolutionStep {
+                source: "global components",
+                found: false,
+                detail: format!(
+                    "not among the {} registered components",
+                    options.components.iter().count()
+                ),
+            },
+        });
+    }
+
+    if !trace.is_resolved() {
+        trace.steps.push(trace_imports(component, bindings));
+    }
+
+    if !trace.is_resolved() {
+        trace.suggestions = suggestions(component, options, bindings);
+    }
+    trace
+}
+
+/// Trace how `component` resolves in an SFC.
+///
+/// Returns `None` when the SFC's template doesn't use the component, or
+/// can't be parsed.
+pub fn trace_sfc(
+    sfc: &Sfc,
+    component: &str,
+    options: &DiagnosticOptions,
+) -> Option<ResolutionTrace> {
+    let template = sfc.template.as_ref()?;
+    let ast = vue_template_compiler::parse_template_with_options(
+        &template.content,
+        &options.parse_options,
+    )
+    .ok()?;
+
+    let wanted = normalize(component);
+    let mut used = None;
+    visit_siblings(&ast.children, &mut |nodes| {
+        for node in nodes {
+            if let TemplateNode::Element(el) = node {
+                if used.is_none() && el.is_component && normalize(el.component_name()) == wanted {
+                    used = Some(el.component_name().to_string());
+                }
+            }
+        }
+    });
+
+    let bindings = style::setup_bindings(sfc);
+    Some(trace_component(&used?, options, bindings.as_ref()))
+}
+
+/// Look a component up among the script setup bindings.
+fn trace_imports(component: &str, bindings: Option<&FxHashSet<String>>) -> ResolutionStep {
+    let source = "script setup imports";
+    let Some(bindings) = bindings else {
+        return ResolutionStep {
+            source,
+            found: false,
+            detail: "no <script setup>, components registered with `components` aren't visible"
+                .to_string(),
+        };
+    };
+
+    if is_imported_component(component, bindings) {
+        let root = component.split('.').next().unwrap_or(component);
+        let binding = if bindings.contains(root) {
+            root.to_string()
+        } else {
+            pascalize(root)
+        };
+        return ResolutionStep {
+            source,
+            found: true,
+            detail: format!("bound as `{}`", binding),
+        };
+    }
+
+    let mut names: Vec<&str> = bindings.iter().map(String::as_str).collect();
+    names.sort_unstable();
+    ResolutionStep {
+        source,
+        found: false,
+        detail: if names.is_empty() {
+            "not imported, script setup has no bindings".to_string()
+        } else {
+            format!("not imported, script setup binds {}", names.join(", "))
+        },
+    }
+}
+
+/// Find known component names close to `component`, likely typos of it.
+fn suggestions(
+    component: &str,
+    options: &DiagnosticOptions,
+    bindings: Option<&FxHashSet<String>>,
+) -> Vec<String> {
+    let wanted = normalize(component);
+    let mut names: Vec<String> = options
+        .components
+        .iter()
+        .map(|info| info.name.as_str())
+        .chain(bindings.into_iter().flatten().map(String::as_str))
+        .filter(|name| edit_distance(&normalize(name), &wanted) <= MAX_SUGGESTION_DISTANCE)
+        .map(str::to_string)
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Get the Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ComponentInfo;
+    use vue_parser::parse_sfc;
+
+    #[test]
+    fn test_trace_lists_import_scan() {
+        let source = r#"<script setup lang="ts">
+import MyButton from './MyButton.vue'
+const count = 1
+</script>
+
+<template>
+  <MyButon />
+</template>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let trace = trace_sfc(&sfc, "my-buton", &DiagnosticOptions::default()).unwrap();
+
+        assert!(!trace.is_resolved());
+        assert_eq!(trace.component, "MyButon");
+        let imports = trace.steps.last().unwrap();
+        assert_eq!(imports.source, "script setup imports");
+        assert_eq!(
+            imports.detail,
+            "not imported, script setup binds MyButton, count"
+        );
+        assert_eq!(trace.suggestions, ["MyButton"]);
+
+        let text = trace.to_string();
+        assert!(text.starts_with("======== Resolving component <MyButon> ========\n"));
+        assert!(text.contains("Did you mean <MyButton>?\n"));
+        assert!(text.ends_with("======== Component <MyButon> was not resolved ========"));
+    }
+
+    #[test]
+    fn test_trace_stops_at_first_match() {
+        let mut options = DiagnosticOptions::default();
+        options.components.insert(ComponentInfo::new("RouterLink"));
+
+        let trace = trace_component("router-link", &options, None);
+        assert!(trace.is_resolved());
+        let sources: Vec<_> = trace.steps.iter().map(|step| step.source).collect();
+        assert_eq!(
+            sources,
+            [
+                "custom elements",
+                "built-in components",
+                "global components"
+            ]
+        );
+        assert_eq!(trace.steps[2].detail, "registered as `RouterLink`");
+
+        let trace = trace_component("Transition", &options, None);
+        assert_eq!(trace.steps.len(), 2);
+        assert!(trace.steps[1].found);
+    }
+
+    #[test]
+    fn test_trace_sfc_without_usage() {
+        let sfc = parse_sfc("<template><div /></template>\n").unwrap();
+        assert!(trace_sfc(&sfc, "MyButton", &DiagnosticOptions::default()).is_none());
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("mybutton", "mybutton"), 0);
+        assert_eq!(edit_distance("mybuton", "mybutton"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "card"), 4);
+    }
+}
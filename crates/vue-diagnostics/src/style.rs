@@ -0,0 +1,508 @@
+//! Style block diagnostics.
+//!
+//! `<style>` blocks can reference setup bindings with `v-bind(expr)`, and
+//! script setup can bind CSS variables itself with `useCssVars()`. These
+//! checks make sure the referenced bindings exist.
+
+use crate::{Diagnostic, DiagnosticCode};
+use rustc_hash::FxHashSet;
+use source_map::Span;
+use vue_parser::Sfc;
+
+/// A `v-bind()` reference inside a style block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StyleVBind {
+    /// The bound expression, without surrounding quotes.
+    pub expression: String,
+    /// Span of the expression, relative to the style content.
+    pub span: Span,
+}
+
+/// Find `v-bind(expr)` references in style content.
+pub fn find_v_binds(content: &str) -> Vec<StyleVBind> {
+    let mut binds = Vec::new();
+    let mut pos = 0;
+
+    while let Some(found) = content[pos..].find("v-bind(") {
+        let open = pos + found + "v-bind(".len();
+        let inner = &content[open..];
+        let trimmed = inner.trim_start();
+        let lead = inner.len() - trimmed.len();
+
+        // Quoted expressions may contain parentheses
+        let (start, end) = match trimmed.chars().next() {
+            Some(quote @ ('"' | '\'')) => match trimmed[1..].find(quote) {
+                Some(len) => (open + lead + 1, open + lead + 1 + len),
+                None => break,
+            },
+            _ => match inner.find(')') {
+                Some(len) => (open + lead, open + inner[..len].trim_end().len()),
+                None => break,
+            },
+        };
+
+        let expression = content[start..end].trim();
+        if !expression.is_empty() {
+            binds.push(StyleVBind {
+                expression: expression.to_string(),
+                span: Span::new(start as u32, end as u32),
+            });
+        }
+        pos = end;
+    }
+
+    binds
+}
+
+/// Check `v-bind()` references in style blocks against the script setup bindings.
+pub fn check_styles(sfc: &Sfc) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let Some(bindings) = setup_bindings(sfc) else {
+        return diagnostics;
+    };
+
+    for style in &sfc.styles {
+        for bind in find_v_binds(&style.content) {
+            let root = root_identifier(&bind.expression);
+            if root.is_empty() || bindings.contains(root) || is_js_global(root) {
+                continue;
+            }
+            let offset = style.content_span.start;
+            diagnostics.push(Diagnostic::error(
+                format!("Unknown binding '{}' in style v-bind()", root),
+                bind.span.shift(offset.into()),
+                DiagnosticCode::UnknownStyleBinding,
+            ));
+        }
+    }
+
+    diagnostics
+}
+
+/// Collect the bindings the template and styles can see.
+///
+/// Without script setup, bindings come from options we can't see, so there
+/// are none to check against.
+pub(crate) fn setup_bindings(sfc: &Sfc) -> Option<FxHashSet<String>> {
+    let script_setup = sfc.script_setup.as_ref()?;
+    let mut bindings = collect_bindings(&script_setup.content);
+    if let Some(script) = &sfc.script {
+        bindings.extend(collect_bindings(&script.content));
+    }
+    Some(bindings)
+}
+
+/// Lint `useCssVars()` calls in script setup for undeclared bindings.
+///
+/// This is a lexical scan of the argument against the names script setup
+/// declares, not a type check: member accesses, property keys and arrow
+/// function parameters are skipped, and a few JavaScript globals are
+/// allowed. The argument is type checked with the rest of script setup in
+/// the virtual file.
+pub fn check_use_css_vars(sfc: &Sfc) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let (Some(script_setup), Some(bindings)) = (&sfc.script_setup, setup_bindings(sfc)) else {
+        return diagnostics;
+    };
+
+    let offset = script_setup.content_span.start;
+    for (name, span) in find_use_css_vars_references(&script_setup.content) {
+        if bindings.contains(name) || is_js_global(name) {
+            continue;
+        }
+        diagnostics.push(Diagnostic::error(
+            format!("Unknown binding '{}' in useCssVars()", name),
+            span.shift(offset.into()),
+            DiagnosticCode::UnknownStyleBinding,
+        ));
+    }
+
+    diagnostics
+}
+
+/// Find the identifiers that `useCssVars(...)` arguments reference.
+///
+/// Property keys, member accesses and the parameters of an arrow function
+/// argument (`ctx => ({ color: ctx.theme })`) aren't references.
+fn find_use_css_vars_references(content: &str) -> Vec<(&str, Span)> {
+    const CALL: &str = "useCssVars(";
+    let is_ident = |c: u8| c.is_ascii_alphanumeric() || c == b'_' || c == b'$' || !c.is_ascii();
+    let bytes = content.as_bytes();
+    let mut references = Vec::new();
+    let mut pos = 0;
+
+    while let Some(found) = content[pos..].find(CALL) {
+        let call = pos + found;
+        pos = call + CALL.len();
+        if call > 0 && is_ident(bytes[call - 1]) {
+            continue;
+        }
+
+        // Identifiers in the argument, up to the closing parenthesis
+        let mut idents = Vec::new();
+        let mut arrow = None;
+        let mut depth = 1;
+        let mut i = pos;
+        while i < bytes.len() && depth > 0 {
+            let c = bytes[i];
+            if is_ident(c) && !c.is_ascii_digit() {
+                let start = i;
+                while i < bytes.len() && is_ident(bytes[i]) {
+                    i += 1;
+                }
+                idents.push((start, i));
+                continue;
+            }
+            match c {
+                b'"' | b'\'' | b'`' => {
+                    i += 1;
+                    while i < bytes.len() && bytes[i] != c {
+                        i += if bytes[i] == b'\\' { 2 } else { 1 };
+                    }
+                }
+                b'(' | b'{' | b'[' => depth += 1,
+                b')' | b'}' | b']' => depth -= 1,
+                b'=' if bytes.get(i + 1) == Some(&b'>') && arrow.is_none() => {
+                    arrow = Some(idents.len());
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        pos = i;
+
+        // Arrow function parameters are local
+        let (params, body) = idents.split_at(arrow.unwrap_or(0));
+        let params: Vec<&str> = params.iter().map(|&(s, e)| &content[s..e]).collect();
+        for &(start, end) in body {
+            let name = &content[start..end];
+            let before = content[..start].trim_end().as_bytes().last();
+            let after = content[end..].trim_start().as_bytes().first();
+            if before == Some(&b'.') || after == Some(&b':') || params.contains(&name) {
+                continue;
+            }
+            references.push((name, Span::new(start as u32, end as u32)));
+        }
+    }
+
+    references
+}
+
+/// Get the leading identifier of an expression (`theme` in `theme.color`).
+fn root_identifier(expr: &str) -> &str {
+    let end = expr
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$'))
+        .unwrap_or(expr.len());
+    &expr[..end]
+}
+
+/// Check if a name is a JavaScript global.
+fn is_js_global(name: &str) -> bool {
+    matches!(
+        name,
+        "Math" | "JSON" | "Number" | "String" | "window" | "undefined" | "true" | "false" | "null"
+    )
+}
+
+/// A script token: an identifier or a punctuation character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    Ident(&'a str),
+    Punct(char),
+}
+
+/// Tokenize script content, skipping strings and comments.
+fn tokenize(content: &str) -> Vec<Token<'_>> {
+    tokenize_with_offsets(content)
+        .into_iter()
+        .map(|(_, token)| token)
+        .collect()
+}
+
+/// Tokenize script content, skipping strings and comments, with the byte
+/// offset each token starts at.
+///
+/// Non-ASCII characters are taken as identifier characters, so `größe` is a
+/// single identifier.
+pub(crate) fn tokenize_with_offsets(content: &str) -> Vec<(usize, Token<'_>)> {
+    let mut tokens = Vec::new();
+    let bytes = content.as_bytes();
+    let mut i = 0;
+    let is_ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || !b.is_ascii();
+
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+        if is_ident(bytes[i]) && !c.is_ascii_digit() {
+            let start = i;
+            while i < bytes.len() && is_ident(bytes[i]) {
+                i += 1;
+            }
+            tokens.push((start, Token::Ident(&content[start..i])));
+            continue;
+        }
+
+        if bytes[i..].starts_with(b"//") {
+            i = content[i..].find('\n').map_or(bytes.len(), |n| i + n);
+        } else if bytes[i..].starts_with(b"/*") {
+            i = content[i + 2..]
+                .find("*/")
+                .map_or(bytes.len(), |n| i + n + 4);
+        } else if matches!(c, '"' | '\'' | '`') {
+            i += 1;
+            while i < bytes.len() && bytes[i] as char != c {
+                i += if bytes[i] == b'\\' { 2 } else { 1 };
+            }
+            i += 1;
+        } else {
+            if c.is_ascii_punctuation() {
+                tokens.push((i, Token::Punct(c)));
+            }
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+/// Collect the names declared at any level of script content.
+///
+/// This is a lightweight scan covering variable, function, class and import
+/// declarations, plus props declared through `defineProps`.
+fn collect_bindings(content: &str) -> FxHashSet<String> {
+    let tokens = tokenize(content);
+    let mut bindings = FxHashSet::default();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        match tokens[i] {
+            Token::Ident("const" | "let" | "var") => {
+                i = collect_pattern(&tokens, i + 1, &mut bindings);
+                continue;
+            }
+            Token::Ident("function" | "class") => {
+                if let Some(Token::Ident(name)) = tokens.get(i + 1) {
+                    bindings.insert(name.to_string());
+                }
+            }
+            Token::Ident("import") => {
+                i = collect_imports(&tokens, i + 1, &mut bindings);
+                continue;
+            }
+            Token::Ident("defineProps") => {
+                collect_prop_keys(&tokens, i + 1, &mut bindings);
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    bindings
+}
+
+/// Collect names from a declaration pattern, returning the index after it.
+fn collect_pattern(tokens: &[Token<'_>], start: usize, bindings: &mut FxHashSet<String>) -> usize {
+    match tokens.get(start) {
+        Some(Token::Ident(name)) => {
+            bindings.insert(name.to_string());
+            start + 1
+        }
+        Some(Token::Punct('{' | '[')) => {
+            let mut depth = 0;
+            let mut in_default = false;
+            let mut i = start;
+            while let Some(token) = tokens.get(i) {
+                match token {
+                    Token::Punct('{' | '[') => depth += 1,
+                    Token::Punct('}' | ']') => {
+                        depth -= 1;
+                        in_default = false;
+                        if depth == 0 {
+                            return i + 1;
+                        }
+                    }
+                    Token::Punct(',') => in_default = false,
+                    Token::Punct('=') => in_default = true,
+                    // `{ key: alias }` binds the alias, not the key
+                    Token::Ident(name)
+                        if !in_default && tokens.get(i + 1) != Some(&Token::Punct(':')) =>
+                    {
+                        bindings.insert(name.to_string());
+                    }
+                    _ => {}
+                }
+                i += 1;
+            }
+            i
+        }
+        _ => start,
+    }
+}
+
+/// Collect imported names, returning the index after the import clause.
+fn collect_imports(tokens: &[Token<'_>], start: usize, bindings: &mut FxHashSet<String>) -> usize {
+    let mut i = start;
+    while let Some(token) = tokens.get(i) {
+        match token {
+            Token::Ident("from") | Token::Punct(';') => return i + 1,
+            Token::Ident("as" | "type" | "typeof") => {}
+            // `a as b` binds `b`
+            Token::Ident(name) if tokens.get(i + 1) != Some(&Token::Ident("as")) => {
+                bindings.insert(name.to_string());
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    i
+}
+
+/// Collect the top-level keys of the first object after `defineProps`.
+fn collect_prop_keys(tokens: &[Token<'_>], start: usize, bindings: &mut FxHashSet<String>) {
+    let Some(open) = tokens[start..]
+        .iter()
+        .position(|t| *t == Token::Punct('{'))
+        .map(|p| start + p)
+    else {
+        return;
+    };
+
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        match token {
+            Token::Punct('{') => depth += 1,
+            Token::Punct('}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return;
+                }
+            }
+            Token::Ident(name) if depth == 1 => {
+                if matches!(tokens.get(i + 1), Some(Token::Punct(':' | '?'))) {
+                    bindings.insert(name.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vue_parser::parse_sfc;
+
+    #[test]
+    fn test_find_v_binds() {
+        let content = ".a { color: v-bind(themeColor); width: v-bind('size.width + \"px\"') }";
+        let binds = find_v_binds(content);
+        assert_eq!(binds.len(), 2);
+        assert_eq!(binds[0].expression, "themeColor");
+        assert_eq!(
+            &content[binds[0].span.start as usize..binds[0].span.end as usize],
+            "themeColor"
+        );
+        assert_eq!(binds[1].expression, "size.width + \"px\"");
+    }
+
+    #[test]
+    fn test_undeclared_style_binding() {
+        let source = r#"<script setup>
+const size = 10
+</script>
+
+<style>
+.a { color: v-bind(themeColor); }
+</style>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let diagnostics = check_styles(&sfc);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::UnknownStyleBinding);
+        let span = diagnostics[0].span;
+        assert_eq!(
+            &source[span.start as usize..span.end as usize],
+            "themeColor"
+        );
+    }
+
+    #[test]
+    fn test_undeclared_use_css_vars_binding() {
+        let source = r#"<script setup>
+import { useCssVars } from 'vue'
+const accent = 'red'
+useCssVars({ color: themeColor, accent, 'font-size': '12px' })
+</script>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let diagnostics = check_use_css_vars(&sfc);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::UnknownStyleBinding);
+        assert_eq!(
+            diagnostics[0].message,
+            "Unknown binding 'themeColor' in useCssVars()"
+        );
+        let span = diagnostics[0].span;
+        assert_eq!(
+            &source[span.start as usize..span.end as usize],
+            "themeColor"
+        );
+    }
+
+    #[test]
+    fn test_use_css_vars_arrow_function() {
+        let source = r#"<script setup>
+const theme = { primary: 'red' }
+useCssVars((ctx) => ({ color: ctx.primary, border: theme.primary }))
+</script>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        assert!(check_use_css_vars(&sfc).is_empty());
+    }
+
+    #[test]
+    fn test_declared_style_bindings() {
+        let source = r#"<script setup lang="ts">
+import { theme as palette } from './theme'
+const { width, height: h = 1 } = useSize()
+function gap() { return 4 }
+defineProps<{ accent: string; border?: number }>()
+</script>
+
+<style>
+.a {
+  color: v-bind('palette.primary');
+  width: v-bind(width);
+  height: v-bind(h);
+  margin: v-bind(gap());
+  background: v-bind(accent);
+  border-width: v-bind(border);
+}
+</style>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        assert!(check_styles(&sfc).is_empty());
+    }
+
+    #[test]
+    fn test_non_ascii_identifiers() {
+        let source = r#"<script setup>
+const größe = 1
+const label = `→ ${größe}` // ≥ 1
+useCssVars({ size: größe })
+</script>
+"#;
+        let sfc = parse_sfc(source).unwrap();
+        let bindings = setup_bindings(&sfc).unwrap();
+        assert!(bindings.contains("größe"));
+        assert!(bindings.contains("label"));
+        assert!(check_use_css_vars(&sfc).is_empty());
+    }
+
+    #[test]
+    fn test_rename_key_is_not_a_binding() {
+        let bindings = collect_bindings("const { height: h } = size");
+        assert!(bindings.contains("h"));
+        assert!(!bindings.contains("height"));
+    }
+}
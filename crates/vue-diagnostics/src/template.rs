@@ -1,49 +1,529 @@
 //! Template diagnostics.
 
-use crate::{Diagnostic, DiagnosticCode, DiagnosticOptions};
-use vue_template_compiler::{ElementNode, ForNode, IfNode, TemplateAst, TemplateNode};
+use crate::{Diagnostic, DiagnosticCode, DiagnosticOptions, SlotInfo};
+use rustc_hash::FxHashSet;
+use source_map::Span;
+use vue_template_compiler::transforms::{extract_binding_names, pascalize};
+use vue_template_compiler::{
+    Attribute, Directive, ElementNode, ForNode, IfNode, TemplateAst, TemplateElementNode,
+    TemplateNode, TextNode,
+};
 
 /// Check a template AST for issues.
 pub fn check_template(ast: &TemplateAst, options: &DiagnosticOptions) -> Vec<Diagnostic> {
+    check_template_with_bindings(ast, options, None)
+}
+
+/// Check a template AST for issues, given the bindings of the component's
+/// script setup.
+///
+/// Script setup components must be imported unless registered globally, so
+/// with `bindings` the unknown component check always runs, and accepts the
+/// imported ones.
+pub fn check_template_with_bindings(
+    ast: &TemplateAst,
+    options: &DiagnosticOptions,
+    bindings: Option<&FxHashSet<String>>,
+) -> Vec<Diagnostic> {
     let mut diagnostics = Vec::new();
 
+    // Errors the parser recovered from
+    diagnostics.extend(ast.errors.iter().map(Diagnostic::from_compile_error));
+
+    let mut scope = Vec::new();
     for child in &ast.children {
-        check_node(child, options, &mut diagnostics);
+        check_node(child, options, bindings, &mut scope, &mut diagnostics);
     }
 
     diagnostics
 }
 
+/// Check that fallthrough attributes have somewhere to go.
+///
+/// Attributes only fall through automatically to a single root node, so a
+/// multi-root template should bind `$attrs` explicitly.
+pub fn check_attrs_fallthrough(ast: &TemplateAst) -> Vec<Diagnostic> {
+    let roots: Vec<&TemplateNode> = ast
+        .children
+        .iter()
+        .filter(|node| match node {
+            TemplateNode::Text(text) => !text.content.trim().is_empty(),
+            TemplateNode::Comment(_) => false,
+            _ => true,
+        })
+        .collect();
+
+    if roots.len() < 2 || roots.iter().any(|node| binds_attrs(node)) {
+        return Vec::new();
+    }
+
+    vec![Diagnostic::hint(
+        "Template has multiple root nodes, so fallthrough attributes are not applied automatically; \
+         bind them with v-bind=\"$attrs\" or set inheritAttrs: false",
+        roots[1].span(),
+        DiagnosticCode::MultiRootAttrs,
+    )]
+}
+
+/// A `ref` on an element.
+struct TemplateRef<'a> {
+    /// The ref name, or the bound identifier for `:ref`.
+    name: &'a str,
+    span: Span,
+    /// `:ref` rather than a static `ref`.
+    dynamic: bool,
+    /// Inside a `v-for`, where refs collect into arrays.
+    in_for: bool,
+    /// Bound to a `v-for` alias or slot prop.
+    scoped: bool,
+    /// The `v-if` chains (by span start) and branches the ref sits in.
+    branches: Vec<(u32, usize)>,
+}
+
+impl TemplateRef<'_> {
+    /// Check if two refs sit in different branches of the same `v-if` chain,
+    /// so they never render together.
+    fn is_exclusive_with(&self, other: &TemplateRef) -> bool {
+        self.branches.iter().any(|(chain, branch)| {
+            other
+                .branches
+                .iter()
+                .any(|(other_chain, other_branch)| chain == other_chain && branch != other_branch)
+        })
+    }
+}
+
+/// Check template refs for collisions and typos.
+///
+/// Two static `ref`s with the same name outside `v-for` overwrite each other.
+/// With script setup `bindings`, a `:ref` bound to an identifier must name one
+/// of them.
+pub fn check_refs(ast: &TemplateAst, bindings: Option<&FxHashSet<String>>) -> Vec<Diagnostic> {
+    let mut refs = Vec::new();
+    collect_refs(
+        &ast.children,
+        false,
+        &mut Vec::new(),
+        &mut Vec::new(),
+        &mut refs,
+    );
+
+    let mut diagnostics = Vec::new();
+    let statics: Vec<&TemplateRef> = refs.iter().filter(|r| !r.dynamic && !r.in_for).collect();
+    for (i, r) in statics.iter().enumerate() {
+        if statics[..i]
+            .iter()
+            .any(|earlier| earlier.name == r.name && !earlier.is_exclusive_with(r))
+        {
+            diagnostics.push(Diagnostic::warning(
+                format!("Duplicate ref '{}'; only the last element is kept", r.name),
+                r.span,
+                DiagnosticCode::DuplicateRef,
+            ));
+        }
+    }
+
+    if let Some(bindings) = bindings {
+        for r in refs.iter().filter(|r| r.dynamic && !r.scoped) {
+            if !bindings.contains(r.name) {
+                diagnostics.push(Diagnostic::error(
+                    format!("':ref' is bound to '{}', which is not declared", r.name),
+                    r.span,
+                    DiagnosticCode::UnknownRef,
+                ));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// Collect the refs in `nodes`.
+///
+/// `scope` holds the `v-for` aliases and slot props visible at the nodes, and
+/// `branches` the `v-if` branches they sit in.
+fn collect_refs<'a>(
+    nodes: &'a [TemplateNode],
+    in_for: bool,
+    scope: &mut Vec<&'a str>,
+    branches: &mut Vec<(u32, usize)>,
+    refs: &mut Vec<TemplateRef<'a>>,
+) {
+    for node in nodes {
+        match node {
+            TemplateNode::Element(el) => {
+                let static_ref = el.attrs.iter().find(|a| a.name == "ref").and_then(|a| {
+                    a.value
+                        .as_deref()
+                        .map(|value| (value.trim(), a.span, false, false))
+                });
+                // Only a plain identifier can be checked against the script
+                let dynamic_ref = el
+                    .props
+                    .iter()
+                    .find(|p| p.name == "ref" && !p.is_dynamic)
+                    .map(|p| (p.value.content.trim(), p.span))
+                    .filter(|(name, _)| is_identifier(name))
+                    .map(|(name, span)| (name, span, true, scope.contains(&name)));
+                for (name, span, dynamic, scoped) in static_ref.into_iter().chain(dynamic_ref) {
+                    refs.push(TemplateRef {
+                        name,
+                        span,
+                        dynamic,
+                        in_for,
+                        scoped,
+                        branches: branches.clone(),
+                    });
+                }
+
+                let marker = scope.len();
+                scope.extend(slot_prop_names(&el.directives));
+                collect_refs(&el.children, in_for, scope, branches, refs);
+                scope.truncate(marker);
+
+                for slot in el.slots.values() {
+                    let marker = scope.len();
+                    if let Some(props) = &slot.props {
+                        scope.extend(extract_binding_names(&props.pattern));
+                    }
+                    collect_refs(&slot.children, in_for, scope, branches, refs);
+                    scope.truncate(marker);
+                }
+            }
+            TemplateNode::For(f) => {
+                let marker = scope.len();
+                for alias in std::iter::once(&f.value).chain(&f.key).chain(&f.index) {
+                    scope.extend(extract_binding_names(&alias.pattern));
+                }
+                collect_refs(&f.children, true, scope, branches, refs);
+                scope.truncate(marker);
+            }
+            TemplateNode::If(i) => {
+                for (index, branch) in i.branches.iter().enumerate() {
+                    branches.push((i.span.start, index));
+                    collect_refs(&branch.children, in_for, scope, branches, refs);
+                    branches.pop();
+                }
+            }
+            TemplateNode::Template(t) => {
+                let marker = scope.len();
+                scope.extend(slot_prop_names(&t.directives));
+                collect_refs(&t.children, in_for, scope, branches, refs);
+                scope.truncate(marker);
+            }
+            TemplateNode::SlotOutlet(s) => collect_refs(&s.fallback, in_for, scope, branches, refs),
+            _ => {}
+        }
+    }
+}
+
+/// Check if text is a single JavaScript identifier.
+fn is_identifier(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Check if a node binds `$attrs` with `v-bind="$attrs"`.
+fn binds_attrs(node: &TemplateNode) -> bool {
+    match node {
+        TemplateNode::Element(el) => {
+            el.directives.iter().any(|d| {
+                d.name == "bind"
+                    && d.arg.is_none()
+                    && d.value
+                        .as_ref()
+                        .is_some_and(|v| v.content.trim() == "$attrs")
+            }) || el.children.iter().any(binds_attrs)
+        }
+        TemplateNode::If(i) => i
+            .branches
+            .iter()
+            .any(|b| b.children.iter().any(binds_attrs)),
+        TemplateNode::For(f) => f.children.iter().any(binds_attrs),
+        TemplateNode::Template(t) => t.children.iter().any(binds_attrs),
+        _ => false,
+    }
+}
+
 /// Check a template node for issues.
-fn check_node(node: &TemplateNode, options: &DiagnosticOptions, diagnostics: &mut Vec<Diagnostic>) {
+///
+/// `scope` holds the `v-for` aliases and slot props visible at the node.
+fn check_node<'a>(
+    node: &'a TemplateNode,
+    options: &DiagnosticOptions,
+    bindings: Option<&FxHashSet<String>>,
+    scope: &mut Vec<&'a str>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
     match node {
-        TemplateNode::Element(el) => check_element(el, options, diagnostics),
-        TemplateNode::For(f) => check_for(f, options, diagnostics),
-        TemplateNode::If(i) => check_if(i, options, diagnostics),
+        TemplateNode::Element(el) => check_element(el, options, bindings, scope, diagnostics),
+        TemplateNode::For(f) => check_for(f, options, bindings, scope, diagnostics),
+        TemplateNode::If(i) => check_if(i, options, bindings, scope, diagnostics),
         TemplateNode::Template(t) => {
+            let marker = scope.len();
+            scope.extend(slot_prop_names(&t.directives));
             for child in &t.children {
-                check_node(child, options, diagnostics);
+                check_node(child, options, bindings, scope, diagnostics);
             }
+            scope.truncate(marker);
         }
         TemplateNode::SlotOutlet(s) => {
             for child in &s.fallback {
-                check_node(child, options, diagnostics);
+                check_node(child, options, bindings, scope, diagnostics);
             }
         }
+        TemplateNode::Text(text) => check_single_braces(text, diagnostics),
         _ => {}
     }
 }
 
+/// Hint at `{ expr }` in text, which renders literally and is usually a
+/// mistyped `{{ expr }}`.
+fn check_single_braces(text: &TextNode, diagnostics: &mut Vec<Diagnostic>) {
+    let content = &text.content;
+    let bytes = content.as_bytes();
+    let mut pos = 0;
+
+    while let Some(found) = content[pos..].find('{') {
+        let open = pos + found;
+        pos = open + 1;
+        if open > 0 && bytes[open - 1] == b'{' {
+            continue;
+        }
+
+        // The closing brace must be on the same line, with no nested braces
+        let Some(len) = content[open + 1..].find(['{', '}', '\n']) else {
+            break;
+        };
+        let close = open + 1 + len;
+        if bytes[close] != b'}' || bytes.get(close + 1) == Some(&b'}') {
+            continue;
+        }
+
+        let inner = content[open + 1..close].trim();
+        if looks_like_expression(inner) {
+            diagnostics.push(Diagnostic::hint(
+                format!(
+                    "Single braces are rendered as text; did you mean '{{{{ {} }}}}'?",
+                    inner
+                ),
+                Span::new(
+                    text.span.start + open as u32,
+                    text.span.start + close as u32 + 1,
+                ),
+                DiagnosticCode::SingleBraceInterpolation,
+            ));
+        }
+        pos = close + 1;
+    }
+}
+
+/// Warn about `{{ expr }}` in a static attribute value, which Vue 2 templates
+/// allowed but Vue 3 renders literally.
+///
+/// The suggested binding is the expression itself, or a template literal
+/// when the value mixes text and mustaches.
+fn check_attribute_interpolation(attr: &Attribute, diagnostics: &mut Vec<Diagnostic>) {
+    let (Some(value), Some(value_span)) = (&attr.value, attr.value_span) else {
+        return;
+    };
+    let Some(open) = value.find("{{") else {
+        return;
+    };
+    if !value[open..].contains("}}") {
+        return;
+    }
+
+    let trimmed = value.trim();
+    let single = trimmed
+        .strip_prefix("{{")
+        .and_then(|rest| rest.strip_suffix("}}"))
+        .filter(|inner| !inner.contains("{{"));
+    let binding = match single {
+        Some(inner) => inner.trim().to_string(),
+        None => {
+            let mut literal = String::from("`");
+            let mut rest = value.as_str();
+            while let Some(start) = rest.find("{{") {
+                let Some(len) = rest[start..].find("}}") else {
+                    break;
+                };
+                literal.push_str(&rest[..start]);
+                literal.push_str("${");
+                literal.push_str(rest[start + 2..start + len].trim());
+                literal.push('}');
+                rest = &rest[start + len + 2..];
+            }
+            literal.push_str(rest);
+            literal.push('`');
+            literal
+        }
+    };
+
+    diagnostics.push(Diagnostic::warning(
+        format!(
+            "Interpolation isn't supported in attribute values; use ':{}=\"{}\"' instead",
+            attr.name, binding
+        ),
+        value_span,
+        DiagnosticCode::InterpolationInAttribute,
+    ));
+}
+
+/// Check if text looks like a JavaScript expression rather than prose.
+///
+/// Two words in a row (`this is`) never form an expression, so anything with
+/// them is treated as prose.
+fn looks_like_expression(text: &str) -> bool {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+
+    let starts_with_ident =
+        text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$');
+    let expression_chars = text
+        .chars()
+        .all(|c| is_ident(c) || c.is_whitespace() || "._()[]+-*/%<>=!?:&|,'\"".contains(c));
+    if !starts_with_ident || !expression_chars {
+        return false;
+    }
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    words
+        .windows(2)
+        .all(|pair| !(pair[0].ends_with(is_ident) && pair[1].starts_with(is_ident)))
+}
+
+/// Check the slots passed to a component against its declared slots.
+///
+/// Dynamic slot names (`#[name]`) can't be checked, and may provide any
+/// required slot. A self-closing component like `<Dialog />` has no children,
+/// so only a `v-slot` on the component itself provides a slot.
+fn check_slots(el: &ElementNode, declared: &[SlotInfo], diagnostics: &mut Vec<Diagnostic>) {
+    let mut used = Vec::new();
+    let mut has_dynamic = false;
+    let mut has_default_content = false;
+
+    let mut visit_slot = |dir: &Directive| match &dir.arg {
+        Some(arg) => match arg.as_static() {
+            Some(name) => used.push((name.to_string(), dir.span)),
+            None => has_dynamic = true,
+        },
+        None => used.push(("default".to_string(), dir.span)),
+    };
+
+    el.directives
+        .iter()
+        .filter(|d| d.name == "slot")
+        .for_each(&mut visit_slot);
+    for child in &el.children {
+        has_default_content |= collect_slot_directives(child, &mut visit_slot);
+    }
+
+    for (name, span) in &used {
+        if !declared.iter().any(|slot| slot.name == *name) {
+            diagnostics.push(Diagnostic::warning(
+                format!("Unknown slot '{}' on <{}>", name, el.component_name()),
+                *span,
+                DiagnosticCode::InvalidSlot,
+            ));
+        }
+    }
+
+    if has_dynamic {
+        return;
+    }
+    for slot in declared.iter().filter(|slot| slot.required) {
+        let provided = used.iter().any(|(name, _)| *name == slot.name)
+            || (slot.name == "default" && has_default_content);
+        if !provided {
+            diagnostics.push(Diagnostic::warning(
+                format!(
+                    "Missing required slot '{}' on <{}>",
+                    slot.name,
+                    el.component_name()
+                ),
+                el.tag_span,
+                DiagnosticCode::InvalidSlot,
+            ));
+        }
+    }
+}
+
+/// Call `f` with the `v-slot` directives of slot templates among a
+/// component's children, looking through `v-if` and `v-for`.
+///
+/// Returns whether the node is content for the default slot instead.
+fn collect_slot_directives<'a>(node: &'a TemplateNode, f: &mut impl FnMut(&'a Directive)) -> bool {
+    match node {
+        TemplateNode::Template(t) if t.directives.iter().any(|d| d.name == "slot") => {
+            t.directives.iter().filter(|d| d.name == "slot").for_each(f);
+            false
+        }
+        TemplateNode::If(i) => i
+            .branches
+            .iter()
+            .flat_map(|b| &b.children)
+            .fold(false, |content, child| {
+                collect_slot_directives(child, f) | content
+            }),
+        TemplateNode::For(node) => node.children.iter().fold(false, |content, child| {
+            collect_slot_directives(child, f) | content
+        }),
+        TemplateNode::Text(text) => !text.content.trim().is_empty(),
+        TemplateNode::Comment(_) => false,
+        _ => true,
+    }
+}
+
+/// Get the slot prop names bound by a `v-slot` directive.
+fn slot_prop_names(directives: &[Directive]) -> Vec<&str> {
+    directives
+        .iter()
+        .filter(|d| d.name == "slot")
+        .filter_map(|d| d.value.as_ref())
+        .flat_map(|value| extract_binding_names(&value.content))
+        .collect()
+}
+
 /// Check an element for issues.
-fn check_element(el: &ElementNode, options: &DiagnosticOptions, diagnostics: &mut Vec<Diagnostic>) {
-    // Check for unknown components
-    if options.check_unknown_components && el.is_component && !is_known_component(&el.tag, options)
+fn check_element<'a>(
+    el: &'a ElementNode,
+    options: &DiagnosticOptions,
+    bindings: Option<&FxHashSet<String>>,
+    scope: &mut Vec<&'a str>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    // `<tr is="vue:MyRow">` is checked as `MyRow`
+    let component = el.component_name();
+
+    // Check for unknown components. Script setup components must be imported
+    // unless registered globally, so with its bindings this is precise enough
+    // to always run.
+    if (options.check_unknown_components || bindings.is_some())
+        && el.is_component
+        && !options.parse_options.is_custom_element(component)
+        && !is_known_component(component, options)
     {
-        diagnostics.push(Diagnostic::warning(
-            format!("Unknown component: <{}>", el.tag),
-            el.tag_span,
-            DiagnosticCode::UnknownComponent,
-        ));
+        let message = match bindings {
+            Some(bindings) if is_imported_component(component, bindings) => None,
+            Some(_) => Some(format!(
+                "Component <{}> is used but not imported in script setup",
+                component
+            )),
+            None => Some(format!("Unknown component: <{}>", component)),
+        };
+        if let Some(message) = message {
+            diagnostics.push(Diagnostic::warning(
+                message,
+                el.tag_span,
+                DiagnosticCode::UnknownComponent,
+            ));
+        }
+    }
+
+    for attr in &el.attrs {
+        check_attribute_interpolation(attr, diagnostics);
     }
 
     // Check for unknown directives
@@ -59,34 +539,121 @@ fn check_element(el: &ElementNode, options: &DiagnosticOptions, diagnostics: &mu
         }
     }
 
+    // Check for props the component doesn't declare
+    if options.check_unknown_props && el.is_component {
+        if let Some(declared) = options
+            .components
+            .get(component)
+            .and_then(|c| c.props.as_ref())
+        {
+            for prop in &el.props {
+                if prop.is_dynamic || prop.is_dom_binding() || is_reserved_prop(&prop.name) {
+                    continue;
+                }
+                let name = prop.component_prop_name();
+                if !declared.contains(&name) {
+                    diagnostics.push(Diagnostic::warning(
+                        format!("Unknown prop '{}' on <{}>", name, component),
+                        prop.span,
+                        DiagnosticCode::UnknownProp,
+                    ));
+                }
+            }
+        }
+    }
+
+    // Check slots against the component's declared slots
+    if el.is_component {
+        if let Some(declared) = options
+            .components
+            .get(component)
+            .and_then(|c| c.slots.as_ref())
+        {
+            check_slots(el, declared, diagnostics);
+        }
+    }
+
     // Check v-model on invalid elements
     if let Some(model_dir) = el.directives.iter().find(|d| d.name == "model") {
-        if !can_use_v_model(&el.tag) {
+        if !can_use_v_model(component) {
             diagnostics.push(Diagnostic::error(
                 format!("v-model is not valid on <{}> elements", el.tag),
                 model_dir.span,
                 DiagnosticCode::InvalidVModel,
             ));
         }
+
+        // Assigning to a loop alias or slot prop only rebinds a local copy
+        if let Some(value) = &model_dir.value {
+            let name = value.content.trim();
+            if scope.contains(&name) {
+                diagnostics.push(Diagnostic::warning(
+                    format!(
+                        "v-model on '{}' assigns to a local scope variable; bind a property of it instead",
+                        name
+                    ),
+                    model_dir.span,
+                    DiagnosticCode::VModelOnScopeVar,
+                ));
+            }
+        }
     }
 
-    // Check children recursively
+    // Check children recursively, with props of a `v-slot` on the element
+    let marker = scope.len();
+    scope.extend(slot_prop_names(&el.directives));
     for child in &el.children {
-        check_node(child, options, diagnostics);
+        check_node(child, options, bindings, scope, diagnostics);
     }
+    scope.truncate(marker);
 
     // Check slots
     for (_name, slot) in &el.slots {
+        let marker = scope.len();
+        if let Some(props) = &slot.props {
+            scope.extend(extract_binding_names(&props.pattern));
+        }
         for child in &slot.children {
-            check_node(child, options, diagnostics);
+            check_node(child, options, bindings, scope, diagnostics);
         }
+        scope.truncate(marker);
     }
 }
 
 /// Check a v-for node for issues.
-fn check_for(f: &ForNode, options: &DiagnosticOptions, diagnostics: &mut Vec<Diagnostic>) {
+fn check_for<'a>(
+    f: &'a ForNode,
+    options: &DiagnosticOptions,
+    bindings: Option<&FxHashSet<String>>,
+    scope: &mut Vec<&'a str>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    // `<template v-for>` takes the key itself in Vue 3, and on its children
+    // in Vue 2
+    let child_keys = match f.children.as_slice() {
+        [TemplateNode::Template(t)] => fragment_child_keys(t),
+        _ => Vec::new(),
+    };
+    if options.is_vue2() {
+        if let (Some(key), [TemplateNode::Template(_)]) = (&f.key_attr, f.children.as_slice()) {
+            diagnostics.push(Diagnostic::warning(
+                "In Vue 2, <template> can't be keyed; place :key on its children",
+                key.span,
+                DiagnosticCode::MisplacedKey,
+            ));
+        }
+    } else {
+        for span in &child_keys {
+            diagnostics.push(Diagnostic::warning(
+                "In Vue 3, :key belongs on the <template v-for>, not its children",
+                *span,
+                DiagnosticCode::MisplacedKey,
+            ));
+        }
+    }
+
     // Check for missing key attribute
-    if options.check_v_for_keys && f.key_attr.is_none() {
+    if options.check_v_for_keys && f.key_attr.is_none() && child_keys.is_empty() {
         diagnostics.push(Diagnostic::warning(
             "v-for is missing a :key attribute",
             f.span,
@@ -94,17 +661,75 @@ fn check_for(f: &ForNode, options: &DiagnosticOptions, diagnostics: &mut Vec<Dia
         ));
     }
 
-    // Check children
+    // The index changes when the list is reordered, so it can't identify items
+    if let (Some(key), Some(index)) = (&f.key_attr, loop_index_alias(f)) {
+        if key.content.trim() == index {
+            diagnostics.push(Diagnostic::hint(
+                format!(
+                    "Using the loop index '{}' as :key breaks updates when the list is reordered; use a stable id instead",
+                    index
+                ),
+                key.span,
+                DiagnosticCode::IndexAsKey,
+            ));
+        }
+    }
+
+    // Check children with the loop aliases in scope
+    let marker = scope.len();
+    for alias in std::iter::once(&f.value).chain(&f.key).chain(&f.index) {
+        scope.extend(extract_binding_names(&alias.pattern));
+    }
     for child in &f.children {
-        check_node(child, options, diagnostics);
+        check_node(child, options, bindings, scope, diagnostics);
+    }
+    scope.truncate(marker);
+}
+
+/// Get the alias holding the position in the list.
+///
+/// That's the third alias, or the second when it's named like an index, as in
+/// `(item, index) in items`. A second alias otherwise may be an object key,
+/// which is stable.
+fn loop_index_alias(f: &ForNode) -> Option<&str> {
+    if let Some(index) = &f.index {
+        return Some(index.pattern.trim());
     }
+    let key = f.key.as_ref()?.pattern.trim();
+    matches!(key, "i" | "idx" | "index")
+        .then_some(key)
+        .or_else(|| key.ends_with("Index").then_some(key))
+}
+
+/// Get the spans of the `key` attributes on the direct children of a
+/// `<template v-for>`.
+fn fragment_child_keys(fragment: &TemplateElementNode) -> Vec<Span> {
+    fragment
+        .children
+        .iter()
+        .filter_map(|child| match child {
+            TemplateNode::Element(el) => el
+                .props
+                .iter()
+                .find(|p| p.name == "key")
+                .map(|p| p.span)
+                .or_else(|| el.attrs.iter().find(|a| a.name == "key").map(|a| a.span)),
+            _ => None,
+        })
+        .collect()
 }
 
 /// Check an if node for issues.
-fn check_if(i: &IfNode, options: &DiagnosticOptions, diagnostics: &mut Vec<Diagnostic>) {
+fn check_if<'a>(
+    i: &'a IfNode,
+    options: &DiagnosticOptions,
+    bindings: Option<&FxHashSet<String>>,
+    scope: &mut Vec<&'a str>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
     for branch in &i.branches {
         for child in &branch.children {
-            check_node(child, options, diagnostics);
+            check_node(child, options, bindings, scope, diagnostics);
         }
     }
 }
@@ -117,10 +742,19 @@ fn is_known_component(name: &str, options: &DiagnosticOptions) -> bool {
     }
 
     // User-specified known components
-    options
-        .known_components
-        .iter()
-        .any(|c| c.eq_ignore_ascii_case(name))
+    options.components.contains(name)
+}
+
+/// Check if a component is bound in script setup, by its name as written, its
+/// PascalCase form or, for `<Form.Input>`, the namespace.
+pub(crate) fn is_imported_component(name: &str, bindings: &FxHashSet<String>) -> bool {
+    let root = name.split('.').next().unwrap_or(name);
+    bindings.contains(root) || bindings.contains(&pascalize(root))
+}
+
+/// Check if a prop name is reserved and never declared by components.
+fn is_reserved_prop(name: &str) -> bool {
+    matches!(name, "key" | "ref" | "class" | "style" | "is")
 }
 
 /// Check if a directive is known.
@@ -153,7 +787,7 @@ fn is_builtin_directive(name: &str) -> bool {
 }
 
 /// Check if a component is a built-in Vue component.
-fn is_builtin_component(name: &str) -> bool {
+pub(crate) fn is_builtin_component(name: &str) -> bool {
     matches!(
         name.to_lowercase().as_str(),
         "transition"
@@ -188,7 +822,8 @@ fn can_use_v_model(tag: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use vue_template_compiler::parse_template;
+    use crate::ComponentInfo;
+    use vue_template_compiler::{parse_template, parse_template_with_options, ParseOptions};
 
     #[test]
     fn test_check_valid_template() {
@@ -197,6 +832,132 @@ mod tests {
         assert!(diagnostics.is_empty());
     }
 
+    #[test]
+    fn test_single_brace_interpolation_hint() {
+        let source = "<p>Count: { count }</p>";
+        let ast = parse_template(source).unwrap();
+        let diagnostics = check_template(&ast, &DiagnosticOptions::default());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].code,
+            DiagnosticCode::SingleBraceInterpolation
+        );
+        assert_eq!(diagnostics[0].severity, crate::Severity::Hint);
+        assert!(diagnostics[0].message.contains("{{ count }}"));
+        let span = diagnostics[0].span;
+        assert_eq!(&source[span.start as usize..span.end as usize], "{ count }");
+    }
+
+    #[test]
+    fn test_single_brace_expressions() {
+        assert!(looks_like_expression("user.name"));
+        assert!(looks_like_expression("items.length > 0 ? 'some' : 'none'"));
+        assert!(looks_like_expression("format(date)"));
+        assert!(!looks_like_expression("this is prose"));
+        assert!(!looks_like_expression("1, 2, 3"));
+        assert!(!looks_like_expression(""));
+    }
+
+    #[test]
+    fn test_interpolation_in_attribute() {
+        let source = r#"<div id="{{ foo }}" :title="foo" class="item-{{ id }}-row"></div>"#;
+        let ast = parse_template(source).unwrap();
+        let diagnostics = check_template(&ast, &DiagnosticOptions::default());
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(
+            diagnostics[0].code,
+            DiagnosticCode::InterpolationInAttribute
+        );
+        assert_eq!(diagnostics[0].severity, crate::Severity::Warning);
+        assert!(diagnostics[0].message.contains(r#"':id="foo"'"#));
+        let span = diagnostics[0].span;
+        assert_eq!(&source[span.start as usize..span.end as usize], "{{ foo }}");
+        assert!(diagnostics[1]
+            .message
+            .contains(r#"':class="`item-${id}-row`"'"#));
+    }
+
+    #[test]
+    fn test_bound_attribute_is_not_interpolation() {
+        let ast = parse_template(r#"<div :id="foo" title="{ braces }">{{ foo }}</div>"#).unwrap();
+        let diagnostics = check_template(&ast, &DiagnosticOptions::default());
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_single_brace_prose_is_not_hinted() {
+        let ast = parse_template("<p>{ this is prose } and {{ count }} with { a\n}</p>").unwrap();
+        let diagnostics = check_template(&ast, &DiagnosticOptions::default());
+        assert!(diagnostics.is_empty());
+    }
+
+    fn check_with_slots(source: &str) -> Vec<Diagnostic> {
+        let mut components = crate::ComponentRegistry::new();
+        components.insert(
+            ComponentInfo::new("Card")
+                .with_slots([SlotInfo::required("header"), SlotInfo::new("default")]),
+        );
+        let options = DiagnosticOptions {
+            components,
+            ..Default::default()
+        };
+        check_template(&parse_template(source).unwrap(), &options)
+    }
+
+    #[test]
+    fn test_unknown_slot() {
+        let source =
+            r#"<Card><template #header>Title</template><template #footer>x</template></Card>"#;
+        let diagnostics = check_with_slots(source);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::InvalidSlot);
+        assert_eq!(diagnostics[0].message, "Unknown slot 'footer' on <Card>");
+        let span = diagnostics[0].span;
+        assert_eq!(&source[span.start as usize..span.end as usize], "#footer");
+    }
+
+    #[test]
+    fn test_missing_required_slot() {
+        let diagnostics = check_with_slots("<Card>Body only</Card>");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "Missing required slot 'header' on <Card>"
+        );
+
+        // Conditional slot templates and dynamic names count as provided
+        assert!(
+            check_with_slots(r#"<Card><template v-if="a" #header>x</template>Body</Card>"#)
+                .is_empty()
+        );
+        assert!(check_with_slots(r#"<Card><template #[name]>x</template></Card>"#).is_empty());
+    }
+
+    #[test]
+    fn test_self_closing_component_misses_required_slots() {
+        let mut components = crate::ComponentRegistry::new();
+        components.insert(ComponentInfo::new("Dialog").with_slots([SlotInfo::required("default")]));
+        let options = DiagnosticOptions {
+            components,
+            ..Default::default()
+        };
+        let check = |source: &str| check_template(&parse_template(source).unwrap(), &options);
+
+        let source = "<div><Dialog /></div>";
+        let diagnostics = check(source);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "Missing required slot 'default' on <Dialog>"
+        );
+        let span = diagnostics[0].span;
+        assert_eq!(&source[span.start as usize..span.end as usize], "Dialog");
+
+        assert_eq!(check("<Dialog/>").len(), 1);
+        assert!(check("<Dialog>Are you sure?</Dialog>").is_empty());
+        assert!(check("<Dialog v-slot=\"{ close }\" />").is_empty());
+    }
+
     #[test]
     fn test_check_missing_key() {
         let ast = parse_template(r#"<div v-for="item in items">{{ item }}</div>"#).unwrap();
@@ -210,6 +971,215 @@ mod tests {
             .any(|d| d.code == DiagnosticCode::MissingKey));
     }
 
+    fn check_keys(source: &str, target: Option<f32>) -> Vec<Diagnostic> {
+        let options = DiagnosticOptions {
+            check_v_for_keys: true,
+            target,
+            ..Default::default()
+        };
+        check_template(&parse_template(source).unwrap(), &options)
+    }
+
+    #[test]
+    fn test_key_on_template_v_for_children_in_vue3() {
+        let source =
+            r#"<template v-for="item in items"><li :key="item.id">{{ item }}</li></template>"#;
+        let diagnostics = check_keys(source, None);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::MisplacedKey);
+        let span = diagnostics[0].span;
+        assert_eq!(
+            &source[span.start as usize..span.end as usize],
+            r#":key="item.id""#
+        );
+
+        let source =
+            r#"<template v-for="item in items" :key="item.id"><li>{{ item }}</li></template>"#;
+        assert!(check_keys(source, Some(3.5)).is_empty());
+    }
+
+    #[test]
+    fn test_index_as_key_hint() {
+        let source = r#"<li v-for="(item, index) in items" :key="index">{{ item }}</li>"#;
+        let diagnostics = check_keys(source, None);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::IndexAsKey);
+        assert_eq!(diagnostics[0].severity, crate::Severity::Hint);
+        let span = diagnostics[0].span;
+        assert_eq!(&source[span.start as usize..span.end as usize], "index");
+
+        let source = r#"<li v-for="(value, key, i) in object" :key="i">{{ value }}</li>"#;
+        assert_eq!(check_keys(source, None)[0].code, DiagnosticCode::IndexAsKey);
+    }
+
+    #[test]
+    fn test_stable_key_has_no_hint() {
+        for source in [
+            r#"<li v-for="(item, index) in items" :key="item.id">{{ index }}</li>"#,
+            r#"<li v-for="(value, key) in object" :key="key">{{ value }}</li>"#,
+        ] {
+            assert!(check_keys(source, None).is_empty(), "{}", source);
+        }
+    }
+
+    #[test]
+    fn test_key_on_template_v_for_in_vue2() {
+        let source =
+            r#"<template v-for="item in items" :key="item.id"><li>{{ item }}</li></template>"#;
+        let diagnostics = check_keys(source, Some(2.7));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::MisplacedKey);
+        assert!(diagnostics[0].message.contains("Vue 2"));
+
+        let source =
+            r#"<template v-for="item in items"><li :key="item.id">{{ item }}</li></template>"#;
+        assert!(check_keys(source, Some(2.7)).is_empty());
+    }
+
+    #[test]
+    fn test_custom_element_is_not_unknown_component() {
+        let options = DiagnosticOptions {
+            check_unknown_components: true,
+            parse_options: ParseOptions {
+                custom_element_tags: vec!["my-widget".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let ast = parse_template_with_options("<my-widget /><my-comp />", &options.parse_options)
+            .unwrap();
+        let diagnostics = check_template(&ast, &options);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("<my-comp>"));
+    }
+
+    #[test]
+    fn test_registered_component_in_kebab_case() {
+        let options = DiagnosticOptions {
+            check_unknown_components: true,
+            components: ["MyButton"].into_iter().collect(),
+            ..Default::default()
+        };
+        let ast = parse_template("<my-button /><MyButton /><OtherButton />").unwrap();
+        let diagnostics = check_template(&ast, &options);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("<OtherButton>"));
+    }
+
+    #[test]
+    fn test_kebab_prop_matches_camel_declaration() {
+        let ast = parse_template(r#"<MyComp :my-prop="a" :other="b" />"#).unwrap();
+        let mut options = DiagnosticOptions {
+            check_unknown_props: true,
+            ..Default::default()
+        };
+        options
+            .components
+            .insert(ComponentInfo::new("MyComp").with_props(["myProp"]));
+        let diagnostics = check_template(&ast, &options);
+        let unknown: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.code == DiagnosticCode::UnknownProp)
+            .collect();
+        assert_eq!(unknown.len(), 1);
+        assert!(unknown[0].message.contains("'other'"));
+    }
+
+    #[test]
+    fn test_vue_is_attribute_checks_component_props() {
+        let ast = parse_template(r#"<table><tr is="vue:MyRow" :item="a" :other="b"></tr></table>"#)
+            .unwrap();
+        let mut options = DiagnosticOptions {
+            check_unknown_components: true,
+            check_unknown_props: true,
+            ..Default::default()
+        };
+        options
+            .components
+            .insert(ComponentInfo::new("MyRow").with_props(["item"]));
+        let diagnostics = check_template(&ast, &options);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::UnknownProp);
+        assert_eq!(diagnostics[0].message, "Unknown prop 'other' on <MyRow>");
+    }
+
+    #[test]
+    fn test_kebab_tag_uses_pascal_declaration() {
+        let ast = parse_template(r#"<my-comp :my-prop="a" />"#).unwrap();
+        let mut options = DiagnosticOptions {
+            check_unknown_props: true,
+            ..Default::default()
+        };
+        options
+            .components
+            .insert(ComponentInfo::new("MyComp").with_props(["myProp"]));
+        let diagnostics = check_template(&ast, &options);
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.code != DiagnosticCode::UnknownProp));
+    }
+
+    #[test]
+    fn test_v_model_on_v_for_alias() {
+        let ast = parse_template(
+            r#"<div v-for="item in items" :key="item.id"><input v-model="item" /><input v-model="item.value" /></div>"#,
+        )
+        .unwrap();
+        let diagnostics = check_template(&ast, &DiagnosticOptions::default());
+        let scope_var: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.code == DiagnosticCode::VModelOnScopeVar)
+            .collect();
+        assert_eq!(scope_var.len(), 1);
+        assert_eq!(scope_var[0].severity, crate::Severity::Warning);
+        assert!(scope_var[0].message.contains("'item'"));
+    }
+
+    #[test]
+    fn test_v_model_on_slot_prop() {
+        let ast = parse_template(
+            r#"<MyList><template #row="{ row }"><input v-model="row" /></template></MyList><input v-model="row" />"#,
+        )
+        .unwrap();
+        let diagnostics = check_template(&ast, &DiagnosticOptions::default());
+        let scope_var: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.code == DiagnosticCode::VModelOnScopeVar)
+            .collect();
+        // The second input is outside the slot, where `row` is a setup binding
+        assert_eq!(scope_var.len(), 1);
+    }
+
+    #[test]
+    fn test_check_dangling_v_else() {
+        let ast = parse_template(r#"<div v-if="a"/><p>x</p><div v-else/>"#).unwrap();
+        let diagnostics = check_template(&ast, &DiagnosticOptions::default());
+        let chain: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.code == DiagnosticCode::InvalidVIfChain)
+            .collect();
+        assert_eq!(chain.len(), 1);
+        assert_eq!(chain[0].severity, crate::Severity::Error);
+    }
+
+    #[test]
+    fn test_multi_root_attrs_hint() {
+        let ast = parse_template("<header />\n<main />").unwrap();
+        let diagnostics = check_attrs_fallthrough(&ast);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::MultiRootAttrs);
+        assert_eq!(diagnostics[0].severity, crate::Severity::Hint);
+    }
+
+    #[test]
+    fn test_multi_root_with_explicit_attrs() {
+        let ast = parse_template(r#"<header /><main v-bind="$attrs" />"#).unwrap();
+        assert!(check_attrs_fallthrough(&ast).is_empty());
+
+        let ast = parse_template("<div>\n  <span />\n</div>\n<!-- c -->").unwrap();
+        assert!(check_attrs_fallthrough(&ast).is_empty());
+    }
+
     #[test]
     fn test_check_v_model_on_div() {
         let ast = parse_template(r#"<div v-model="value">Content</div>"#).unwrap();
@@ -49,6 +49,35 @@ impl Sfc {
     pub fn is_typescript(&self) -> bool {
         matches!(self.script_lang(), Some("ts" | "tsx"))
     }
+
+    /// Get the Vue version pinned by a root `<!-- @vue-target 3.3 -->`
+    /// comment, which overrides `vueCompilerOptions.target` for this file.
+    pub fn vue_target(&self) -> Option<f32> {
+        self.comments.iter().find_map(|comment| {
+            comment
+                .content
+                .trim()
+                .strip_prefix("@vue-target")?
+                .trim()
+                .parse()
+                .ok()
+        })
+    }
+}
+
+/// Identifies a block within an SFC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockKind {
+    /// The `<template>` block.
+    Template,
+    /// The regular `<script>` block.
+    Script,
+    /// The `<script setup>` block.
+    ScriptSetup,
+    /// A `<style>` block, by index.
+    Style(usize),
+    /// A custom block, by index.
+    Custom(usize),
 }
 
 /// A block in the SFC with common properties.
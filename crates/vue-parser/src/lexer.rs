@@ -21,6 +21,11 @@ impl<'a> SfcLexer<'a> {
 
     /// Get the remaining source.
     pub fn remaining(&self) -> &'a str {
+        debug_assert!(
+            self.source.is_char_boundary(self.pos),
+            "lexer position {} is not a char boundary",
+            self.pos
+        );
         &self.source[self.pos..]
     }
 
@@ -57,6 +62,7 @@ impl<'a> SfcLexer<'a> {
     /// Consume a string if the remaining source starts with it.
     pub fn consume(&mut self, s: &str) -> bool {
         if self.starts_with(s) {
+            // `s` matched in full, so this always lands on a char boundary
             self.pos += s.len();
             true
         } else {
@@ -194,9 +200,9 @@ impl<'a> SfcLexer<'a> {
         let pattern = format!("</{}", closing_tag);
 
         while !self.remaining().is_empty() {
-            // Check for closing tag (case-insensitive)
-            if self.remaining().len() >= pattern.len() {
-                let potential = &self.remaining()[..pattern.len()];
+            // Check for closing tag (case-insensitive). The slice may end
+            // mid-codepoint when multi-byte text precedes it, which can't match.
+            if let Some(potential) = self.remaining().get(..pattern.len()) {
                 if potential.eq_ignore_ascii_case(&pattern) {
                     // Check if followed by > or whitespace
                     let after = self.remaining().chars().nth(pattern.len());
@@ -257,4 +263,14 @@ mod tests {
         let content = lexer.read_block_content("template");
         assert_eq!(content, "<div>Hello</div>");
     }
+
+    #[test]
+    fn test_read_block_content_multi_byte() {
+        // The closing-tag probe would end inside the last `é`
+        let mut lexer = SfcLexer::new("aééééé</template>");
+        let content = lexer.read_block_content("template");
+        assert_eq!(content, "aééééé");
+        assert!(lexer.consume("</template>"));
+        assert!(lexer.is_eof());
+    }
 }
@@ -1,8 +1,9 @@
 //! Parser for Vue Single File Components.
 
 use crate::ast::*;
-use crate::error::{ParseError, ParseResult};
+use crate::error::{ErrorCode, ParseError, ParseResult};
 use crate::lexer::SfcLexer;
+use smol_str::SmolStr;
 use source_map::Span;
 
 /// Parse a Vue SFC from source code.
@@ -32,6 +33,10 @@ impl<'a> SfcParser<'a> {
     fn parse(&mut self) -> ParseResult<Sfc> {
         let mut sfc = Sfc::new(self.source.to_string());
 
+        // A UTF-8 BOM is not content. Spans stay relative to the original
+        // source, BOM included, so they match the file as read from disk.
+        self.lexer.consume("\u{FEFF}");
+
         while !self.lexer.is_eof() {
             self.lexer.skip_whitespace();
 
@@ -325,6 +330,118 @@ fn get_src_attr(attrs: &[BlockAttr]) -> Option<SrcAttr> {
         })
 }
 
+impl Sfc {
+    /// Replace the content of a single block without reparsing the whole file.
+    ///
+    /// Spans after the block are shifted by the change in length, so callers
+    /// only need to recompile the edited block, as `vue_codegen::reparse_block`
+    /// does. Block tags and attributes are kept as they are.
+    pub fn replace_block_content(&mut self, kind: BlockKind, new_content: &str) -> ParseResult<()> {
+        let tag: SmolStr = match kind {
+            BlockKind::Template => "template".into(),
+            BlockKind::Script | BlockKind::ScriptSetup => "script".into(),
+            BlockKind::Style(_) => "style".into(),
+            BlockKind::Custom(index) => self
+                .custom_blocks
+                .get(index)
+                .map(|b| b.block_type.clone())
+                .unwrap_or_default(),
+        };
+        let old = self
+            .block_mut(kind)
+            .map(|block| block.content_span)
+            .ok_or_else(|| {
+                ParseError::new(
+                    format!("No {} block to update", tag),
+                    Span::empty(0),
+                    ErrorCode::InvalidContent,
+                )
+            })?;
+
+        // A closing tag in the content would change the block structure
+        if new_content.contains(&format!("</{}", tag)) {
+            return Err(ParseError::new(
+                format!("Block content can't contain </{}>", tag),
+                old,
+                ErrorCode::InvalidContent,
+            ));
+        }
+
+        self.content.replace_range(old.to_range(), new_content);
+        let delta = new_content.len() as i64 - old.len() as i64;
+        self.shift_spans_after(old.end, delta);
+
+        let block = self.block_mut(kind).expect("block exists");
+        block.content = new_content.to_string();
+        block.content_span = Span::new(old.start, old.start + new_content.len() as u32);
+        Ok(())
+    }
+
+    /// Get the common properties of a block.
+    fn block_mut(&mut self, kind: BlockKind) -> Option<&mut SfcBlock> {
+        match kind {
+            BlockKind::Template => self.template.as_mut().map(|b| &mut b.block),
+            BlockKind::Script => self.script.as_mut().map(|b| &mut b.block),
+            BlockKind::ScriptSetup => self.script_setup.as_mut().map(|b| &mut b.block),
+            BlockKind::Style(index) => self.styles.get_mut(index).map(|b| &mut b.block),
+            BlockKind::Custom(index) => self.custom_blocks.get_mut(index).map(|b| &mut b.block),
+        }
+    }
+
+    /// Shift every span position after `offset` by `delta` bytes.
+    fn shift_spans_after(&mut self, offset: u32, delta: i64) {
+        // A span around the edit, like the edited block's, only grows or
+        // shrinks at the end
+        let shift = |span: &mut Span| {
+            if span.start > offset {
+                *span = span.shift(delta);
+            } else if span.end > offset {
+                span.end = Span::empty(span.end).shift(delta).end;
+            }
+        };
+
+        let mut srcs = Vec::new();
+        let mut blocks = Vec::new();
+        if let Some(template) = &mut self.template {
+            srcs.extend(template.src.as_mut());
+            blocks.push(&mut template.block);
+        }
+        if let Some(script) = &mut self.script {
+            srcs.extend(script.src.as_mut());
+            blocks.push(&mut script.block);
+        }
+        if let Some(script_setup) = &mut self.script_setup {
+            if let Some(span) = &mut script_setup.generic_span {
+                shift(span);
+            }
+            blocks.push(&mut script_setup.block);
+        }
+        for style in &mut self.styles {
+            srcs.extend(style.src.as_mut());
+            blocks.push(&mut style.block);
+        }
+        blocks.extend(self.custom_blocks.iter_mut().map(|b| &mut b.block));
+
+        for block in blocks {
+            shift(&mut block.span);
+            shift(&mut block.content_span);
+            for attr in &mut block.attrs {
+                shift(&mut attr.span);
+                if let Some(span) = &mut attr.value_span {
+                    shift(span);
+                }
+            }
+        }
+        for src in srcs {
+            shift(&mut src.span);
+            shift(&mut src.value_span);
+        }
+        for comment in &mut self.comments {
+            shift(&mut comment.span);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -347,6 +464,37 @@ mod tests {
         assert_eq!(template.content.trim(), "<div>Hello</div>");
     }
 
+    #[test]
+    fn test_parse_multi_byte_block_content() {
+        let source = "<template>éaéééé</template>\n<style>ü{}</style>";
+        let sfc = parse_sfc(source).unwrap();
+        let template = sfc.template.unwrap();
+        assert_eq!(template.content, "éaéééé");
+        assert_eq!(
+            &source[template.content_span.start as usize..template.content_span.end as usize],
+            "éaéééé"
+        );
+        assert_eq!(sfc.styles[0].content, "ü{}");
+    }
+
+    #[test]
+    fn test_parse_with_bom() {
+        let source = "\u{FEFF}\n\n<template><div>Hi</div></template>\n<script setup>\nconst a = 1\n</script>";
+        let sfc = parse_sfc(source).unwrap();
+        let template = sfc.template.unwrap();
+        assert_eq!(template.content, "<div>Hi</div>");
+        assert_eq!(template.span.start, 5);
+        assert_eq!(
+            &source[template.content_span.start as usize..template.content_span.end as usize],
+            "<div>Hi</div>"
+        );
+        let script = sfc.script_setup.unwrap();
+        assert_eq!(
+            &source[script.content_span.start as usize..script.content_span.end as usize],
+            "\nconst a = 1\n"
+        );
+    }
+
     #[test]
     fn test_parse_script_setup() {
         let source = r#"<script setup lang="ts">
@@ -405,6 +553,17 @@ defineProps<{ value: T; other: U }>()
         let sfc = parse_sfc(source).unwrap();
         assert_eq!(sfc.comments.len(), 1);
         assert!(sfc.comments[0].content.contains("This is a comment"));
+        assert_eq!(sfc.vue_target(), None);
+    }
+
+    #[test]
+    fn test_vue_target_comment() {
+        let sfc = parse_sfc("<!-- @vue-target 3.3 -->\n<template><div /></template>").unwrap();
+        assert_eq!(sfc.vue_target(), Some(3.3));
+
+        // Only a version counts
+        let sfc = parse_sfc("<!-- @vue-target latest -->\n<template><div /></template>").unwrap();
+        assert_eq!(sfc.vue_target(), None);
     }
 
     #[test]
@@ -432,6 +591,50 @@ const props = defineProps<Props>()
         assert_eq!(sfc.styles[0].module.as_deref(), Some("classes"));
     }
 
+    #[test]
+    fn test_replace_template_content() {
+        let source = "<template>\n  <div>a</div>\n</template>\n\n<script setup lang=\"ts\">\nconst a = 1\n</script>\n<!-- end -->\n";
+        let mut sfc = parse_sfc(source).unwrap();
+        let script_before = sfc.script_setup.clone().unwrap();
+
+        let new_template = "\n  <section>{{ a }}</section>\n";
+        sfc.replace_block_content(BlockKind::Template, new_template)
+            .unwrap();
+
+        // The result matches a full parse of the edited file
+        let full = parse_sfc(&sfc.content).unwrap();
+        let template = sfc.template.as_ref().unwrap();
+        assert_eq!(template.content, new_template);
+        assert_eq!(
+            template.content_span,
+            full.template.as_ref().unwrap().content_span
+        );
+        assert_eq!(template.span, full.template.as_ref().unwrap().span);
+
+        let script = sfc.script_setup.as_ref().unwrap();
+        assert_eq!(script.content, script_before.content);
+        assert_eq!(
+            script.content_span,
+            full.script_setup.as_ref().unwrap().content_span
+        );
+        assert_eq!(&sfc.content[script.content_span.to_range()], script.content);
+        assert_eq!(
+            script.get_attr("lang"),
+            full.script_setup.as_ref().unwrap().get_attr("lang")
+        );
+        assert_eq!(sfc.comments[0].span, full.comments[0].span);
+    }
+
+    #[test]
+    fn test_replace_block_content_rejects_closing_tag() {
+        let mut sfc = parse_sfc("<template><div /></template>").unwrap();
+        let err = sfc
+            .replace_block_content(BlockKind::Template, "<div /></template><template>")
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidContent);
+        assert!(sfc.replace_block_content(BlockKind::Script, "").is_err());
+    }
+
     #[test]
     fn test_parse_external_src() {
         let source = r#"<script src="./external.ts" lang="ts"></script>"#;
@@ -1,5 +1,6 @@
 //! AST types for Vue templates.
 
+use crate::error::CompileError;
 use indexmap::IndexMap;
 use smol_str::SmolStr;
 use source_map::Span;
@@ -16,6 +17,9 @@ pub struct TemplateAst {
     pub scope_vars: Vec<ScopeVar>,
     /// Source span of the entire template.
     pub span: Span,
+    /// Recoverable errors found while parsing.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub errors: Vec<CompileError>,
 }
 
 impl TemplateAst {
@@ -32,6 +36,73 @@ impl TemplateAst {
             ..Default::default()
         }
     }
+
+    /// Iterate over every expression in the template, in source order within
+    /// each element.
+    ///
+    /// This covers interpolations, bound props, event handlers, v-for sources,
+    /// v-if conditions, dynamic directive arguments and directive values.
+    /// Slot props and v-for aliases are binding patterns, not expressions, so
+    /// they're skipped. Spans are those of the parsed source.
+    pub fn expressions(&self) -> impl Iterator<Item = &Expression> {
+        let mut expressions = Vec::new();
+        collect_expressions(&self.children, &mut expressions);
+        expressions.into_iter()
+    }
+}
+
+/// Collect the expressions of `nodes` and their descendants.
+fn collect_expressions<'a>(nodes: &'a [TemplateNode], out: &mut Vec<&'a Expression>) {
+    for node in nodes {
+        match node {
+            TemplateNode::Element(el) => {
+                collect_directive_expressions(&el.directives, out);
+                out.extend(el.props.iter().map(|p| &p.value));
+                out.extend(el.events.iter().map(|e| &e.handler));
+                collect_expressions(&el.children, out);
+                for slot in el.slots.values() {
+                    collect_expressions(&slot.children, out);
+                }
+            }
+            TemplateNode::Interpolation(i) => out.push(&i.expression),
+            TemplateNode::If(i) => {
+                for branch in &i.branches {
+                    out.extend(&branch.condition);
+                    collect_expressions(&branch.children, out);
+                }
+            }
+            TemplateNode::For(f) => {
+                out.push(&f.source);
+                out.extend(&f.key_attr);
+                collect_expressions(&f.children, out);
+            }
+            TemplateNode::SlotOutlet(s) => {
+                if !s.name.is_static {
+                    out.push(&s.name);
+                }
+                out.extend(s.props.iter().map(|p| &p.value));
+                collect_expressions(&s.fallback, out);
+            }
+            TemplateNode::Template(t) => {
+                collect_directive_expressions(&t.directives, out);
+                collect_expressions(&t.children, out);
+            }
+            TemplateNode::Text(_) | TemplateNode::Comment(_) => {}
+        }
+    }
+}
+
+/// Collect the dynamic arguments and values of directives.
+fn collect_directive_expressions<'a>(directives: &'a [Directive], out: &mut Vec<&'a Expression>) {
+    for directive in directives {
+        if let Some(DirectiveArg::Dynamic(arg)) = &directive.arg {
+            out.push(arg);
+        }
+        // A v-slot value is a binding pattern
+        if !directive.is_slot() {
+            out.extend(&directive.value);
+        }
+    }
 }
 
 /// A node in the template AST.
@@ -111,6 +182,31 @@ impl ElementNode {
         self.directives.iter().find(|d| d.name == name)
     }
 
+    /// Get the component named by an in-DOM `is="vue:Name"` attribute.
+    ///
+    /// `<component>` resolves its own `is`, so this only applies to other
+    /// elements, such as `<tr is="vue:MyRow">` inside a `<table>`.
+    pub fn vue_is_target(&self) -> Option<&str> {
+        if self.tag == "component" {
+            return None;
+        }
+        let value = self
+            .attrs
+            .iter()
+            .find(|a| a.name == "is")?
+            .value
+            .as_deref()?;
+        value
+            .trim()
+            .strip_prefix("vue:")
+            .filter(|name| !name.is_empty())
+    }
+
+    /// Get the name this element resolves to as a component.
+    pub fn component_name(&self) -> &str {
+        self.vue_is_target().unwrap_or(&self.tag)
+    }
+
     /// Check if this is a built-in element.
     pub fn is_builtin(&self) -> bool {
         matches!(
@@ -209,10 +305,49 @@ pub struct Prop {
     pub value: Expression,
     /// Whether this is a dynamic prop name.
     pub is_dynamic: bool,
+    /// Modifiers (e.g., .camel, .prop, .attr).
+    pub modifiers: Vec<SmolStr>,
     /// Source span.
     pub span: Span,
 }
 
+impl Prop {
+    /// Check if the binding has a modifier.
+    pub fn has_modifier(&self, modifier: &str) -> bool {
+        self.modifiers.iter().any(|m| m == modifier)
+    }
+
+    /// Check if the binding is forced onto the DOM with `.prop` or `.attr`.
+    ///
+    /// These bindings skip component props and target the rendered element.
+    pub fn is_dom_binding(&self) -> bool {
+        self.has_modifier("prop") || self.has_modifier("attr")
+    }
+
+    /// Get the name this prop binds to on a component.
+    ///
+    /// Components declare props in camelCase, so `:my-prop` binds to `myProp`.
+    /// Native element attributes keep their name as written.
+    pub fn component_prop_name(&self) -> String {
+        if self.is_dynamic {
+            self.name.to_string()
+        } else {
+            crate::transforms::camelize(&self.name)
+        }
+    }
+
+    /// Get the name this prop binds to on a native element.
+    ///
+    /// The `.camel` modifier renames `:view-box.camel` to `viewBox`.
+    pub fn element_prop_name(&self) -> String {
+        if !self.is_dynamic && self.has_modifier("camel") {
+            crate::transforms::camelize(&self.name)
+        } else {
+            self.name.to_string()
+        }
+    }
+}
+
 /// An event listener.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -305,6 +440,8 @@ pub struct IfBranch {
     pub children: Vec<TemplateNode>,
     /// Source span.
     pub span: Span,
+    /// Span of the `v-if`/`v-else-if`/`v-else` directive.
+    pub directive_span: Span,
 }
 
 /// Type of if branch.
@@ -0,0 +1,271 @@
+//! Readable tree dumps of template ASTs, for debugging the parser.
+
+use crate::ast::*;
+use source_map::Span;
+use std::fmt::Write;
+
+impl TemplateAst {
+    /// Render the tree as indented text, one node or attribute per line.
+    ///
+    /// Each line shows the node kind, the relevant source text and the span.
+    /// `source` is the template the AST was parsed from.
+    pub fn dump(&self, source: &str) -> String {
+        let mut out = String::new();
+        line(&mut out, 0, "Template", self.span);
+        dump_nodes(&mut out, source, &self.children, 1);
+        for error in &self.errors {
+            line(
+                &mut out,
+                1,
+                &format!("error {:?}", error.message),
+                error.span,
+            );
+        }
+        out
+    }
+}
+
+/// Append one line of the dump.
+fn line(out: &mut String, depth: usize, text: &str, span: Span) {
+    let _ = writeln!(
+        out,
+        "{}{} @{}..{}",
+        "  ".repeat(depth),
+        text,
+        span.start,
+        span.end
+    );
+}
+
+/// Get the source text of an expression, as written.
+fn expr(source: &str, expression: &Expression) -> String {
+    let text = source
+        .get(expression.span.to_range())
+        .unwrap_or(&expression.content);
+    format!("`{}`", text)
+}
+
+/// Format a directive or binding name with its modifiers.
+fn with_modifiers(name: &str, modifiers: &[smol_str::SmolStr]) -> String {
+    let mut name = name.to_string();
+    for modifier in modifiers {
+        name.push('.');
+        name.push_str(modifier);
+    }
+    name
+}
+
+fn dump_nodes(out: &mut String, source: &str, nodes: &[TemplateNode], depth: usize) {
+    for node in nodes {
+        dump_node(out, source, node, depth);
+    }
+}
+
+fn dump_node(out: &mut String, source: &str, node: &TemplateNode, depth: usize) {
+    match node {
+        TemplateNode::Element(el) => {
+            let kind = if el.is_component {
+                "Component"
+            } else {
+                "Element"
+            };
+            line(out, depth, &format!("{} <{}>", kind, el.tag), el.span);
+            dump_attributes(out, &el.attrs, depth + 1);
+            dump_directives(out, source, &el.directives, depth + 1);
+            for prop in &el.props {
+                let name = if prop.is_dynamic {
+                    format!(":[{}]", prop.name)
+                } else {
+                    format!(":{}", prop.name)
+                };
+                let name = with_modifiers(&name, &prop.modifiers);
+                line(
+                    out,
+                    depth + 1,
+                    &format!("{} {}", name, expr(source, &prop.value)),
+                    prop.span,
+                );
+            }
+            for event in &el.events {
+                let name = if event.is_dynamic {
+                    format!("@[{}]", event.name)
+                } else {
+                    format!("@{}", event.name)
+                };
+                let name = with_modifiers(&name, &event.modifiers);
+                line(
+                    out,
+                    depth + 1,
+                    &format!("{} {}", name, expr(source, &event.handler)),
+                    event.span,
+                );
+            }
+            dump_nodes(out, source, &el.children, depth + 1);
+            for slot in el.slots.values() {
+                let text = match &slot.props {
+                    Some(props) => format!("Slot #{} `{}`", slot.name, props.pattern),
+                    None => format!("Slot #{}", slot.name),
+                };
+                line(out, depth + 1, &text, slot.span);
+                dump_nodes(out, source, &slot.children, depth + 2);
+            }
+        }
+        TemplateNode::Text(text) => {
+            line(out, depth, &format!("Text {:?}", text.content), text.span);
+        }
+        TemplateNode::Interpolation(i) => {
+            line(
+                out,
+                depth,
+                &format!("Interpolation {}", expr(source, &i.expression)),
+                i.span,
+            );
+        }
+        TemplateNode::Comment(comment) => {
+            line(
+                out,
+                depth,
+                &format!("Comment {:?}", comment.content),
+                comment.span,
+            );
+        }
+        TemplateNode::If(if_node) => {
+            line(out, depth, "If", if_node.span);
+            for branch in &if_node.branches {
+                let directive = match branch.branch_type {
+                    IfBranchType::If => "v-if",
+                    IfBranchType::ElseIf => "v-else-if",
+                    IfBranchType::Else => "v-else",
+                };
+                let text = match &branch.condition {
+                    Some(condition) => format!("{} {}", directive, expr(source, condition)),
+                    None => directive.to_string(),
+                };
+                line(out, depth + 1, &text, branch.span);
+                dump_nodes(out, source, &branch.children, depth + 2);
+            }
+        }
+        TemplateNode::For(for_node) => {
+            let mut aliases = vec![for_node.value.pattern.as_str()];
+            aliases.extend(for_node.key.as_ref().map(|a| a.pattern.as_str()));
+            aliases.extend(for_node.index.as_ref().map(|a| a.pattern.as_str()));
+            line(
+                out,
+                depth,
+                &format!(
+                    "For ({}) in {}",
+                    aliases.join(", "),
+                    expr(source, &for_node.source)
+                ),
+                for_node.span,
+            );
+            if let Some(key) = &for_node.key_attr {
+                line(
+                    out,
+                    depth + 1,
+                    &format!(":key {}", expr(source, key)),
+                    key.span,
+                );
+            }
+            dump_nodes(out, source, &for_node.children, depth + 1);
+        }
+        TemplateNode::SlotOutlet(slot) => {
+            let name = if slot.name.is_static {
+                format!("{:?}", slot.name.content)
+            } else {
+                expr(source, &slot.name)
+            };
+            line(out, depth, &format!("SlotOutlet {}", name), slot.span);
+            for prop in &slot.props {
+                line(
+                    out,
+                    depth + 1,
+                    &format!(":{} {}", prop.name, expr(source, &prop.value)),
+                    prop.span,
+                );
+            }
+            dump_nodes(out, source, &slot.fallback, depth + 1);
+        }
+        TemplateNode::Template(template) => {
+            line(out, depth, "Template <template>", template.span);
+            dump_directives(out, source, &template.directives, depth + 1);
+            dump_nodes(out, source, &template.children, depth + 1);
+        }
+    }
+}
+
+fn dump_attributes(out: &mut String, attrs: &[Attribute], depth: usize) {
+    for attr in attrs {
+        let text = match &attr.value {
+            Some(value) => format!("attr {}={:?}", attr.name, value),
+            None => format!("attr {}", attr.name),
+        };
+        line(out, depth, &text, attr.span);
+    }
+}
+
+fn dump_directives(out: &mut String, source: &str, directives: &[Directive], depth: usize) {
+    for directive in directives {
+        let mut name = format!("v-{}", directive.name);
+        match &directive.arg {
+            Some(DirectiveArg::Static(arg, _)) => {
+                name.push(':');
+                name.push_str(arg);
+            }
+            Some(DirectiveArg::Dynamic(arg)) => {
+                name.push_str(":[");
+                name.push_str(&arg.content);
+                name.push(']');
+            }
+            None => {}
+        }
+        let name = with_modifiers(&name, &directive.modifiers);
+        let text = match &directive.value {
+            Some(value) => format!("{} {}", name, expr(source, value)),
+            None => name,
+        };
+        line(out, depth, &text, directive.span);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_template;
+
+    #[test]
+    fn test_dump_template() {
+        let source = r#"<ul class="list"><li v-for="(item, i) in items" :key="item.id" @click.stop="pick(i)">{{ item.name }}</li><p v-if="!items.length">Empty</p><!-- end --></ul>"#;
+        let ast = parse_template(source).unwrap();
+        let expected = "\
+Template @0..155
+  Element <ul> @0..155
+    attr class=\"list\" @4..16
+    For (item, i) in `items` @17..105
+      :key `item.id` @54..61
+      Element <li> @17..105
+        @click.stop `pick(i)` @63..84
+        Interpolation `item.name` @85..100
+    If @105..138
+      v-if `!items.length` @105..138
+        Element <p> @105..138
+          Text \"Empty\" @129..134
+    Comment \" end \" @138..150
+";
+        assert_eq!(ast.dump(source), expected);
+    }
+
+    #[test]
+    fn test_dump_slots() {
+        let source = r#"<List><template #item="{ row }">{{ row }}</template></List><slot />"#;
+        let ast = parse_template(source).unwrap();
+        let expected = "\
+Template @0..67
+  Component <List> @0..59
+    Template <template> @6..52
+      v-slot:item `{ row }` @16..31
+      Interpolation `row` @32..41
+  SlotOutlet \"default\" @59..67
+";
+        assert_eq!(ast.dump(source), expected);
+    }
+}
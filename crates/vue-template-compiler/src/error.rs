@@ -82,8 +82,12 @@ pub enum CompileErrorCode {
     InvalidVFor,
     /// Invalid v-model syntax.
     InvalidVModel,
+    /// `v-else`/`v-else-if` without an adjacent `v-if`.
+    InvalidVIfChain,
     /// Component resolution error.
     ComponentResolution,
+    /// Elements nested deeper than the parser allows.
+    NestingTooDeep,
 }
 
 impl CompileErrorCode {
@@ -98,7 +102,9 @@ impl CompileErrorCode {
             Self::InvalidSlot => "invalid-slot",
             Self::InvalidVFor => "invalid-v-for",
             Self::InvalidVModel => "invalid-v-model",
+            Self::InvalidVIfChain => "invalid-v-if-chain",
             Self::ComponentResolution => "component-resolution",
+            Self::NestingTooDeep => "nesting-too-deep",
         }
     }
 }
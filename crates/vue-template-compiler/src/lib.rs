@@ -5,13 +5,14 @@
 //! and code generation.
 
 pub mod ast;
+mod dump;
 pub mod error;
 pub mod parser;
 pub mod transforms;
 
 pub use ast::*;
-pub use error::{CompileError, CompileResult};
-pub use parser::parse_template;
+pub use error::{CompileError, CompileErrorCode, CompileResult};
+pub use parser::{parse_template, parse_template_with_options, ParseOptions, DEFAULT_MAX_DEPTH};
 
 /// Compile a Vue template to AST.
 pub fn compile(source: &str) -> CompileResult<TemplateAst> {
@@ -29,6 +30,59 @@ mod tests {
         assert_eq!(ast.children.len(), 1);
     }
 
+    #[test]
+    fn test_compile_reparsed_template_block() {
+        let source =
+            "<script setup>\nconst a = 1\n</script>\n<template><div>{{ a }}</div></template>\n";
+        let mut sfc = vue_parser::parse_sfc(source).unwrap();
+        let script = sfc.script_setup.clone().unwrap();
+
+        sfc.replace_block_content(vue_parser::BlockKind::Template, "<p>{{ a }}</p><span />")
+            .unwrap();
+        let ast = compile(&sfc.template.as_ref().unwrap().content).unwrap();
+        assert_eq!(ast.children.len(), 2);
+        assert!(matches!(&ast.children[0], TemplateNode::Element(el) if el.tag == "p"));
+
+        // The script block comes first, so nothing about it moves
+        let after = sfc.script_setup.as_ref().unwrap();
+        assert_eq!(after.span, script.span);
+        assert_eq!(after.content, script.content);
+    }
+
+    #[test]
+    fn test_template_expressions() {
+        let source = r#"<ul v-if="show" :class="cls">
+            <li v-for="item in items" :key="item.id" @click="select(item)">{{ item.name }}</li>
+            <MyList><template #row="{ row }"><b v-text="row" /></template></MyList>
+            <slot :name="slotName" :data="data" />
+        </ul>"#;
+        let ast = compile(source).unwrap();
+        let expressions: Vec<_> = ast.expressions().map(|e| e.content.as_str()).collect();
+        assert_eq!(
+            expressions,
+            [
+                "show",
+                "cls",
+                "items",
+                "item.id",
+                "select(item)",
+                "item.name",
+                "row",
+                "slotName",
+                "data"
+            ]
+        );
+
+        // Spans point at the expression text
+        for expression in ast.expressions() {
+            let span = expression.span;
+            assert_eq!(
+                &source[span.start as usize..span.end as usize],
+                expression.content
+            );
+        }
+    }
+
     #[test]
     fn test_compile_with_directives() {
         let source = r#"<div v-if="show" v-for="item in items" :class="{ active: isActive }">
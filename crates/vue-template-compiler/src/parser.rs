@@ -2,30 +2,105 @@
 
 use crate::ast::*;
 use crate::error::{CompileError, CompileErrorCode, CompileResult};
+use crate::transforms::camelize;
 use smol_str::SmolStr;
 use source_map::Span;
 
+/// The element nesting depth templates may reach by default.
+///
+/// Parsing, diagnostics and code generation at this depth fit in a 2 MiB
+/// thread stack (the default for spawned and rayon threads) even in debug
+/// builds, which use several kilobytes of stack per level.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// Options for template parsing.
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Tags to treat as native custom elements rather than Vue components.
+    pub custom_element_tags: Vec<String>,
+    /// Registered component names, treated as components regardless of casing.
+    ///
+    /// Matching ignores case and hyphens, so `my-card` matches `MyCard`.
+    pub component_tags: Vec<String>,
+    /// The deepest element nesting to parse, or `None` for
+    /// [`DEFAULT_MAX_DEPTH`].
+    ///
+    /// Parsing and every later pass over the AST recurse per level, so this
+    /// keeps a pathological template from overflowing the stack.
+    pub max_depth: Option<usize>,
+}
+
+impl ParseOptions {
+    /// Check if a tag is a configured custom element.
+    pub fn is_custom_element(&self, tag: &str) -> bool {
+        self.custom_element_tags.iter().any(|t| t == tag)
+    }
+
+    /// Check if a tag is a registered component.
+    pub fn is_registered_component(&self, tag: &str) -> bool {
+        let same = |a: &str| {
+            let mut a = a.chars().filter(|&c| c != '-');
+            let mut b = tag.chars().filter(|&c| c != '-');
+            loop {
+                match (a.next(), b.next()) {
+                    (Some(x), Some(y)) if x.eq_ignore_ascii_case(&y) => {}
+                    (None, None) => return true,
+                    _ => return false,
+                }
+            }
+        };
+        self.component_tags.iter().any(|t| same(t))
+    }
+
+    /// Check if a tag is parsed as a component.
+    ///
+    /// Configured custom elements are always native, and registered
+    /// components always components; other tags use the casing heuristic.
+    pub fn is_component(&self, tag: &str) -> bool {
+        if self.is_custom_element(tag) {
+            return false;
+        }
+        match get_element_type(tag) {
+            ElementType::Component => true,
+            ElementType::Element => self.is_registered_component(tag),
+            ElementType::Builtin => false,
+        }
+    }
+}
+
 /// Parse a Vue template into an AST.
 pub fn parse_template(source: &str) -> CompileResult<TemplateAst> {
-    let mut parser = TemplateParser::new(source);
+    parse_template_with_options(source, &ParseOptions::default())
+}
+
+/// Parse a Vue template into an AST with the given options.
+pub fn parse_template_with_options(
+    source: &str,
+    options: &ParseOptions,
+) -> CompileResult<TemplateAst> {
+    let mut parser = TemplateParser::new(source, options);
     parser.parse()
 }
 
 /// Parser for Vue templates.
-#[allow(dead_code)]
 struct TemplateParser<'a> {
     source: &'a str,
     pos: usize,
     errors: Vec<CompileError>,
+    options: &'a ParseOptions,
+    /// How many elements enclose the current position.
+    depth: usize,
 }
 
 impl<'a> TemplateParser<'a> {
     /// Create a new parser.
-    fn new(source: &'a str) -> Self {
+    fn new(source: &'a str, options: &'a ParseOptions) -> Self {
         Self {
             source,
             pos: 0,
             errors: Vec::new(),
+            options,
+            depth: 0,
         }
     }
 
@@ -33,7 +108,9 @@ impl<'a> TemplateParser<'a> {
     fn parse(&mut self) -> CompileResult<TemplateAst> {
         let children = self.parse_children(None)?;
         let span = Span::new(0, self.source.len() as u32);
-        Ok(TemplateAst::with_children(children, span))
+        let mut ast = TemplateAst::with_children(children, span);
+        ast.errors = std::mem::take(&mut self.errors);
+        Ok(ast)
     }
 
     /// Get remaining source.
@@ -130,14 +207,59 @@ impl<'a> TemplateParser<'a> {
             }
 
             // Parse node
-            if let Some(node) = self.parse_node()? {
-                children.push(node);
+            match self.parse_node()? {
+                Some(TemplateNode::If(if_node))
+                    if if_node.branches[0].branch_type != IfBranchType::If =>
+                {
+                    self.attach_if_branch(&mut children, if_node);
+                }
+                Some(node) => children.push(node),
+                None => {}
             }
         }
 
         Ok(children)
     }
 
+    /// Attach a `v-else-if`/`v-else` branch to the preceding `v-if` chain.
+    ///
+    /// Only whitespace and comments may separate the branches. A branch that
+    /// can't attach is kept as-is and recorded as an error.
+    fn attach_if_branch(&mut self, children: &mut Vec<TemplateNode>, if_node: IfNode) {
+        let prev = children.iter().rposition(|node| match node {
+            TemplateNode::Text(text) => !text.content.trim().is_empty(),
+            TemplateNode::Comment(_) => false,
+            _ => true,
+        });
+
+        if let Some(index) = prev {
+            if let TemplateNode::If(prev) = &mut children[index] {
+                if prev
+                    .branches
+                    .last()
+                    .is_some_and(|b| b.branch_type != IfBranchType::Else)
+                {
+                    prev.span = prev.span.merge(if_node.span);
+                    prev.branches.extend(if_node.branches);
+                    children.truncate(index + 1);
+                    return;
+                }
+            }
+        }
+
+        let branch = &if_node.branches[0];
+        let directive = match branch.branch_type {
+            IfBranchType::ElseIf => "v-else-if",
+            _ => "v-else",
+        };
+        self.errors.push(CompileError::new(
+            format!("{} has no adjacent v-if or v-else-if", directive),
+            branch.directive_span,
+            CompileErrorCode::InvalidVIfChain,
+        ));
+        children.push(TemplateNode::If(if_node));
+    }
+
     /// Parse a single node.
     fn parse_node(&mut self) -> CompileResult<Option<TemplateNode>> {
         // Comment
@@ -183,9 +305,11 @@ impl<'a> TemplateParser<'a> {
     fn parse_interpolation(&mut self) -> CompileResult<InterpolationNode> {
         let start = self.pos;
         self.consume("{{");
-        let expr_start = self.pos;
-        let content = self.read_until("}}").trim();
-        let expr_end = self.pos;
+        let raw = self.read_until("}}");
+        let content = raw.trim();
+        // The expression span covers the trimmed content
+        let expr_start = self.pos - raw.len() + (raw.len() - raw.trim_start().len());
+        let expr_end = expr_start + content.len();
         self.consume("}}");
         let span = Span::new(start as u32, self.pos as u32);
         let expr_span = Span::new(expr_start as u32, expr_end as u32);
@@ -233,7 +357,7 @@ impl<'a> TemplateParser<'a> {
         }
 
         // Parse attributes
-        let (attrs, directives, props, events) = self.parse_attributes()?;
+        let (attrs, directives, mut props, events) = self.parse_attributes()?;
 
         self.skip_whitespace();
 
@@ -250,7 +374,21 @@ impl<'a> TemplateParser<'a> {
         let children = if self_closing || is_void {
             Vec::new()
         } else {
-            self.parse_children(Some(&tag))?
+            let max_depth = self.options.max_depth.unwrap_or(DEFAULT_MAX_DEPTH);
+            if self.depth >= max_depth {
+                return Err(CompileError::new(
+                    format!(
+                        "Template nesting exceeds the maximum depth of {}",
+                        max_depth
+                    ),
+                    tag_span,
+                    CompileErrorCode::NestingTooDeep,
+                ));
+            }
+            self.depth += 1;
+            let children = self.parse_children(Some(&tag));
+            self.depth -= 1;
+            children?
         };
 
         // Consume closing tag
@@ -275,41 +413,46 @@ impl<'a> TemplateParser<'a> {
 
         // Handle v-for
         if let Some(dir) = v_for {
-            if let Some(ref value) = dir.value {
-                let for_node = self.parse_v_for_expression(&value.content, value.span)?;
-                let mut for_node = for_node;
-                // Check for :key before moving props
-                let key_attr = props
-                    .iter()
-                    .find(|p| p.name == "key")
-                    .map(|p| p.value.clone());
-
-                for_node.children = vec![self.create_element_node(
-                    tag.into(),
-                    tag_span,
-                    attrs,
-                    directives.into_iter().filter(|d| d.name != "for").collect(),
-                    props,
-                    events,
-                    children,
-                    self_closing,
-                    span,
-                )];
-                for_node.span = span;
-                for_node.key_attr = key_attr;
+            let Some(value) = dir.value.as_ref().filter(|v| !v.content.trim().is_empty()) else {
+                return Err(CompileError::new(
+                    "v-for is missing an expression, expected `item in items`",
+                    dir.span,
+                    CompileErrorCode::InvalidVFor,
+                ));
+            };
+            let mut for_node = self.parse_v_for_expression(&value.content, value.span, dir.span)?;
+            // `key` is consumed by the loop, not passed on as a prop
+            let key_attr = props
+                .iter()
+                .position(|p| p.name == "key" && !p.is_dynamic)
+                .map(|i| props.remove(i).value);
 
-                return Ok(TemplateNode::For(for_node));
-            }
+            for_node.children = vec![self.create_element_node(
+                tag.into(),
+                tag_span,
+                attrs,
+                directives.into_iter().filter(|d| d.name != "for").collect(),
+                props,
+                events,
+                children,
+                self_closing,
+                span,
+            )];
+            for_node.span = span;
+            for_node.key_attr = key_attr;
+
+            return Ok(TemplateNode::For(for_node));
         }
 
         // Handle v-if/v-else-if/v-else
         if v_if.is_some() || v_else_if.is_some() || v_else.is_some() {
-            let (branch_type, condition) = if let Some(dir) = v_if {
-                (IfBranchType::If, dir.value.clone())
+            let (branch_type, condition, directive_span) = if let Some(dir) = v_if {
+                (IfBranchType::If, dir.value.clone(), dir.span)
             } else if let Some(dir) = v_else_if {
-                (IfBranchType::ElseIf, dir.value.clone())
+                (IfBranchType::ElseIf, dir.value.clone(), dir.span)
             } else {
-                (IfBranchType::Else, None)
+                let span = v_else.map(|d| d.span).unwrap_or(span);
+                (IfBranchType::Else, None, span)
             };
 
             let filtered_directives: Vec<_> = directives
@@ -334,6 +477,7 @@ impl<'a> TemplateParser<'a> {
                 branch_type,
                 children: vec![element_node],
                 span,
+                directive_span,
             };
 
             return Ok(TemplateNode::If(IfNode {
@@ -358,19 +502,6 @@ impl<'a> TemplateParser<'a> {
             }));
         }
 
-        // Handle template element (for slots, v-if without wrapper, etc.)
-        if tag == "template" {
-            // Check for v-slot
-            let v_slot = directives.iter().find(|d| d.name == "slot");
-            if v_slot.is_some() {
-                return Ok(TemplateNode::Template(TemplateElementNode {
-                    directives,
-                    children,
-                    span,
-                }));
-            }
-        }
-
         // Regular element or component
         Ok(self.create_element_node(
             tag.into(),
@@ -399,8 +530,18 @@ impl<'a> TemplateParser<'a> {
         self_closing: bool,
         span: Span,
     ) -> TemplateNode {
-        let is_component = get_element_type(&tag) == ElementType::Component;
-        TemplateNode::Element(ElementNode {
+        // `<template>` is a fragment: it renders only its children, so it
+        // keeps just its directives (such as `v-slot`)
+        if tag == "template" {
+            return TemplateNode::Template(TemplateElementNode {
+                directives,
+                children,
+                span,
+            });
+        }
+
+        let is_component = self.options.is_component(&tag);
+        let mut el = ElementNode {
             tag,
             is_component,
             attrs,
@@ -412,7 +553,10 @@ impl<'a> TemplateParser<'a> {
             self_closing,
             span,
             tag_span,
-        })
+        };
+        // A native element with `is="vue:Name"` renders the `Name` component
+        el.is_component |= el.vue_is_target().is_some();
+        TemplateNode::Element(el)
     }
 
     /// Parse attributes, directives, props, and events.
@@ -471,22 +615,30 @@ impl<'a> TemplateParser<'a> {
 
             let span = Span::new(attr_start as u32, self.pos as u32);
 
-            // Parse based on prefix
-            if let Some(directive_name) = name.strip_prefix("v-") {
-                // Directive: v-name:arg.mod="value"
-                let directive = self.parse_directive(directive_name, value, span)?;
-                directives.push(directive);
-            } else if let Some(prop_name) = name
+            // Parse based on prefix; `v-bind:` and `v-on:` are checked first
+            // so they parse like their shorthands
+            if let Some(prop_name) = name
                 .strip_prefix(':')
                 .or_else(|| name.strip_prefix("v-bind:"))
             {
                 // Binding: :prop or v-bind:prop
-                let (prop_name, is_dynamic) = parse_prop_name(prop_name);
-                if let Some((val, val_span)) = value {
+                let name_start = attr_start + name.len() - prop_name.len();
+                let (prop_name, is_dynamic, modifiers) = parse_prop_name(prop_name);
+                let value = match value {
+                    Some((val, val_span)) => Some(Expression::new(val, val_span)),
+                    // Same-name shorthand (Vue 3.4+): `:count` binds `count`
+                    None if !is_dynamic => Some(Expression::new(
+                        camelize(prop_name),
+                        Span::new(name_start as u32, (name_start + prop_name.len()) as u32),
+                    )),
+                    None => None,
+                };
+                if let Some(value) = value {
                     props.push(Prop {
                         name: prop_name.into(),
-                        value: Expression::new(val, val_span),
+                        value,
                         is_dynamic,
+                        modifiers: modifiers.into_iter().map(SmolStr::from).collect(),
                         span,
                     });
                 }
@@ -511,6 +663,10 @@ impl<'a> TemplateParser<'a> {
                         span,
                     });
                 }
+            } else if let Some(directive_name) = name.strip_prefix("v-") {
+                // Directive: v-name:arg.mod="value"
+                let directive = self.parse_directive(directive_name, value, span)?;
+                directives.push(directive);
             } else if let Some(slot_name) = name.strip_prefix('#') {
                 // Slot shorthand: #name or #[dynamic]
                 let directive = Directive {
@@ -583,9 +739,8 @@ impl<'a> TemplateParser<'a> {
         span: Span,
     ) -> CompileResult<Directive> {
         // Parse: name:arg.mod1.mod2
-        let mut parts: Vec<&str> = name_with_mods.split('.').collect();
-        let name_and_arg = parts.remove(0);
-        let modifiers: Vec<SmolStr> = parts.iter().map(|s| SmolStr::from(*s)).collect();
+        let (name_and_arg, modifiers) = split_modifiers(name_with_mods);
+        let modifiers: Vec<SmolStr> = modifiers.into_iter().map(SmolStr::from).collect();
 
         let (name, arg) = if let Some(colon_pos) = name_and_arg.find(':') {
             let name = &name_and_arg[..colon_pos];
@@ -610,18 +765,20 @@ impl<'a> TemplateParser<'a> {
     }
 
     /// Parse a v-for expression.
-    fn parse_v_for_expression(&self, expr: &str, span: Span) -> CompileResult<ForNode> {
+    ///
+    /// The second alias is the key for object iteration and the index for
+    /// array iteration; the third alias is only meaningful for objects.
+    fn parse_v_for_expression(
+        &self,
+        expr: &str,
+        span: Span,
+        directive_span: Span,
+    ) -> CompileResult<ForNode> {
         // Patterns:
         // item in items
         // (item, index) in items
         // (value, key, index) in items
-        let expr = expr.trim();
-
-        let (alias_part, source_part) = if let Some(in_pos) = expr.find(" in ") {
-            (&expr[..in_pos], &expr[in_pos + 4..])
-        } else if let Some(of_pos) = expr.find(" of ") {
-            (&expr[..of_pos], &expr[of_pos + 4..])
-        } else {
+        let Some((alias_range, source_range)) = split_v_for_expression(expr) else {
             return Err(CompileError::new(
                 "Invalid v-for expression",
                 span,
@@ -629,68 +786,80 @@ impl<'a> TemplateParser<'a> {
             ));
         };
 
-        let alias_part = alias_part.trim();
-        let source_part = source_part.trim();
-
-        // Parse aliases
-        let (value, key, index) = if alias_part.starts_with('(') && alias_part.ends_with(')') {
-            let inner = &alias_part[1..alias_part.len() - 1];
-            let parts: Vec<&str> = inner.split(',').map(|s| s.trim()).collect();
-            match parts.len() {
-                1 => (
-                    ForAlias {
-                        pattern: parts[0].to_string(),
-                        span,
-                    },
-                    None,
-                    None,
-                ),
-                2 => (
-                    ForAlias {
-                        pattern: parts[0].to_string(),
-                        span,
-                    },
-                    Some(ForAlias {
-                        pattern: parts[1].to_string(),
-                        span,
-                    }),
-                    None,
-                ),
-                3 => (
-                    ForAlias {
-                        pattern: parts[0].to_string(),
-                        span,
-                    },
-                    Some(ForAlias {
-                        pattern: parts[1].to_string(),
-                        span,
-                    }),
-                    Some(ForAlias {
-                        pattern: parts[2].to_string(),
-                        span,
-                    }),
+        let sub_span = |start: usize, end: usize| {
+            Span::new(span.start + start as u32, span.start + end as u32)
+        };
+
+        let (alias_start, alias_end) = trim_range(expr, alias_range);
+        let (source_start, source_end) = trim_range(expr, source_range);
+        let alias_part = &expr[alias_start..alias_end];
+        let alias_span = sub_span(alias_start, alias_end);
+
+        if source_start == source_end {
+            return Err(CompileError::new(
+                "v-for is missing a source expression, expected `item in items`",
+                directive_span,
+                CompileErrorCode::InvalidVFor,
+            ));
+        }
+
+        // Parse aliases, remembering where each one sits in the source
+        let aliases: Vec<(usize, usize)> =
+            if alias_part.starts_with('(') && alias_part.ends_with(')') {
+                split_top_level_commas(expr, (alias_start + 1, alias_end - 1))
+                    .into_iter()
+                    .map(|range| trim_range(expr, range))
+                    .collect()
+            } else {
+                vec![(alias_start, alias_end)]
+            };
+
+        if aliases.len() > 3 {
+            return Err(CompileError::new(
+                format!(
+                    "v-for has too many aliases: expected at most 3 (value, key, index), found {}",
+                    aliases.len()
                 ),
-                _ => {
-                    return Err(CompileError::new(
-                        "Invalid v-for aliases",
-                        span,
-                        CompileErrorCode::InvalidVFor,
-                    ));
-                }
-            }
-        } else {
-            (
-                ForAlias {
-                    pattern: alias_part.to_string(),
-                    span,
-                },
-                None,
-                None,
-            )
+                alias_span,
+                CompileErrorCode::InvalidVFor,
+            ));
+        }
+
+        let to_alias = |(start, end): (usize, usize)| {
+            (start < end).then(|| ForAlias {
+                pattern: expr[start..end].to_string(),
+                span: sub_span(start, end),
+            })
         };
 
+        let value = to_alias(aliases[0]).ok_or_else(|| {
+            CompileError::new(
+                "v-for is missing a value alias",
+                alias_span,
+                CompileErrorCode::InvalidVFor,
+            )
+        })?;
+        let key = aliases.get(1).copied().and_then(to_alias);
+        let index = aliases.get(2).copied().and_then(to_alias);
+
+        for alias in std::iter::once(&value).chain(&key).chain(&index) {
+            if !is_binding_pattern(&alias.pattern) {
+                return Err(CompileError::new(
+                    format!(
+                        "v-for alias `{}` is not a valid binding, expected a name or a destructuring pattern",
+                        alias.pattern
+                    ),
+                    alias.span,
+                    CompileErrorCode::InvalidVFor,
+                ));
+            }
+        }
+
         Ok(ForNode {
-            source: Expression::new(source_part, span),
+            source: Expression::new(
+                &expr[source_start..source_end],
+                sub_span(source_start, source_end),
+            ),
             value,
             key,
             index,
@@ -701,6 +870,68 @@ impl<'a> TemplateParser<'a> {
     }
 }
 
+/// Check that a v-for alias can be bound: a plain name, or a destructuring
+/// pattern whose contents are checked by codegen.
+fn is_binding_pattern(pattern: &str) -> bool {
+    if pattern.starts_with('{') || pattern.starts_with('[') {
+        return true;
+    }
+    let mut chars = pattern.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Split a v-for expression into its alias and source byte ranges.
+fn split_v_for_expression(expr: &str) -> Option<((usize, usize), (usize, usize))> {
+    let leading = expr.len() - expr.trim_start().len();
+    let rest = &expr[leading..];
+
+    // An empty alias list (` in items`) leaves the keyword at the start
+    for keyword in ["in ", "of "] {
+        if rest.starts_with(keyword) {
+            return Some(((leading, leading), (leading + 3, expr.len())));
+        }
+    }
+
+    [" in ", " of "]
+        .iter()
+        .filter_map(|sep| expr.find(sep))
+        .min()
+        .map(|pos| ((0, pos), (pos + 4, expr.len())))
+}
+
+/// Split a byte range of `text` at commas that are not nested in brackets.
+fn split_top_level_commas(text: &str, (start, end): (usize, usize)) -> Vec<(usize, usize)> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut part_start = start;
+
+    for (i, c) in text[start..end].char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push((part_start, start + i));
+                part_start = start + i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push((part_start, end));
+
+    parts
+}
+
+/// Shrink a byte range of `text` so it excludes surrounding whitespace.
+fn trim_range(text: &str, (start, end): (usize, usize)) -> (usize, usize) {
+    let slice = &text[start..end];
+    let trimmed_start = start + (slice.len() - slice.trim_start().len());
+    let trimmed_end = end - (slice.len() - slice.trim_end().len());
+    (trimmed_start, trimmed_end.max(trimmed_start))
+}
+
 /// Check if an element is a void element (self-closing).
 fn is_void_element(tag: &str) -> bool {
     matches!(
@@ -722,24 +953,38 @@ fn is_void_element(tag: &str) -> bool {
     )
 }
 
-/// Parse a prop name, handling dynamic syntax.
-fn parse_prop_name(name: &str) -> (&str, bool) {
-    if name.starts_with('[') && name.ends_with(']') {
-        (&name[1..name.len() - 1], true)
-    } else {
-        // Handle modifiers like .sync, .prop, .camel
-        let base = name.split('.').next().unwrap_or(name);
-        (base, false)
+/// Parse a prop name, handling dynamic syntax and modifiers.
+fn parse_prop_name(name: &str) -> (&str, bool, Vec<&str>) {
+    // Modifiers like .camel, .prop, .attr follow the name (or `]`)
+    let (base, modifiers) = split_modifiers(name);
+    match base.strip_prefix('[').and_then(|b| b.strip_suffix(']')) {
+        Some(inner) => (inner, true, modifiers),
+        None => (base, false, modifiers),
     }
 }
 
 /// Parse event name with modifiers.
 fn parse_event_with_modifiers(name: &str) -> (&str, Vec<&str>) {
-    let parts: Vec<&str> = name.split('.').collect();
-    if parts.len() > 1 {
-        (parts[0], parts[1..].to_vec())
-    } else {
-        (name, Vec::new())
+    split_modifiers(name)
+}
+
+/// Split a directive name such as `bind:arg.mod1.mod2` into the part before
+/// the modifiers and the modifiers.
+///
+/// A dynamic argument may contain dots (`[obj.key]`), so modifiers only
+/// start after its closing bracket.
+fn split_modifiers(name: &str) -> (&str, Vec<&str>) {
+    let modifiers_from = match (name.find('['), name.rfind(']')) {
+        (Some(open), Some(close)) if open < close => close,
+        _ => 0,
+    };
+    match name[modifiers_from..].find('.') {
+        Some(dot) => {
+            let (base, rest) = name.split_at(modifiers_from + dot);
+            let modifiers = rest[1..].split('.').filter(|m| !m.is_empty()).collect();
+            (base, modifiers)
+        }
+        None => (name, Vec::new()),
     }
 }
 
@@ -760,11 +1005,34 @@ mod tests {
         match &ast.children[0] {
             TemplateNode::Interpolation(node) => {
                 assert_eq!(node.expression.content.trim(), "message");
+                assert_eq!(node.expression.span, Span::new(3, 10));
             }
             _ => panic!("Expected interpolation"),
         }
     }
 
+    #[test]
+    fn test_interpolation_span_excludes_whitespace() {
+        for source in [
+            "{{   name   }}",
+            "<p>{{\n    name\n  }}</p>",
+            "{{\u{3000}name\u{3000}}}",
+        ] {
+            let ast = parse_template(source).unwrap();
+            let node = match &ast.children[0] {
+                TemplateNode::Interpolation(node) => node,
+                TemplateNode::Element(el) => match &el.children[0] {
+                    TemplateNode::Interpolation(node) => node,
+                    _ => panic!("Expected interpolation"),
+                },
+                _ => panic!("Expected interpolation"),
+            };
+            let span = node.expression.span;
+            assert_eq!(node.expression.content, "name");
+            assert_eq!(&source[span.start as usize..span.end as usize], "name");
+        }
+    }
+
     #[test]
     fn test_parse_v_for() {
         let ast = parse_template(r#"<div v-for="item in items" :key="item.id">{{ item }}</div>"#)
@@ -779,6 +1047,103 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_v_for_alias_spans() {
+        let source = r#"<li v-for="(item, index) in items">{{ item }}</li>"#;
+        let ast = parse_template(source).unwrap();
+        match &ast.children[0] {
+            TemplateNode::For(node) => {
+                assert_eq!(&source[node.value.span.to_range()], "item");
+                assert_eq!(node.key.as_ref().unwrap().pattern, "index");
+                assert_eq!(&source[node.source.span.to_range()], "items");
+            }
+            _ => panic!("Expected for node"),
+        }
+    }
+
+    #[test]
+    fn test_parse_v_for_destructured_alias() {
+        let ast =
+            parse_template(r#"<li v-for="({ id, name }, i) in items">{{ name }}</li>"#).unwrap();
+        match &ast.children[0] {
+            TemplateNode::For(node) => {
+                assert_eq!(node.value.pattern, "{ id, name }");
+                assert_eq!(node.key.as_ref().unwrap().pattern, "i");
+            }
+            _ => panic!("Expected for node"),
+        }
+    }
+
+    #[test]
+    fn test_parse_v_for_empty_alias() {
+        let source = r#"<li v-for=" in items"></li>"#;
+        let err = parse_template(source).unwrap_err();
+        assert_eq!(err.code, CompileErrorCode::InvalidVFor);
+        assert_eq!(err.message, "v-for is missing a value alias");
+        assert_eq!(err.span, Span::new(12, 12));
+
+        let err = parse_template(r#"<li v-for="(, i) in items"></li>"#).unwrap_err();
+        assert_eq!(err.code, CompileErrorCode::InvalidVFor);
+    }
+
+    #[test]
+    fn test_parse_v_for_empty_expression() {
+        for source in [r#"<li v-for=""></li>"#, r#"<li v-for></li>"#] {
+            let err = parse_template(source).unwrap_err();
+            assert_eq!(err.code, CompileErrorCode::InvalidVFor);
+            assert_eq!(
+                err.message,
+                "v-for is missing an expression, expected `item in items`"
+            );
+            assert!(source[err.span.to_range()].starts_with("v-for"));
+        }
+    }
+
+    #[test]
+    fn test_parse_v_for_missing_parts() {
+        let source = r#"<li v-for="in items"></li>"#;
+        let err = parse_template(source).unwrap_err();
+        assert_eq!(err.code, CompileErrorCode::InvalidVFor);
+        assert_eq!(err.message, "v-for is missing a value alias");
+
+        let source = r#"<li v-for="item in  "></li>"#;
+        let err = parse_template(source).unwrap_err();
+        assert_eq!(
+            err.message,
+            "v-for is missing a source expression, expected `item in items`"
+        );
+        assert_eq!(&source[err.span.to_range()], r#"v-for="item in  ""#);
+    }
+
+    #[test]
+    fn test_parse_v_for_invalid_alias() {
+        let source = r#"<li v-for="(item, 1) in items"></li>"#;
+        let err = parse_template(source).unwrap_err();
+        assert_eq!(err.code, CompileErrorCode::InvalidVFor);
+        assert_eq!(&source[err.span.to_range()], "1");
+
+        let err = parse_template(r#"<li v-for="a.b in items"></li>"#).unwrap_err();
+        assert_eq!(err.code, CompileErrorCode::InvalidVFor);
+
+        assert!(is_binding_pattern("item"));
+        assert!(is_binding_pattern("$_el2"));
+        assert!(is_binding_pattern("{ id, name }"));
+        assert!(is_binding_pattern("[first, second]"));
+        assert!(!is_binding_pattern("item /* x */"));
+    }
+
+    #[test]
+    fn test_parse_v_for_too_many_aliases() {
+        let source = r#"<li v-for="(a, b, c, d) in items"></li>"#;
+        let err = parse_template(source).unwrap_err();
+        assert_eq!(err.code, CompileErrorCode::InvalidVFor);
+        assert_eq!(
+            err.message,
+            "v-for has too many aliases: expected at most 3 (value, key, index), found 4"
+        );
+        assert_eq!(&source[err.span.to_range()], "(a, b, c, d)");
+    }
+
     #[test]
     fn test_parse_v_if() {
         let ast = parse_template(r#"<div v-if="show">Visible</div>"#).unwrap();
@@ -792,6 +1157,66 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_template_fragment() {
+        let ast =
+            parse_template(r#"<template v-for="i in items" #row>{{ i }}</template>"#).unwrap();
+        let TemplateNode::For(node) = &ast.children[0] else {
+            panic!("Expected for node");
+        };
+        let TemplateNode::Template(fragment) = &node.children[0] else {
+            panic!("Expected template fragment");
+        };
+        assert!(fragment.directives.iter().any(|d| d.name == "slot"));
+        assert!(!fragment.directives.iter().any(|d| d.name == "for"));
+        assert!(matches!(
+            fragment.children[0],
+            TemplateNode::Interpolation(_)
+        ));
+    }
+
+    #[test]
+    fn test_parse_v_if_chain() {
+        let source = r#"<div v-if="a">A</div>
+<!-- b -->
+<div v-else-if="b">B</div>
+<div v-else>C</div>"#;
+        let ast = parse_template(source).unwrap();
+        assert!(ast.errors.is_empty());
+        assert_eq!(ast.children.len(), 1);
+        match &ast.children[0] {
+            TemplateNode::If(node) => {
+                let types: Vec<_> = node.branches.iter().map(|b| b.branch_type).collect();
+                assert_eq!(
+                    types,
+                    vec![IfBranchType::If, IfBranchType::ElseIf, IfBranchType::Else]
+                );
+                assert_eq!(node.span, Span::new(0, source.len() as u32));
+            }
+            _ => panic!("Expected if node"),
+        }
+    }
+
+    #[test]
+    fn test_parse_dangling_v_else() {
+        let source = r#"<div v-if="a"/><p>x</p><div v-else/>"#;
+        let ast = parse_template(source).unwrap();
+        assert_eq!(ast.children.len(), 3);
+        assert_eq!(ast.errors.len(), 1);
+
+        let error = &ast.errors[0];
+        assert_eq!(error.code, CompileErrorCode::InvalidVIfChain);
+        let start = source.rfind("v-else").unwrap() as u32;
+        assert_eq!(error.span, Span::new(start, start + "v-else".len() as u32));
+    }
+
+    #[test]
+    fn test_parse_v_else_after_v_else() {
+        let ast = parse_template(r#"<a v-if="x"/><b v-else/><c v-else-if="y"/>"#).unwrap();
+        assert_eq!(ast.errors.len(), 1);
+        assert!(ast.errors[0].message.starts_with("v-else-if"));
+    }
+
     #[test]
     fn test_parse_component() {
         let ast = parse_template(r#"<MyComponent :prop="value" @click="handler" />"#).unwrap();
@@ -807,6 +1232,232 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_prop_modifiers() {
+        let ast = parse_template(r#"<svg :view-box.camel="box" :[key].prop="v" />"#).unwrap();
+        let TemplateNode::Element(el) = &ast.children[0] else {
+            panic!("Expected element");
+        };
+        assert_eq!(el.props[0].name.as_str(), "view-box");
+        assert_eq!(el.props[0].modifiers, vec![SmolStr::from("camel")]);
+        assert_eq!(el.props[0].element_prop_name(), "viewBox");
+        assert_eq!(el.props[1].name.as_str(), "key");
+        assert!(el.props[1].is_dynamic);
+        assert!(el.props[1].is_dom_binding());
+    }
+
+    #[test]
+    fn test_same_name_shorthand() {
+        let source = r#"<Comp :count v-bind:max-items.prop :[key] />"#;
+        let ast = parse_template(source).unwrap();
+        let TemplateNode::Element(el) = &ast.children[0] else {
+            panic!("Expected element");
+        };
+        assert_eq!(el.props.len(), 2);
+        assert_eq!(el.props[0].name.as_str(), "count");
+        assert_eq!(el.props[0].value.content.as_str(), "count");
+        assert_eq!(&source[el.props[0].value.span.to_range()], "count");
+        assert_eq!(el.props[1].name.as_str(), "max-items");
+        assert_eq!(el.props[1].value.content.as_str(), "maxItems");
+        assert_eq!(&source[el.props[1].value.span.to_range()], "max-items");
+        assert!(el.props[1].has_modifier("prop"));
+    }
+
+    #[test]
+    fn test_dynamic_args_with_dots() {
+        let ast = parse_template(
+            r#"<div v-bind:[obj.key].prop="x" @[names.event].once="go" v-tip:[opts.side].fade="t" />"#,
+        )
+        .unwrap();
+        let TemplateNode::Element(el) = &ast.children[0] else {
+            panic!("Expected element");
+        };
+        assert_eq!(el.props[0].name.as_str(), "obj.key");
+        assert!(el.props[0].is_dynamic);
+        assert_eq!(el.props[0].modifiers, vec![SmolStr::from("prop")]);
+
+        assert_eq!(el.events[0].name.as_str(), "names.event");
+        assert!(el.events[0].is_dynamic);
+        assert_eq!(el.events[0].modifiers, vec![SmolStr::from("once")]);
+
+        let directive = &el.directives[0];
+        assert_eq!(directive.name.as_str(), "tip");
+        match &directive.arg {
+            Some(DirectiveArg::Dynamic(arg)) => assert_eq!(arg.content, "opts.side"),
+            other => panic!("Expected dynamic arg, got {:?}", other),
+        }
+        assert_eq!(directive.modifiers, vec![SmolStr::from("fade")]);
+    }
+
+    #[test]
+    fn test_split_modifiers() {
+        assert_eq!(split_modifiers("click"), ("click", vec![]));
+        assert_eq!(
+            split_modifiers("keyup.enter.exact"),
+            ("keyup", vec!["enter", "exact"])
+        );
+        assert_eq!(split_modifiers("bind:[a.b]"), ("bind:[a.b]", vec![]));
+        assert_eq!(
+            split_modifiers("[a[0].b].camel"),
+            ("[a[0].b]", vec!["camel"])
+        );
+    }
+
+    #[test]
+    fn test_v_for_key_moves_to_loop() {
+        for source in [
+            r#"<li v-for="item in items" :key="item.id" :title="item.name"></li>"#,
+            r#"<li v-for="item in items" v-bind:key="item.id" v-bind:title="item.name"></li>"#,
+        ] {
+            let ast = parse_template(source).unwrap();
+            let TemplateNode::For(for_node) = &ast.children[0] else {
+                panic!("Expected v-for");
+            };
+            assert_eq!(
+                for_node.key_attr.as_ref().map(|k| k.content.as_str()),
+                Some("item.id")
+            );
+            let TemplateNode::Element(el) = &for_node.children[0] else {
+                panic!("Expected element");
+            };
+            let props: Vec<_> = el.props.iter().map(|p| p.name.as_str()).collect();
+            assert_eq!(props, ["title"]);
+            assert!(el.directives.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_long_form_bindings_parse_like_shorthands() {
+        let ast = parse_template(
+            r#"<MyComp v-bind:msg.camel="m" v-on:update.once="onUpdate" v-bind="attrs" />"#,
+        )
+        .unwrap();
+        let TemplateNode::Element(el) = &ast.children[0] else {
+            panic!("Expected element");
+        };
+        assert_eq!(el.props[0].name.as_str(), "msg");
+        assert!(el.props[0].has_modifier("camel"));
+        assert_eq!(el.events[0].name.as_str(), "update");
+        assert_eq!(el.events[0].modifiers, vec![SmolStr::from("once")]);
+        // Object binding has no argument and stays a directive
+        assert_eq!(el.directives.len(), 1);
+        assert!(el.directives[0].is_bind());
+    }
+
+    #[test]
+    fn test_parse_vue_is_attribute() {
+        let ast = parse_template(r#"<table><tr is="vue:MyRow" :item="row"></tr></table>"#).unwrap();
+        let TemplateNode::Element(table) = &ast.children[0] else {
+            panic!("Expected element node");
+        };
+        let TemplateNode::Element(row) = &table.children[0] else {
+            panic!("Expected element node");
+        };
+        assert_eq!(row.tag.as_str(), "tr");
+        assert!(row.is_component);
+        assert_eq!(row.component_name(), "MyRow");
+
+        // Without the `vue:` prefix, `is` is a customized built-in element
+        let ast = parse_template(r#"<button is="fancy-button"></button>"#).unwrap();
+        let TemplateNode::Element(button) = &ast.children[0] else {
+            panic!("Expected element node");
+        };
+        assert!(!button.is_component);
+        assert_eq!(button.component_name(), "button");
+    }
+
+    #[test]
+    fn test_parse_too_deep() {
+        let source = format!("{}{}", "<div>".repeat(2000), "</div>".repeat(2000));
+        let err = parse_template(&source).unwrap_err();
+        assert_eq!(err.code, CompileErrorCode::NestingTooDeep);
+        // The element that would go one level too deep
+        assert_eq!(err.span.start as usize, DEFAULT_MAX_DEPTH * 5 + 1);
+
+        let options = ParseOptions {
+            max_depth: Some(3),
+            ..Default::default()
+        };
+        let source = "<a><b><c>text</c></b></a>";
+        assert!(parse_template_with_options(source, &options).is_ok());
+        let source = "<a><b><c><d>text</d></c></b></a>";
+        let err = parse_template_with_options(source, &options).unwrap_err();
+        assert_eq!(err.code, CompileErrorCode::NestingTooDeep);
+    }
+
+    #[test]
+    fn test_parse_namespaced_attributes() {
+        let source = r##"<svg xmlns:xlink="http://www.w3.org/1999/xlink"><use xlink:href="#icon" :href="url" /></svg>"##;
+        let ast = parse_template(source).unwrap();
+        let TemplateNode::Element(svg) = &ast.children[0] else {
+            panic!("expected element");
+        };
+        assert_eq!(svg.attrs[0].name, "xmlns:xlink");
+        assert_eq!(
+            svg.attrs[0].value.as_deref(),
+            Some("http://www.w3.org/1999/xlink")
+        );
+
+        let TemplateNode::Element(use_el) = &svg.children[0] else {
+            panic!("expected element");
+        };
+        assert_eq!(use_el.attrs.len(), 1);
+        assert_eq!(use_el.attrs[0].name, "xlink:href");
+        assert_eq!(use_el.attrs[0].value.as_deref(), Some("#icon"));
+        assert!(use_el.directives.is_empty());
+        assert_eq!(use_el.props.len(), 1);
+        assert_eq!(use_el.props[0].name, "href");
+    }
+
+    #[test]
+    fn test_parse_custom_element_tag() {
+        let options = ParseOptions {
+            custom_element_tags: vec!["my-widget".to_string()],
+            ..Default::default()
+        };
+        let source = r#"<my-widget :value="v" /><my-comp />"#;
+        let ast = parse_template_with_options(source, &options).unwrap();
+        let is_component: Vec<bool> = ast
+            .children
+            .iter()
+            .map(|child| match child {
+                TemplateNode::Element(el) => el.is_component,
+                _ => panic!("Expected element node"),
+            })
+            .collect();
+        assert_eq!(is_component, vec![false, true]);
+
+        // Without options, dashed tags are components
+        let ast = parse_template(source).unwrap();
+        assert!(matches!(&ast.children[0], TemplateNode::Element(el) if el.is_component));
+    }
+
+    #[test]
+    fn test_parse_registered_lowercase_component() {
+        let options = ParseOptions {
+            component_tags: vec!["card".to_string(), "FooBar".to_string()],
+            ..Default::default()
+        };
+        let source = "<card /><foobar /><div /><section />";
+        let ast = parse_template_with_options(source, &options).unwrap();
+        let is_component: Vec<bool> = ast
+            .children
+            .iter()
+            .map(|child| match child {
+                TemplateNode::Element(el) => el.is_component,
+                _ => panic!("Expected element node"),
+            })
+            .collect();
+        assert_eq!(is_component, vec![true, true, false, false]);
+
+        // Configured native tags win over the casing heuristic
+        let options = ParseOptions {
+            custom_element_tags: vec!["Card".to_string()],
+            ..Default::default()
+        };
+        assert!(!options.is_component("Card"));
+    }
+
     #[test]
     fn test_parse_slot() {
         let ast = parse_template(r#"<slot name="header">Default</slot>"#).unwrap();
@@ -217,7 +217,7 @@ fn is_builtin_directive(name: &str) -> bool {
 }
 
 /// Extract binding names from a pattern (simple extraction).
-fn extract_binding_names(pattern: &str) -> Vec<&str> {
+pub fn extract_binding_names(pattern: &str) -> Vec<&str> {
     let pattern = pattern.trim();
 
     // Handle object destructuring: { a, b: c, d = 1 }
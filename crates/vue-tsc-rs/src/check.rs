@@ -0,0 +1,132 @@
+//! Checking a project from another program.
+
+use crate::cli::Args;
+use crate::orchestrator::{Orchestrator, ProjectCheckResult};
+use miette::Result;
+use std::path::{Path, PathBuf};
+
+/// Options for [`run_check`].
+#[derive(Debug, Clone)]
+pub struct CheckOptions {
+    /// Path to tsconfig.json, searched for from the workspace if unset.
+    pub tsconfig: Option<PathBuf>,
+    /// Run the TypeScript compiler as well as the Vue diagnostics.
+    pub typecheck: bool,
+    /// Use tsgo instead of tsc.
+    pub use_tsgo: bool,
+    /// Type check template expressions.
+    pub template_check: bool,
+    /// Run the Vue-specific diagnostics.
+    pub vue_diagnostics: bool,
+    /// Ignore patterns (glob).
+    pub ignore: Vec<String>,
+    /// Sort diagnostics by file and position.
+    pub sort: bool,
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        Self {
+            tsconfig: None,
+            typecheck: true,
+            use_tsgo: false,
+            template_check: true,
+            vue_diagnostics: true,
+            ignore: Vec::new(),
+            sort: true,
+        }
+    }
+}
+
+impl CheckOptions {
+    /// Get the CLI arguments that check a workspace with these options.
+    fn to_args(&self, workspace: &Path) -> Args {
+        Args {
+            project: self.tsconfig.iter().cloned().collect(),
+            skip_typecheck: !self.typecheck,
+            use_tsgo: self.use_tsgo,
+            no_template_check: !self.template_check,
+            no_vue_diagnostics: !self.vue_diagnostics,
+            ignore: self.ignore.clone(),
+            no_sort: !self.sort,
+            ..Args::for_workspace(workspace)
+        }
+    }
+}
+
+/// Check a Vue project and return the results, printing nothing.
+///
+/// The configuration is loaded like the CLI's, from the tsconfig.json and
+/// vue-tsc-rs.json of the workspace.
+pub async fn run_check(workspace: &Path, options: &CheckOptions) -> Result<ProjectCheckResult> {
+    let orchestrator = Orchestrator::new(workspace.to_path_buf(), options.to_args(workspace))?;
+    orchestrator.check().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-run-check")
+            .tempdir()
+            .unwrap();
+        let files: [(&str, &[u8]); 3] = [
+            ("Clean.vue", b"<template><div /></template>\n"),
+            (
+                "BadLoop.vue",
+                b"<template><li v-for=\"(a, b, c, d) in items\">{{ a }}</li></template>\n",
+            ),
+            // Not UTF-8, so it can't be read
+            ("Broken.vue", b"<template>\xff</template>\n"),
+        ];
+        for (name, content) in files {
+            std::fs::write(dir.path().join(name), content).unwrap();
+        }
+        dir
+    }
+
+    fn options() -> CheckOptions {
+        CheckOptions {
+            typecheck: false,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_check_returns_structured_results() {
+        let dir = fixture();
+        let result = run_check(dir.path(), &options()).await.unwrap();
+
+        assert_eq!(result.summary.file_count, 3);
+        assert_eq!(result.summary.error_count, 1);
+        assert_eq!(result.summary.failed_count, 1);
+        assert_eq!(result.summary.diagnostic_counts["invalid-v-for"], 1);
+
+        assert_eq!(result.vue_diagnostics.len(), 1);
+        let (file, diagnostics) = &result.vue_diagnostics[0];
+        assert!(file.ends_with("BadLoop.vue"));
+        assert_eq!(diagnostics.len(), 1);
+
+        assert_eq!(result.failed_files.len(), 1);
+        assert!(result.failed_files[0].0.ends_with("Broken.vue"));
+        assert!(result.ts_diagnostics.diagnostics.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_check_options() {
+        let dir = fixture();
+        let options = CheckOptions {
+            vue_diagnostics: false,
+            ignore: vec!["**/Broken.vue".to_string()],
+            ..options()
+        };
+        let result = run_check(dir.path(), &options).await.unwrap();
+
+        assert_eq!(result.summary.file_count, 2);
+        assert_eq!(result.summary.error_count, 0);
+        assert_eq!(result.summary.failed_count, 0);
+        assert!(result.vue_diagnostics.is_empty());
+    }
+}
@@ -8,26 +8,42 @@ use std::path::PathBuf;
 #[command(name = "vue-tsc-rs")]
 #[command(author, version, about, long_about = None)]
 pub struct Args {
+    /// Files or globs to check instead of the whole workspace
+    #[arg(value_name = "FILES")]
+    pub files: Vec<PathBuf>,
+
     /// Workspace directory to check
     #[arg(short, long)]
     pub workspace: Option<PathBuf>,
 
-    /// Path to tsconfig.json
+    /// Path to tsconfig.json; repeat to check several projects
     #[arg(short = 'p', long)]
-    pub project: Option<PathBuf>,
+    pub project: Vec<PathBuf>,
+
+    /// Highest directory to search for tsconfig.json (e.g. a monorepo root)
+    #[arg(long, value_name = "DIR")]
+    pub root_dir: Option<PathBuf>,
 
     /// Run in watch mode
     #[arg(short, long)]
     pub watch: bool,
 
-    /// Output format
-    #[arg(long, default_value = "human")]
-    pub output: OutputFormat,
+    /// Output format [default: human]
+    #[arg(long)]
+    pub output: Option<OutputFormat>,
+
+    /// Write diagnostics and the summary to a file instead of stdout
+    #[arg(long, value_name = "PATH")]
+    pub output_file: Option<PathBuf>,
 
     /// Fail on warnings
     #[arg(long)]
     pub fail_on_warning: bool,
 
+    /// Fail when there are more than this many warnings
+    #[arg(long, value_name = "N")]
+    pub max_warnings: Option<usize>,
+
     /// Emit generated TypeScript files (for debugging)
     #[arg(long)]
     pub emit_ts: bool,
@@ -36,14 +52,54 @@ pub struct Args {
     #[arg(long)]
     pub timings: bool,
 
+    /// Print the generated TypeScript for a Vue file and exit
+    #[arg(long, value_name = "FILE")]
+    pub print_virtual: Option<PathBuf>,
+
+    /// Print the parsed template tree of a Vue file and exit
+    #[arg(long, value_name = "FILE")]
+    pub dump_ast: Option<PathBuf>,
+
+    /// Print the files that would be checked and exit
+    #[arg(long)]
+    pub list_files: bool,
+
+    /// Explain how a component resolves in each file using it and exit
+    #[arg(long, value_name = "COMPONENT")]
+    pub explain: Option<String>,
+
+    /// Validate tsconfig.json (extends chain, files, references) and exit
+    #[arg(long)]
+    pub check_config: bool,
+
+    /// With --print-virtual, append an inline sourceMappingURL comment
+    #[arg(long, requires = "print_virtual")]
+    pub inline_source_map: bool,
+
+    /// Write a JSON metrics report to the given path
+    #[arg(long, value_name = "PATH")]
+    pub stats: Option<PathBuf>,
+
     /// Maximum number of errors to show
     #[arg(long)]
     pub max_errors: Option<usize>,
 
+    /// Keep diagnostics in the order they were reported instead of sorting by file and position
+    #[arg(long)]
+    pub no_sort: bool,
+
     /// Skip type checking (only run Vue diagnostics)
     #[arg(long)]
     pub skip_typecheck: bool,
 
+    /// Skip template type checking (only check scripts)
+    #[arg(long)]
+    pub no_template_check: bool,
+
+    /// Skip Vue-specific diagnostics (only run TypeScript)
+    #[arg(long)]
+    pub no_vue_diagnostics: bool,
+
     /// Ignore patterns (glob)
     #[arg(long)]
     pub ignore: Vec<String>,
@@ -56,13 +112,22 @@ pub struct Args {
     #[arg(long)]
     pub use_tsgo: bool,
 
+    /// Path to the tsc (or, with --use-tsgo, tsgo) binary to run
+    #[arg(long, value_name = "PATH")]
+    pub ts_path: Option<PathBuf>,
+
+    /// Extra argument to pass to tsc (repeatable), e.g. --tsc-arg=--skipLibCheck
+    #[arg(long = "tsc-arg", value_name = "ARG", allow_hyphen_values = true)]
+    pub tsc_args: Vec<String>,
+
     /// Preserve watch output (don't clear screen)
     #[arg(long)]
     pub preserve_watch_output: bool,
 }
 
 /// Output format for diagnostics.
-#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum OutputFormat {
     /// Human-readable output
     #[default]
@@ -76,14 +141,50 @@ pub enum OutputFormat {
 }
 
 impl Args {
-    /// Get the tsconfig path.
+    /// Get the tsconfig path, the first one when several projects are
+    /// checked.
     pub fn tsconfig(&self) -> Option<&PathBuf> {
-        self.project.as_ref()
+        self.project.first()
     }
 
     /// Check if output should be verbose.
     #[allow(dead_code)]
     pub fn is_verbose(&self) -> bool {
-        self.verbose || matches!(self.output, OutputFormat::HumanVerbose)
+        self.verbose || matches!(self.output, Some(OutputFormat::HumanVerbose))
+    }
+
+    /// Arguments for checking a workspace without running tsc.
+    pub fn for_workspace(workspace: &std::path::Path) -> Self {
+        Self {
+            files: Vec::new(),
+            workspace: Some(workspace.to_path_buf()),
+            project: Vec::new(),
+            root_dir: None,
+            watch: false,
+            output: Some(OutputFormat::Machine),
+            output_file: None,
+            fail_on_warning: false,
+            max_warnings: None,
+            emit_ts: false,
+            timings: false,
+            print_virtual: None,
+            dump_ast: None,
+            list_files: false,
+            explain: None,
+            check_config: false,
+            inline_source_map: false,
+            stats: None,
+            max_errors: None,
+            no_sort: false,
+            skip_typecheck: true,
+            no_template_check: false,
+            no_vue_diagnostics: false,
+            ignore: Vec::new(),
+            verbose: false,
+            use_tsgo: false,
+            ts_path: None,
+            tsc_args: Vec::new(),
+            preserve_watch_output: false,
+        }
     }
 }
@@ -1,10 +1,13 @@
 //! Configuration loading and management.
 
-use crate::cli::Args;
-use miette::{IntoDiagnostic, Result};
+use crate::cli::{Args, OutputFormat};
+use miette::{IntoDiagnostic, Result, WrapErr};
+use serde::Deserialize;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use ts_runner::TsConfig;
-use vue_diagnostics::DiagnosticOptions;
+use vue_diagnostics::{ComponentInfo, DiagnosticOptions, Severity};
+use vue_template_compiler::ParseOptions;
 
 /// Configuration for vue-tsc-rs.
 #[derive(Debug, Clone)]
@@ -22,16 +25,98 @@ pub struct Config {
     pub extensions: Vec<String>,
     /// Ignore patterns.
     pub ignore_patterns: Vec<String>,
+    /// Path to the tsc or tsgo binary, instead of searching for one.
+    pub ts_path: Option<PathBuf>,
+    /// The highest directory to search for a tsconfig.
+    pub root_dir: Option<PathBuf>,
+    /// Path to vue-tsc-rs.json.
+    pub project_config_path: Option<PathBuf>,
+    /// Output format.
+    pub output: OutputFormat,
+}
+
+/// Project settings from `vue-tsc-rs.json`.
+///
+/// CLI flags take precedence over the file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// Severities by diagnostic code (`"missing-key": "off"`).
+    #[serde(default)]
+    pub severity: HashMap<String, SeverityLevel>,
+    /// Component names to treat as known.
+    #[serde(default)]
+    pub components: Vec<String>,
+    /// Run accessibility checks.
+    #[serde(default)]
+    pub a11y: bool,
+    /// Ignore patterns (glob), in addition to `--ignore`.
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    /// Default output format.
+    pub output: Option<OutputFormat>,
+}
+
+/// A severity set in `vue-tsc-rs.json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SeverityLevel {
+    Error,
+    Warning,
+    Hint,
+    /// Don't report the code at all.
+    Off,
+}
+
+impl SeverityLevel {
+    /// Get the severity to report with, if any.
+    fn severity(self) -> Option<Severity> {
+        match self {
+            Self::Error => Some(Severity::Error),
+            Self::Warning => Some(Severity::Warning),
+            Self::Hint => Some(Severity::Hint),
+            Self::Off => None,
+        }
+    }
+}
+
+impl ProjectConfig {
+    /// The config file name.
+    pub const FILE_NAME: &'static str = "vue-tsc-rs.json";
+
+    /// Load a config file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Find the config file in a directory or its parents, searching no
+    /// higher than `root`, like tsconfig.json.
+    pub fn find_within(dir: &Path, root: Option<&Path>) -> Option<PathBuf> {
+        dir.ancestors()
+            .take_while(|current| root.map_or(true, |root| current.starts_with(root)))
+            .map(|current| current.join(Self::FILE_NAME))
+            .find(|path| path.is_file())
+    }
 }
 
 impl Config {
     /// Load configuration from CLI arguments and workspace.
     pub fn load(workspace: &Path, args: &Args) -> Result<Self> {
-        // Find or use specified tsconfig
-        let tsconfig_path = args
-            .tsconfig()
-            .cloned()
-            .or_else(|| TsConfig::find(workspace).map(|p| p.into_std_path_buf()));
+        // Find or use specified tsconfig. Paths are compared as-is, so with
+        // `--root-dir` both are made absolute first.
+        let root_dir = args.root_dir.as_deref().map(canonicalize);
+        let search_dir = match &root_dir {
+            Some(_) => canonicalize(workspace),
+            None => workspace.to_path_buf(),
+        };
+        let tsconfig_path = args.tsconfig().cloned().or_else(|| {
+            TsConfig::find_within(&search_dir, root_dir.as_deref()).map(|p| p.into_std_path_buf())
+        });
 
         // Load tsconfig
         let tsconfig = if let Some(ref path) = tsconfig_path {
@@ -40,8 +125,15 @@ impl Config {
             None
         };
 
+        // Load vue-tsc-rs.json
+        let project_config_path = ProjectConfig::find_within(&search_dir, root_dir.as_deref());
+        let project_config = match &project_config_path {
+            Some(path) => ProjectConfig::load(path)?,
+            None => ProjectConfig::default(),
+        };
+
         // Build diagnostic options
-        let diagnostic_options = DiagnosticOptions {
+        let mut diagnostic_options = DiagnosticOptions {
             check_unknown_components: tsconfig
                 .as_ref()
                 .and_then(|c| c.vue_compiler_options.check_unknown_components)
@@ -51,14 +143,60 @@ impl Config {
                 .and_then(|c| c.vue_compiler_options.check_unknown_directives)
                 .unwrap_or(false),
             check_v_for_keys: true,
-            known_components: Vec::new(),
+            check_unknown_props: tsconfig
+                .as_ref()
+                .and_then(|c| c.vue_compiler_options.check_unknown_props)
+                .unwrap_or(false),
+            components: Default::default(),
             known_directives: Vec::new(),
+            global_components_dts: global_components_dts(
+                workspace,
+                tsconfig_path.as_deref(),
+                tsconfig.as_ref(),
+            ),
+            parse_options: ParseOptions {
+                custom_element_tags: tsconfig
+                    .as_ref()
+                    .map(|c| c.vue_compiler_options.native_tags.clone())
+                    .unwrap_or_default(),
+                ..Default::default()
+            },
+            target: tsconfig
+                .as_ref()
+                .and_then(|c| c.vue_compiler_options.target),
+            check_a11y: project_config.a11y,
+            severity_overrides: project_config
+                .severity
+                .iter()
+                .map(|(code, level)| (code.clone(), level.severity()))
+                .collect(),
         };
 
+        for name in &project_config.components {
+            diagnostic_options
+                .parse_options
+                .component_tags
+                .push(name.clone());
+            diagnostic_options
+                .components
+                .insert(ComponentInfo::new(name.clone()));
+        }
+
+        diagnostic_options
+            .load_global_components()
+            .into_diagnostic()
+            .wrap_err("Failed to load global component declarations")?;
+
         // Get extensions
         let extensions = tsconfig
             .as_ref()
-            .map(|c| c.vue_compiler_options.extensions.to_vec())
+            .map(|c| {
+                c.vue_compiler_options
+                    .file_extensions()
+                    .into_iter()
+                    .map(String::from)
+                    .collect()
+            })
             .unwrap_or_else(|| vec![".vue".to_string()]);
 
         // Build ignore patterns
@@ -67,8 +205,20 @@ impl Config {
             "**/dist/**".to_string(),
             "**/.git/**".to_string(),
         ];
+        ignore_patterns.extend(project_config.ignore.iter().cloned());
         ignore_patterns.extend(args.ignore.iter().cloned());
 
+        // `--ts-path` overrides `vueCompilerOptions.tsPath`, which is
+        // relative to the tsconfig
+        let ts_path = args.ts_path.clone().or_else(|| {
+            let path = tsconfig.as_ref()?.vue_compiler_options.ts_path.as_ref()?;
+            let base = tsconfig_path
+                .as_deref()
+                .and_then(Path::parent)
+                .unwrap_or(workspace);
+            Some(base.join(path))
+        });
+
         Ok(Self {
             workspace: workspace.to_path_buf(),
             tsconfig_path,
@@ -76,33 +226,129 @@ impl Config {
             diagnostic_options,
             extensions,
             ignore_patterns,
+            ts_path,
+            root_dir,
+            project_config_path,
+            output: args.output.or(project_config.output).unwrap_or_default(),
         })
     }
+}
 
-    /// Check if a file should be processed.
-    #[allow(dead_code)]
-    pub fn should_process(&self, path: &Path) -> bool {
-        // Check extension
-        let ext = path
-            .extension()
-            .map(|e| format!(".{}", e.to_string_lossy()))
-            .unwrap_or_default();
-
-        if !self.extensions.iter().any(|e| e == &ext) {
-            return false;
-        }
+/// Find the global component declarations for the project.
+///
+/// Uses `vueCompilerOptions.globalComponentsDts` (relative to the tsconfig),
+/// falling back to the `components.d.ts` that unplugin-vue-components
+/// generates in the workspace root.
+fn global_components_dts(
+    workspace: &Path,
+    tsconfig_path: Option<&Path>,
+    tsconfig: Option<&TsConfig>,
+) -> Option<PathBuf> {
+    if let Some(path) = tsconfig.and_then(|c| c.vue_compiler_options.global_components_dts.as_ref())
+    {
+        let base = tsconfig_path.and_then(Path::parent).unwrap_or(workspace);
+        return Some(base.join(path));
+    }
 
-        // Check ignore patterns
-        let path_str = path.to_string_lossy();
-        for pattern in &self.ignore_patterns {
-            if let Ok(glob) = globset::Glob::new(pattern) {
-                let matcher = glob.compile_matcher();
-                if matcher.is_match(&*path_str) {
-                    return false;
-                }
-            }
-        }
+    let default = workspace.join("components.d.ts");
+    default.is_file().then_some(default)
+}
+
+/// Make a path absolute, resolving symlinks, if it exists.
+fn canonicalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tsconfig_discovery_stops_at_root_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("monorepo");
+        let package = root.join("packages").join("app");
+        std::fs::create_dir_all(&package).unwrap();
+        std::fs::write(dir.path().join("tsconfig.json"), "{}").unwrap();
+
+        let args = Args {
+            root_dir: Some(root.clone()),
+            ..Args::for_workspace(&package)
+        };
+        let config = Config::load(&package, &args).unwrap();
+        assert!(config.tsconfig_path.is_none());
+
+        std::fs::write(root.join("tsconfig.json"), "{}").unwrap();
+        let config = Config::load(&package, &args).unwrap();
+        assert_eq!(
+            config.tsconfig_path,
+            Some(canonicalize(&root).join("tsconfig.json"))
+        );
+    }
+
+    #[test]
+    fn test_project_config_populates_diagnostic_options() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = dir.path().join("app");
+        std::fs::create_dir_all(&app).unwrap();
+        std::fs::write(
+            dir.path().join(ProjectConfig::FILE_NAME),
+            r#"{
+                "severity": { "missing-key": "off", "todo-comment": "warning" },
+                "components": ["RouterLink"],
+                "a11y": true,
+                "ignore": ["**/generated/**"],
+                "output": "json"
+            }"#,
+        )
+        .unwrap();
+
+        let args = Args {
+            output: None,
+            ..Args::for_workspace(&app)
+        };
+        let config = Config::load(&app, &args).unwrap();
+        assert_eq!(
+            config.project_config_path,
+            Some(dir.path().join(ProjectConfig::FILE_NAME))
+        );
+        let options = &config.diagnostic_options;
+        assert_eq!(options.severity_overrides["missing-key"], None);
+        assert_eq!(
+            options.severity_overrides["todo-comment"],
+            Some(Severity::Warning)
+        );
+        assert!(options.components.contains("RouterLink"));
+        assert!(options.check_a11y);
+        assert!(config
+            .ignore_patterns
+            .contains(&"**/generated/**".to_string()));
+        assert_eq!(config.output, OutputFormat::Json);
+    }
+
+    #[test]
+    fn test_cli_overrides_project_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(ProjectConfig::FILE_NAME),
+            r#"{ "output": "json" }"#,
+        )
+        .unwrap();
+
+        let config = Config::load(dir.path(), &Args::for_workspace(dir.path())).unwrap();
+        assert_eq!(config.output, OutputFormat::Machine);
+    }
+
+    #[test]
+    fn test_project_config_rejects_unknown_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(ProjectConfig::FILE_NAME),
+            r#"{ "severities": { "missing-key": "off" } }"#,
+        )
+        .unwrap();
 
-        true
+        let err = Config::load(dir.path(), &Args::for_workspace(dir.path())).unwrap_err();
+        assert!(format!("{:?}", err).contains("unknown field `severities`"));
     }
 }
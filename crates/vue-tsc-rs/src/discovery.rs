@@ -0,0 +1,236 @@
+//! Deciding which files in the workspace are checked.
+
+use crate::config::Config;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::path::{Component, Path, PathBuf};
+use ts_runner::config::has_file_extension;
+
+/// The rules a file must pass to be checked.
+///
+/// A file is checked when it has a Vue extension, sits outside hidden
+/// directories and `node_modules`, matches the tsconfig `include` (if any),
+/// and matches none of the tsconfig `exclude`, the workspace `.gitignore`, or
+/// the ignore patterns from vue-tsc-rs.json and `--ignore`.
+#[derive(Debug)]
+pub(crate) struct FileFilter {
+    /// The workspace root.
+    workspace: PathBuf,
+    /// Extensions of the files to check, with the dot.
+    extensions: Vec<String>,
+    /// Included files, relative to the tsconfig. `None` includes everything.
+    include: Option<GlobSet>,
+    /// Excluded files, relative to the tsconfig.
+    exclude: GlobSet,
+    /// Directory the tsconfig patterns are relative to.
+    tsconfig_dir: PathBuf,
+    /// Ignored files, relative to the workspace.
+    ignore: GlobSet,
+}
+
+impl FileFilter {
+    /// Build the filter for a configuration.
+    ///
+    /// Invalid patterns are skipped.
+    pub(crate) fn new(config: &Config) -> Self {
+        let tsconfig_dir = config
+            .tsconfig_path
+            .as_deref()
+            .and_then(Path::parent)
+            .unwrap_or(&config.workspace)
+            .to_path_buf();
+        let tsconfig = config.tsconfig.as_ref();
+
+        let include = tsconfig
+            .map(|tsconfig| tsconfig.include.as_slice())
+            .filter(|include| !include.is_empty())
+            .map(|include| build_set(include.iter().flat_map(|p| tsconfig_globs(p))));
+        let exclude = build_set(
+            tsconfig
+                .into_iter()
+                .flat_map(|tsconfig| &tsconfig.exclude)
+                .flat_map(|p| tsconfig_globs(p)),
+        );
+
+        let gitignore =
+            std::fs::read_to_string(config.workspace.join(".gitignore")).unwrap_or_default();
+        let ignore = build_set(
+            gitignore_globs(&gitignore)
+                .into_iter()
+                .chain(config.ignore_patterns.iter().cloned()),
+        );
+
+        Self {
+            workspace: config.workspace.clone(),
+            extensions: config.extensions.clone(),
+            include,
+            exclude,
+            tsconfig_dir,
+            ignore,
+        }
+    }
+
+    /// Check if a file should be checked.
+    pub(crate) fn matches(&self, path: &Path) -> bool {
+        if !has_file_extension(path, &self.extensions) {
+            return false;
+        }
+
+        // Skip node_modules and hidden directories within the workspace
+        let relative = path.strip_prefix(&self.workspace).unwrap_or(path);
+        let hidden = relative.components().any(|c| match c {
+            Component::Normal(name) => {
+                name == "node_modules" || name.to_string_lossy().starts_with('.')
+            }
+            _ => false,
+        });
+        if hidden || self.ignore.is_match(relative) {
+            return false;
+        }
+
+        let from_tsconfig = path.strip_prefix(&self.tsconfig_dir).unwrap_or(path);
+        if let Some(include) = &self.include {
+            if !include.is_match(from_tsconfig) {
+                return false;
+            }
+        }
+        !self.exclude.is_match(from_tsconfig)
+    }
+}
+
+/// Files named on the command line, which restrict the check to them.
+///
+/// Each is a path, checked as given, or a glob relative to the workspace.
+#[derive(Debug)]
+pub(crate) struct FileSelection {
+    /// Paths of files to check.
+    paths: Vec<PathBuf>,
+    /// Globs of files to check, if any were given.
+    globs: Option<GlobSet>,
+}
+
+impl FileSelection {
+    /// Sort the arguments into paths and globs.
+    pub(crate) fn new(args: &[PathBuf]) -> Self {
+        let (globs, paths): (Vec<&PathBuf>, Vec<&PathBuf>) = args
+            .iter()
+            .partition(|arg| arg.to_string_lossy().contains(['*', '?', '[', '{']));
+        Self {
+            paths: paths.into_iter().cloned().collect(),
+            globs: (!globs.is_empty()).then(|| {
+                build_set(
+                    globs
+                        .into_iter()
+                        .map(|glob| glob.to_string_lossy().trim_start_matches("./").to_string()),
+                )
+            }),
+        }
+    }
+
+    /// The files named by path.
+    pub(crate) fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Check if any globs were given, so the workspace must be searched.
+    pub(crate) fn has_globs(&self) -> bool {
+        self.globs.is_some()
+    }
+
+    /// Check if a path relative to the workspace matches one of the globs.
+    pub(crate) fn matches_glob(&self, relative: &Path) -> bool {
+        self.globs
+            .as_ref()
+            .is_some_and(|globs| globs.is_match(relative))
+    }
+}
+
+/// Compile glob patterns into a set, skipping invalid ones.
+fn build_set(patterns: impl IntoIterator<Item = String>) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        if let Ok(glob) = Glob::new(&pattern) {
+            builder.add(glob);
+        }
+    }
+    builder.build().unwrap_or_else(|_| GlobSet::empty())
+}
+
+/// Get the globs for a tsconfig `include` or `exclude` pattern.
+///
+/// As in tsc, a pattern names files or directories, so everything under a
+/// matching directory matches too.
+fn tsconfig_globs(pattern: &str) -> [String; 2] {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    [pattern.to_string(), format!("{}/**", pattern)]
+}
+
+/// Get the globs for the patterns in a `.gitignore`.
+///
+/// Patterns without a slash match at any depth, and a directory ignores
+/// everything under it. Negated patterns are not supported and are skipped.
+fn gitignore_globs(gitignore: &str) -> Vec<String> {
+    let mut globs = Vec::new();
+    for line in gitignore.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            continue;
+        }
+        let pattern = line.trim_end_matches('/');
+        let pattern = match pattern.strip_prefix('/') {
+            Some(anchored) => anchored.to_string(),
+            None if pattern.contains('/') => pattern.to_string(),
+            None => format!("**/{}", pattern),
+        };
+        globs.push(format!("{}/**", pattern));
+        globs.push(pattern);
+    }
+    globs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gitignore_globs() {
+        let globs = gitignore_globs("# build output\ndist/\n/coverage\nsrc/gen\n!keep.vue\n\n");
+        assert_eq!(
+            globs,
+            vec![
+                "**/dist/**",
+                "**/dist",
+                "coverage/**",
+                "coverage",
+                "src/gen/**",
+                "src/gen"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tsconfig_globs() {
+        assert_eq!(
+            tsconfig_globs("./src/"),
+            ["src".to_string(), "src/**".to_string()]
+        );
+        let set = build_set(tsconfig_globs("src/**/*.vue"));
+        assert!(set.is_match("src/a/App.vue"));
+        assert!(!set.is_match("src/a/main.ts"));
+    }
+
+    #[test]
+    fn test_file_selection() {
+        let selection = FileSelection::new(&[
+            PathBuf::from("src/App.vue"),
+            PathBuf::from("./src/components/*.vue"),
+        ]);
+        assert_eq!(selection.paths(), [PathBuf::from("src/App.vue")]);
+        assert!(selection.has_globs());
+        assert!(selection.matches_glob(Path::new("src/components/Button.vue")));
+        assert!(!selection.matches_glob(Path::new("src/App.vue")));
+
+        let selection = FileSelection::new(&[PathBuf::from("App.vue")]);
+        assert!(!selection.has_globs());
+        assert!(!selection.matches_glob(Path::new("App.vue")));
+    }
+}
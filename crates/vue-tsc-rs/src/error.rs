@@ -0,0 +1,153 @@
+//! The library-wide error type.
+
+use source_map::Span;
+use std::fmt;
+use ts_runner::TsError;
+use vue_codegen::CodegenError;
+use vue_parser::ParseError;
+use vue_template_compiler::CompileError;
+
+/// Result type for vue-tsc-rs operations.
+pub type VueTscResult<T> = Result<T, VueTscError>;
+
+/// An error from any stage of checking a Vue project.
+///
+/// Each variant wraps the error of the stage it came from, so callers can
+/// match on the category and still reach the original error (and its span)
+/// through the variant or [`std::error::Error::source`].
+#[derive(Debug, Clone)]
+pub enum VueTscError {
+    /// The SFC could not be split into blocks.
+    Parse(ParseError),
+    /// A template could not be compiled.
+    Compile(CompileError),
+    /// Virtual TypeScript could not be generated.
+    Codegen(CodegenError),
+    /// The TypeScript compiler could not be configured or run.
+    Ts(TsError),
+}
+
+impl VueTscError {
+    /// Get the source span of the error, if it points into a Vue file.
+    ///
+    /// Template spans are relative to the template content.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Self::Parse(e) => Some(e.span),
+            Self::Compile(e) => Some(e.span),
+            Self::Codegen(e) => Some(e.span),
+            Self::Ts(_) => None,
+        }
+    }
+
+    /// Get the error message, without the category prefix.
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Parse(e) => &e.message,
+            Self::Compile(e) => &e.message,
+            Self::Codegen(e) => &e.message,
+            Self::Ts(e) => &e.message,
+        }
+    }
+}
+
+impl fmt::Display for VueTscError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "parse error: {}", e),
+            Self::Compile(e) => write!(f, "template error: {}", e),
+            Self::Codegen(e) => write!(f, "codegen error: {}", e),
+            Self::Ts(e) => write!(f, "TypeScript error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for VueTscError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(e) => Some(e),
+            Self::Compile(e) => Some(e),
+            Self::Codegen(e) => Some(e),
+            Self::Ts(e) => Some(e),
+        }
+    }
+}
+
+/// Lets the error travel through `miette` reports, from which the binary
+/// downcasts it to pick an exit code.
+impl miette::Diagnostic for VueTscError {}
+
+impl From<ParseError> for VueTscError {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+impl From<CompileError> for VueTscError {
+    fn from(e: CompileError) -> Self {
+        Self::Compile(e)
+    }
+}
+
+impl From<CodegenError> for VueTscError {
+    fn from(e: CodegenError) -> Self {
+        Self::Codegen(e)
+    }
+}
+
+impl From<TsError> for VueTscError {
+    fn from(e: TsError) -> Self {
+        Self::Ts(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+    use ts_runner::TsErrorKind;
+    use vue_parser::error::ErrorCode;
+    use vue_template_compiler::CompileErrorCode;
+
+    #[test]
+    fn test_parse_and_compile_errors_keep_spans() {
+        let err = VueTscError::from(ParseError::unclosed_tag("template", Span::new(0, 10)));
+        assert!(matches!(err, VueTscError::Parse(ref e) if e.code == ErrorCode::UnclosedTag));
+        assert_eq!(err.span(), Some(Span::new(0, 10)));
+        assert_eq!(err.to_string(), "parse error: Unclosed tag: <template>");
+
+        let err = VueTscError::from(CompileError::new(
+            "Invalid v-for expression",
+            Span::new(5, 12),
+            CompileErrorCode::InvalidVFor,
+        ));
+        assert!(matches!(err, VueTscError::Compile(_)));
+        assert_eq!(err.span(), Some(Span::new(5, 12)));
+        assert_eq!(err.message(), "Invalid v-for expression");
+    }
+
+    #[test]
+    fn test_codegen_error_keeps_span() {
+        let err = VueTscError::from(CodegenError {
+            message: "Unsupported script lang".to_string(),
+            span: Span::new(3, 8),
+        });
+        assert!(matches!(err, VueTscError::Codegen(_)));
+        assert_eq!(err.span(), Some(Span::new(3, 8)));
+    }
+
+    #[test]
+    fn test_ts_error_has_no_span() {
+        let err = VueTscError::from(TsError::config("Invalid tsconfig.json"));
+        assert!(matches!(err, VueTscError::Ts(ref e) if e.kind == TsErrorKind::Config));
+        assert_eq!(err.span(), None);
+    }
+
+    #[test]
+    fn test_source_chain() {
+        let err = VueTscError::from(TsError::process("tsc exited with code 2"));
+        let source = err.source().unwrap();
+        assert_eq!(source.to_string(), "tsc exited with code 2");
+        assert!(source.downcast_ref::<TsError>().is_some());
+    }
+}
@@ -0,0 +1,20 @@
+//! vue-tsc-rs as a library.
+//!
+//! [`run_check`] checks a whole project and returns the results without
+//! printing anything, for build plugins and other embedders; the
+//! `vue-tsc-rs` binary is built on the same [`Orchestrator`]. [`VueTscError`]
+//! unifies the error types of the individual crates.
+
+pub mod check;
+pub mod cli;
+mod config;
+mod discovery;
+pub mod error;
+pub mod orchestrator;
+mod output;
+mod stats;
+mod watch;
+
+pub use check::{run_check, CheckOptions};
+pub use error::{VueTscError, VueTscResult};
+pub use orchestrator::{CheckResult, Orchestrator, ProjectCheckResult};
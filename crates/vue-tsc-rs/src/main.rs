@@ -5,13 +5,71 @@ use miette::Result;
 use std::path::PathBuf;
 use std::process::ExitCode;
 
-mod cli;
-mod config;
-mod orchestrator;
-mod output;
+use vue_tsc_rs::cli::Args;
+use vue_tsc_rs::orchestrator::{CheckResult, Orchestrator};
+use vue_tsc_rs::VueTscError;
 
-use cli::Args;
-use orchestrator::Orchestrator;
+/// The process exit status.
+///
+/// CI can tell diagnostics in the checked code apart from failures to run
+/// the check at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Exit {
+    /// No errors found.
+    Clean = 0,
+    /// Errors found, or warnings beyond what `--fail-on-warning` or
+    /// `--max-warnings` allow.
+    Diagnostics = 1,
+    /// The check couldn't run: bad configuration, unreadable or unparsable
+    /// files, or tsc failing to start.
+    ToolError = 2,
+}
+
+impl Exit {
+    /// Get the status for a completed check.
+    ///
+    /// Warnings fail the check with `fail_on_warning`, or when there are more
+    /// than `max_warnings` of them.
+    fn for_result(
+        result: &CheckResult,
+        fail_on_warning: bool,
+        max_warnings: Option<usize>,
+    ) -> Self {
+        let warnings_allowed = if fail_on_warning {
+            Some(0)
+        } else {
+            max_warnings
+        };
+        if result.failed_count > 0 {
+            Self::ToolError
+        } else if result.error_count > 0
+            || warnings_allowed.is_some_and(|max| result.warning_count > max)
+        {
+            Self::Diagnostics
+        } else {
+            Self::Clean
+        }
+    }
+
+    /// Get the status for a run that failed.
+    ///
+    /// A template that doesn't compile is a problem in the checked code, like
+    /// any other diagnostic; everything else means the check couldn't run.
+    fn for_error(error: &miette::Report) -> Self {
+        match error.downcast_ref::<VueTscError>() {
+            Some(VueTscError::Compile(_)) => Self::Diagnostics,
+            Some(VueTscError::Parse(_) | VueTscError::Codegen(_) | VueTscError::Ts(_)) | None => {
+                Self::ToolError
+            }
+        }
+    }
+}
+
+impl From<Exit> for ExitCode {
+    fn from(exit: Exit) -> Self {
+        ExitCode::from(exit as u8)
+    }
+}
 
 #[tokio::main]
 async fn main() -> ExitCode {
@@ -29,39 +87,255 @@ async fn main() -> ExitCode {
     }))
     .ok();
 
-    match run(args).await {
-        Ok(exit_code) => exit_code,
-        Err(e) => {
-            eprintln!("{:?}", e);
-            ExitCode::FAILURE
-        }
+    let result = run(args).await;
+    if let Err(e) = &result {
+        eprintln!("{:?}", e);
+    }
+    exit_for(&result).into()
+}
+
+/// Get the status for a run, whether it completed or failed.
+fn exit_for(result: &Result<Exit>) -> Exit {
+    match result {
+        Ok(exit) => *exit,
+        Err(e) => Exit::for_error(e),
     }
 }
 
-async fn run(args: Args) -> Result<ExitCode> {
+async fn run(args: Args) -> Result<Exit> {
     // Determine workspace
     let workspace = args
         .workspace
         .clone()
         .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
 
-    // Capture watch flag before moving args
+    // Capture flags before moving args
     let watch = args.watch;
+    let print_virtual = args.print_virtual.clone();
+    let dump_ast = args.dump_ast.clone();
+    let list_files = args.list_files;
+    let explain = args.explain.clone();
+    let check_config = args.check_config;
+    let fail_on_warning = args.fail_on_warning;
+    let max_warnings = args.max_warnings;
 
     // Create orchestrator
     let mut orchestrator = Orchestrator::new(workspace, args)?;
 
+    // Print generated code without type checking
+    if let Some(file) = print_virtual {
+        print!("{}", orchestrator.virtual_code(&file)?);
+        return Ok(Exit::Clean);
+    }
+
+    // Print the template tree without type checking
+    if let Some(file) = dump_ast {
+        print!("{}", orchestrator.dump_ast(&file)?);
+        return Ok(Exit::Clean);
+    }
+
+    // Print the discovered files without type checking
+    if list_files {
+        for file in orchestrator.list_files()? {
+            println!("{}", file.display());
+        }
+        return Ok(Exit::Clean);
+    }
+
+    // Trace component resolution without type checking
+    if let Some(component) = explain {
+        let traces = orchestrator.explain_component(&component)?;
+        if traces.is_empty() {
+            println!("No checked file uses <{}>", component);
+        }
+        for (file, trace) in traces {
+            println!("{}\n{}\n", file.display(), trace);
+        }
+        return Ok(Exit::Clean);
+    }
+
+    // Validate tsconfig.json without type checking
+    if check_config {
+        let (path, errors) = orchestrator.check_config()?;
+        if errors.is_empty() {
+            println!("{}: no problems found", path.display());
+            return Ok(Exit::Clean);
+        }
+        for error in &errors {
+            eprintln!("{}: {}", path.display(), error);
+        }
+        return Ok(Exit::Diagnostics);
+    }
+
     // Run type checking
     if watch {
         orchestrator.run_watch_mode().await?;
-        Ok(ExitCode::SUCCESS)
+        Ok(Exit::Clean)
     } else {
         let result = orchestrator.run_single_check().await?;
+        Ok(Exit::for_result(&result, fail_on_warning, max_warnings))
+    }
+}
 
-        if result.error_count > 0 {
-            Ok(ExitCode::from(1))
-        } else {
-            Ok(ExitCode::SUCCESS)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-exit")
+            .tempdir()
+            .unwrap();
+        for (name, content) in files {
+            std::fs::write(dir.path().join(name), content).unwrap();
         }
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_clean_run_exits_zero() {
+        let dir = workspace(&[("App.vue", "<template><div /></template>\n")]);
+        let exit = run(Args::for_workspace(dir.path())).await.unwrap();
+        assert_eq!(exit, Exit::Clean);
+        assert_eq!(exit as u8, 0);
+    }
+
+    #[tokio::test]
+    async fn test_diagnostics_exit_one() {
+        let dir = workspace(&[(
+            "App.vue",
+            "<template><li v-for=\"(a, b, c, d) in items\">{{ a }}</li></template>\n",
+        )]);
+        let exit = run(Args::for_workspace(dir.path())).await.unwrap();
+        assert_eq!(exit, Exit::Diagnostics);
+        assert_eq!(exit as u8, 1);
+    }
+
+    #[tokio::test]
+    async fn test_config_error_exits_two() {
+        let dir = workspace(&[
+            ("tsconfig.json", "{ not json"),
+            ("App.vue", "<template><div /></template>\n"),
+        ]);
+        let result = run(Args::for_workspace(dir.path())).await;
+        assert!(format!("{:?}", result.as_ref().unwrap_err()).contains("tsconfig"));
+        let exit = exit_for(&result);
+        assert_eq!(exit, Exit::ToolError);
+        assert_eq!(exit as u8, 2);
+    }
+
+    #[test]
+    fn test_exit_for_error() {
+        use source_map::Span;
+        use vue_template_compiler::{CompileError, CompileErrorCode};
+
+        let compile = miette::Report::new(VueTscError::from(CompileError::new(
+            "Invalid v-for expression",
+            Span::new(0, 5),
+            CompileErrorCode::InvalidVFor,
+        )));
+        assert_eq!(Exit::for_error(&compile), Exit::Diagnostics);
+
+        let parse = miette::Report::new(VueTscError::from(vue_parser::ParseError::unclosed_tag(
+            "template",
+            Span::new(0, 10),
+        )))
+        .wrap_err("Failed to parse App.vue");
+        assert_eq!(Exit::for_error(&parse), Exit::ToolError);
+
+        let ts = miette::Report::new(VueTscError::from(ts_runner::TsError::process(
+            "tsc exited with code 2",
+        )));
+        assert_eq!(Exit::for_error(&ts), Exit::ToolError);
+        assert_eq!(
+            Exit::for_error(&miette::miette!("No tsconfig.json found")),
+            Exit::ToolError
+        );
+    }
+
+    #[test]
+    fn test_exit_for_result() {
+        let warnings = CheckResult {
+            warning_count: 2,
+            ..Default::default()
+        };
+        assert_eq!(Exit::for_result(&warnings, false, None), Exit::Clean);
+        assert_eq!(Exit::for_result(&warnings, true, None), Exit::Diagnostics);
+
+        // Files that couldn't be checked outweigh any diagnostics
+        let failed = CheckResult {
+            error_count: 3,
+            failed_count: 1,
+            ..Default::default()
+        };
+        assert_eq!(Exit::for_result(&failed, false, None), Exit::ToolError);
+    }
+
+    #[test]
+    fn test_max_warnings() {
+        let warnings = CheckResult {
+            warning_count: 2,
+            ..Default::default()
+        };
+        // At or below the threshold passes, above it fails
+        assert_eq!(Exit::for_result(&warnings, false, Some(3)), Exit::Clean);
+        assert_eq!(Exit::for_result(&warnings, false, Some(2)), Exit::Clean);
+        assert_eq!(
+            Exit::for_result(&warnings, false, Some(1)),
+            Exit::Diagnostics
+        );
+        assert_eq!(
+            Exit::for_result(&warnings, false, Some(0)),
+            Exit::Diagnostics
+        );
+
+        // --fail-on-warning is stricter than any threshold
+        assert_eq!(
+            Exit::for_result(&warnings, true, Some(5)),
+            Exit::Diagnostics
+        );
+
+        // Errors fail whatever the warning budget
+        let errors = CheckResult {
+            error_count: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            Exit::for_result(&errors, false, Some(10)),
+            Exit::Diagnostics
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_warnings_run() {
+        let dir = workspace(&[(
+            "App.vue",
+            "<template><div v-for=\"item in items\">{{ item }}</div></template>\n",
+        )]);
+        let args = |max_warnings| Args {
+            max_warnings: Some(max_warnings),
+            ..Args::for_workspace(dir.path())
+        };
+        assert_eq!(run(args(1)).await.unwrap(), Exit::Clean);
+        assert_eq!(run(args(0)).await.unwrap(), Exit::Diagnostics);
+    }
+
+    #[tokio::test]
+    async fn test_hints_only_run_exits_zero() {
+        let dir = workspace(&[("App.vue", "<template><p>Count: { count }</p></template>\n")]);
+        let output = dir.path().join("report.txt");
+        let args = Args {
+            output: Some(vue_tsc_rs::cli::OutputFormat::Json),
+            output_file: Some(output.clone()),
+            ..Args::for_workspace(dir.path())
+        };
+        assert_eq!(run(args).await.unwrap(), Exit::Clean);
+
+        let report = std::fs::read_to_string(output).unwrap();
+        let summary: serde_json::Value =
+            serde_json::from_str(report.lines().last().unwrap()).unwrap();
+        assert_eq!(summary["type"], "summary");
+        assert_eq!(summary["errors"], 0);
+        assert_eq!(summary["hints"], 1);
     }
 }
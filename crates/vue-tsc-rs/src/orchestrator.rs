@@ -2,14 +2,28 @@
 
 use crate::cli::Args;
 use crate::config::Config;
+use crate::discovery::{FileFilter, FileSelection};
+use crate::error::VueTscError;
 use crate::output::OutputFormatter;
+use crate::stats::{as_millis, StatsReport};
+use crate::watch::{coalesce_events, WatchReporter};
 use miette::{IntoDiagnostic, Result, WrapErr};
 use rayon::prelude::*;
+use source_map::{LineCol, LineIndex, Span};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
-use ts_runner::{TsDiagnostics, TsRunner, TsRunnerOptions};
-use vue_diagnostics::{diagnose_sfc, Diagnostic, Severity};
+use std::time::{Duration, Instant};
+use ts_runner::config::has_file_extension;
+use ts_runner::{RunTimings, TsDiagnostic, TsDiagnostics, TsError, TsRunner, TsRunnerOptions};
+use vue_codegen::{CodegenOptions, VueTarget};
+use vue_diagnostics::resolution::{trace_sfc, ResolutionTrace};
+use vue_diagnostics::{diagnose_sfc, Diagnostic, DiagnosticCode, Severity};
+use vue_parser::Sfc;
 
 /// Result of a check run.
 #[derive(Debug, Default)]
@@ -20,8 +34,225 @@ pub struct CheckResult {
     pub error_count: usize,
     /// Number of warnings.
     pub warning_count: usize,
+    /// Number of hints, which never fail a check.
+    pub hint_count: usize,
+    /// Number of files that couldn't be read or parsed.
+    pub failed_count: usize,
     /// Time taken.
     pub duration_ms: u64,
+    /// Time spent parsing and diagnosing Vue files.
+    pub parse_duration: Duration,
+    /// Time spent generating virtual TypeScript files.
+    pub codegen_duration: Duration,
+    /// Time spent running the TypeScript compiler.
+    pub tsc_duration: Duration,
+    /// Number of diagnostics per code.
+    pub diagnostic_counts: BTreeMap<String, usize>,
+    /// Peak number of files checked concurrently.
+    pub peak_concurrency: usize,
+    /// Time spent on each file, slowest first.
+    pub file_timings: Vec<FileTimings>,
+}
+
+/// Everything found by checking a project, with nothing printed.
+#[derive(Debug)]
+pub struct ProjectCheckResult {
+    /// Vue diagnostics for each file that has any.
+    pub vue_diagnostics: Vec<(PathBuf, Vec<Diagnostic>)>,
+    /// TypeScript diagnostics, mapped back to `.vue` files.
+    pub ts_diagnostics: TsDiagnostics,
+    /// Files that couldn't be read or parsed, with the reason.
+    pub failed_files: Vec<(PathBuf, String)>,
+    /// Counts and timings.
+    pub summary: CheckResult,
+    /// The contents of every Vue file read, for diagnostic context.
+    sources: SourceCache,
+    /// Number of unchanged files whose cached diagnostics were reused.
+    reused: usize,
+}
+
+impl ProjectCheckResult {
+    /// Add the results of checking another project.
+    fn merge(&mut self, other: ProjectCheckResult) {
+        self.vue_diagnostics.extend(other.vue_diagnostics);
+        self.ts_diagnostics.error_count += other.ts_diagnostics.error_count;
+        self.ts_diagnostics.warning_count += other.ts_diagnostics.warning_count;
+        self.ts_diagnostics
+            .diagnostics
+            .extend(other.ts_diagnostics.diagnostics);
+        self.failed_files.extend(other.failed_files);
+        self.summary.merge(other.summary);
+        self.sources.sources.extend(other.sources.sources);
+        self.reused += other.reused;
+    }
+}
+
+impl CheckResult {
+    /// Add the counts and timings of another check.
+    fn merge(&mut self, other: CheckResult) {
+        self.file_count += other.file_count;
+        self.error_count += other.error_count;
+        self.warning_count += other.warning_count;
+        self.hint_count += other.hint_count;
+        self.failed_count += other.failed_count;
+        self.duration_ms += other.duration_ms;
+        self.parse_duration += other.parse_duration;
+        self.codegen_duration += other.codegen_duration;
+        self.tsc_duration += other.tsc_duration;
+        for (code, count) in other.diagnostic_counts {
+            *self.diagnostic_counts.entry(code).or_default() += count;
+        }
+        self.peak_concurrency = self.peak_concurrency.max(other.peak_concurrency);
+        self.file_timings.extend(other.file_timings);
+        self.file_timings
+            .sort_by_key(|timings| std::cmp::Reverse(timings.total()));
+    }
+}
+
+/// Get the directory a tsconfig's files are relative to, which is the
+/// workspace when checking it as one of several projects.
+fn project_dir(tsconfig: &Path) -> PathBuf {
+    match tsconfig.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// Time spent on each phase for a single file.
+#[derive(Debug, Clone, Default)]
+pub struct FileTimings {
+    /// The file.
+    pub path: PathBuf,
+    /// Time spent parsing the SFC.
+    pub parse: Duration,
+    /// Time spent running Vue diagnostics.
+    pub diagnose: Duration,
+    /// Time spent generating the virtual TypeScript file.
+    pub codegen: Duration,
+    /// Time spent writing the virtual TypeScript file.
+    pub write: Duration,
+}
+
+impl FileTimings {
+    /// Total time spent on the file.
+    pub fn total(&self) -> Duration {
+        self.parse + self.diagnose + self.codegen + self.write
+    }
+}
+
+/// Number of files listed by the verbose timing report.
+const SLOWEST_FILES: usize = 10;
+
+/// Result of running Vue diagnostics over a set of files.
+struct VueCheck {
+    /// Diagnostics for each file that has any.
+    diagnostics: FileDiagnostics,
+    /// The contents of every file read.
+    sources: SourceCache,
+    /// Time spent parsing and diagnosing each file.
+    timings: Vec<FileTimings>,
+    /// Peak number of files checked concurrently.
+    peak_concurrency: usize,
+    /// Number of unchanged files whose cached diagnostics were reused.
+    reused: usize,
+    /// Files that couldn't be read or parsed, with the reason.
+    failed: Vec<(PathBuf, String)>,
+}
+
+/// Result of checking a single Vue file.
+struct CheckedFile {
+    /// The file contents.
+    source: String,
+    /// Vue diagnostics for the file.
+    diagnostics: Vec<Diagnostic>,
+    /// Time spent parsing and diagnosing the file.
+    timings: FileTimings,
+    /// Whether the diagnostics came from the cache.
+    reused: bool,
+}
+
+/// Vue diagnostics for each file that has any.
+type FileDiagnostics = Vec<(PathBuf, Vec<Diagnostic>)>;
+
+/// Original `.vue` contents, read once during discovery.
+///
+/// Diagnostic context is looked up here rather than re-read from disk, so TS
+/// diagnostics mapped back to a `.vue` file show the source that was checked.
+#[derive(Debug, Default)]
+struct SourceCache {
+    /// Workspace root, for resolving relative diagnostic paths.
+    workspace: PathBuf,
+    /// File contents keyed by path.
+    sources: HashMap<PathBuf, String>,
+}
+
+impl SourceCache {
+    /// Create an empty cache.
+    fn new(workspace: PathBuf) -> Self {
+        Self {
+            workspace,
+            sources: HashMap::new(),
+        }
+    }
+
+    /// Get the cached contents of a file.
+    fn get(&self, path: &Path) -> Option<&str> {
+        self.sources
+            .get(path)
+            .or_else(|| self.sources.get(&self.workspace.join(path)))
+            .map(String::as_str)
+    }
+
+    /// Get the source to show as context for a diagnostic in `path`.
+    ///
+    /// `.vue` files only come from the cache. Other files (plain `.ts`, or
+    /// virtual files that could not be remapped) are read from disk if they
+    /// still exist.
+    fn context_source(&self, path: &Path) -> Option<Cow<'_, str>> {
+        if let Some(source) = self.get(path) {
+            return Some(Cow::Borrowed(source));
+        }
+        if path.extension().is_some_and(|ext| ext == "vue") {
+            return None;
+        }
+        std::fs::read_to_string(path).ok().map(Cow::Owned)
+    }
+}
+
+/// Vue diagnostics from earlier runs, keyed by file.
+///
+/// Watch mode checks every file again on each change. A file whose contents
+/// hash the same as last time reuses its diagnostics instead.
+#[derive(Debug, Default)]
+struct DiagnosticCache {
+    /// Content hash and diagnostics keyed by path.
+    files: Mutex<HashMap<PathBuf, (u64, Vec<Diagnostic>)>>,
+}
+
+impl DiagnosticCache {
+    /// Get the diagnostics for a file, if its contents are unchanged.
+    fn get(&self, path: &Path, hash: u64) -> Option<Vec<Diagnostic>> {
+        let files = self.files.lock().unwrap();
+        let (cached_hash, diagnostics) = files.get(path)?;
+        (*cached_hash == hash).then(|| diagnostics.clone())
+    }
+
+    /// Store the diagnostics for a file.
+    fn insert(&self, path: PathBuf, hash: u64, diagnostics: Vec<Diagnostic>) {
+        self.files.lock().unwrap().insert(path, (hash, diagnostics));
+    }
+
+    /// Forget every file.
+    fn clear(&self) {
+        self.files.lock().unwrap().clear();
+    }
+}
+
+/// Hash file contents for the diagnostic cache.
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
 }
 
 /// Orchestrator for running vue-tsc-rs.
@@ -32,64 +263,345 @@ pub struct Orchestrator {
     args: Args,
     /// Output formatter.
     formatter: OutputFormatter,
+    /// Vue diagnostics of files checked by earlier runs, in watch mode.
+    diagnostic_cache: Option<DiagnosticCache>,
+    /// Progress output, in watch mode.
+    watch_reporter: Option<WatchReporter>,
 }
 
 impl Orchestrator {
     /// Create a new orchestrator.
     pub fn new(workspace: PathBuf, args: Args) -> Result<Self> {
         let config = Config::load(&workspace, &args)?;
-        let formatter = OutputFormatter::new(args.output);
+        let formatter = match &args.output_file {
+            Some(path) => {
+                let file = std::fs::File::create(path)
+                    .into_diagnostic()
+                    .wrap_err_with(|| format!("Failed to create {}", path.display()))?;
+                OutputFormatter::with_writer(config.output, Box::new(BufWriter::new(file)))
+            }
+            None => OutputFormatter::new(config.output),
+        };
 
         Ok(Self {
             config,
-            args,
             formatter,
+            diagnostic_cache: args.watch.then(DiagnosticCache::default),
+            watch_reporter: args
+                .watch
+                .then(|| WatchReporter::stderr(args.preserve_watch_output)),
+            args,
         })
     }
 
-    /// Run a single check.
-    pub async fn run_single_check(&mut self) -> Result<CheckResult> {
+    /// Load the configuration again, dropping cached diagnostics that were
+    /// computed with the old one.
+    fn reload_config(&mut self) -> Result<()> {
+        self.config = Config::load(&self.config.workspace, &self.args)?;
+        if let Some(cache) = &self.diagnostic_cache {
+            cache.clear();
+        }
+        Ok(())
+    }
+
+    /// Check if a changed path is the tsconfig, vue-tsc-rs.json or global
+    /// component declarations the configuration was loaded from, which may be
+    /// given relative to the workspace.
+    fn is_config_path(&self, path: &Path) -> bool {
+        [
+            &self.config.tsconfig_path,
+            &self.config.project_config_path,
+            &self.config.diagnostic_options.global_components_dts,
+        ]
+        .into_iter()
+        .flatten()
+        .any(|config| path.ends_with(config.strip_prefix(".").unwrap_or(config)))
+    }
+
+    /// Check the workspace, returning the results without printing them.
+    ///
+    /// With several `--project`s, each is checked in the directory of its
+    /// tsconfig and the results are combined.
+    pub async fn check(&self) -> Result<ProjectCheckResult> {
+        if self.args.project.len() > 1 {
+            self.check_projects().await
+        } else {
+            self.check_workspace().await
+        }
+    }
+
+    /// Check each `--project` on its own and combine the results.
+    async fn check_projects(&self) -> Result<ProjectCheckResult> {
+        let start = Instant::now();
+        let mut combined: Option<ProjectCheckResult> = None;
+        for project in &self.args.project {
+            let workspace = project_dir(project);
+            let args = Args {
+                workspace: Some(workspace.clone()),
+                project: vec![project.clone()],
+                output_file: None,
+                watch: false,
+                ..self.args.clone()
+            };
+            let check = Orchestrator::new(workspace, args)?
+                .check_workspace()
+                .await
+                .wrap_err_with(|| format!("Failed to check {}", project.display()))?;
+            match &mut combined {
+                Some(combined) => combined.merge(check),
+                None => combined = Some(check),
+            }
+        }
+
+        let mut combined = combined.unwrap_or_else(|| ProjectCheckResult {
+            vue_diagnostics: Vec::new(),
+            ts_diagnostics: TsDiagnostics::default(),
+            failed_files: Vec::new(),
+            summary: CheckResult::default(),
+            sources: SourceCache::new(self.config.workspace.clone()),
+            reused: 0,
+        });
+        combined.summary.duration_ms = start.elapsed().as_millis() as u64;
+        Ok(combined)
+    }
+
+    /// Check the files of this workspace.
+    async fn check_workspace(&self) -> Result<ProjectCheckResult> {
         let start = Instant::now();
 
         // Find Vue files
         let vue_files = self.find_vue_files()?;
-
-        if self.args.verbose {
-            eprintln!("Found {} Vue files", vue_files.len());
+        if let Some(reporter) = &self.watch_reporter {
+            reporter.start(vue_files.len());
         }
 
         // Run Vue diagnostics in parallel
-        let vue_diagnostics = self.run_vue_diagnostics(&vue_files)?;
+        let parse_start = Instant::now();
+        let mut vue_check = self.run_vue_diagnostics(&vue_files)?;
+        let parse_duration = parse_start.elapsed();
 
         // Run TypeScript type checking
-        let ts_diagnostics = if !self.args.skip_typecheck {
+        let (mut ts_diagnostics, ts_timings) = if !self.args.skip_typecheck {
             self.run_ts_check().await?
         } else {
-            TsDiagnostics::default()
+            (TsDiagnostics::default(), RunTimings::default())
         };
+        if let Some(reporter) = &self.watch_reporter {
+            reporter.end();
+        }
+        if !self.args.files.is_empty() {
+            // tsc checks the whole project, for the types the files use
+            ts_diagnostics = only_in_files(ts_diagnostics, &vue_files);
+        }
+        dedupe_diagnostics(
+            &vue_check.diagnostics,
+            &mut ts_diagnostics,
+            &vue_check.sources,
+        );
+        order_diagnostics(
+            &mut vue_check.diagnostics,
+            &mut ts_diagnostics,
+            &vue_files,
+            !self.args.no_sort,
+        );
 
-        // Combine and output results
-        let result = self.output_results(&vue_files, &vue_diagnostics, &ts_diagnostics);
-
-        let duration = start.elapsed();
-        let check_result = CheckResult {
+        let (error_count, warning_count, hint_count) =
+            count_by_severity(&vue_check.diagnostics, &ts_diagnostics);
+        let summary = CheckResult {
             file_count: vue_files.len(),
-            error_count: result.0,
-            warning_count: result.1,
-            duration_ms: duration.as_millis() as u64,
+            error_count,
+            warning_count,
+            hint_count,
+            failed_count: vue_check.failed.len(),
+            duration_ms: start.elapsed().as_millis() as u64,
+            parse_duration,
+            codegen_duration: ts_timings.codegen,
+            tsc_duration: ts_timings.tsc,
+            diagnostic_counts: count_by_code(&vue_check.diagnostics, &ts_diagnostics),
+            peak_concurrency: vue_check.peak_concurrency,
+            file_timings: merge_file_timings(vue_check.timings, ts_timings.files),
         };
 
+        Ok(ProjectCheckResult {
+            vue_diagnostics: vue_check.diagnostics,
+            ts_diagnostics,
+            failed_files: vue_check.failed,
+            summary,
+            sources: vue_check.sources,
+            reused: vue_check.reused,
+        })
+    }
+
+    /// Run a single check and print its results.
+    pub async fn run_single_check(&mut self) -> Result<CheckResult> {
+        let check = self.check().await?;
+
+        if self.args.verbose {
+            eprintln!("Found {} Vue files", check.summary.file_count);
+            if check.reused > 0 {
+                eprintln!("Reused diagnostics for {} unchanged files", check.reused);
+            }
+        }
+        for (file, error) in &check.failed_files {
+            eprintln!("Error checking {}: {}", file.display(), error);
+        }
+
+        self.print_diagnostics(&check)
+            .into_diagnostic()
+            .wrap_err("Failed to write diagnostics")?;
+        let check_result = check.summary;
+
         // Show timing if requested
         if self.args.timings {
             eprintln!("\nTiming: {}ms", check_result.duration_ms);
         }
+        if self.args.verbose {
+            write_slowest_files(
+                &mut std::io::stderr(),
+                &check_result.file_timings,
+                &self.config.workspace,
+            )
+            .into_diagnostic()?;
+        }
+
+        // Write the metrics report if requested
+        if let Some(path) = &self.args.stats {
+            StatsReport::from_result(&check_result).write(path)?;
+        }
 
         // Show summary
-        self.formatter.print_summary(&check_result);
+        self.formatter
+            .print_summary(&check_result)
+            .into_diagnostic()
+            .wrap_err("Failed to write summary")?;
 
         Ok(check_result)
     }
 
+    /// Validate the loaded tsconfig, for `--check-config`.
+    ///
+    /// Returns the tsconfig path and its problems.
+    pub fn check_config(&self) -> Result<(PathBuf, Vec<TsError>)> {
+        let (Some(path), Some(tsconfig)) = (&self.config.tsconfig_path, &self.config.tsconfig)
+        else {
+            miette::bail!("No tsconfig.json found");
+        };
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+        Ok((path.clone(), tsconfig.validate(base_dir)))
+    }
+
+    /// Generate the virtual TypeScript for a single Vue file, for `--print-virtual`.
+    pub fn virtual_code(&self, path: &Path) -> Result<String> {
+        let content = std::fs::read_to_string(path)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+
+        let sfc = vue_parser::parse(&content)
+            .map_err(VueTscError::from)
+            .wrap_err_with(|| format!("Failed to parse {}", path.display()))?;
+
+        let result = vue_codegen::generate(&sfc, &self.codegen_options(path, &sfc));
+
+        if self.args.inline_source_map {
+            Ok(result.with_inline_source_map())
+        } else {
+            Ok(result.code)
+        }
+    }
+
+    /// Dump the parsed template of a Vue file as an indented tree.
+    ///
+    /// Spans in the dump are relative to the template content.
+    pub fn dump_ast(&self, path: &Path) -> Result<String> {
+        let content = std::fs::read_to_string(path)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+
+        let sfc = vue_parser::parse(&content)
+            .map_err(VueTscError::from)
+            .wrap_err_with(|| format!("Failed to parse {}", path.display()))?;
+
+        let template = sfc
+            .template
+            .as_ref()
+            .ok_or_else(|| miette::miette!("{} has no <template> block", path.display()))?;
+
+        let ast = vue_template_compiler::parse_template_with_options(
+            &template.content,
+            &self.config.diagnostic_options.parse_options,
+        )
+        .map_err(VueTscError::from)
+        .wrap_err_with(|| format!("Failed to compile the template of {}", path.display()))?;
+
+        Ok(ast.dump(&template.content))
+    }
+
+    /// List the files a check would cover, without checking them.
+    ///
+    /// Paths are relative to the workspace and sorted.
+    pub fn list_files(&self) -> Result<Vec<PathBuf>> {
+        let workspace = &self.config.workspace;
+        let mut files: Vec<PathBuf> = self
+            .find_vue_files()?
+            .into_iter()
+            .map(|file| {
+                file.strip_prefix(workspace)
+                    .map_or(file.clone(), Path::to_path_buf)
+            })
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+
+    /// Trace how `component` resolves in each file that uses it.
+    ///
+    /// Paths are relative to the workspace and sorted. Files that don't parse
+    /// are left out, as a check reports them.
+    pub fn explain_component(&self, component: &str) -> Result<Vec<(PathBuf, ResolutionTrace)>> {
+        let workspace = &self.config.workspace;
+        let mut traces = Vec::new();
+        for file in self.find_vue_files()? {
+            let content = std::fs::read_to_string(&file)
+                .into_diagnostic()
+                .wrap_err_with(|| format!("Failed to read {}", file.display()))?;
+            let Ok(sfc) = vue_parser::parse(&content) else {
+                continue;
+            };
+            if let Some(trace) = trace_sfc(&sfc, component, &self.config.diagnostic_options) {
+                let path = file.strip_prefix(workspace).unwrap_or(&file);
+                traces.push((path.to_path_buf(), trace));
+            }
+        }
+        traces.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(traces)
+    }
+
+    /// Get the options virtual TypeScript is generated with for a file.
+    ///
+    /// A `<!-- @vue-target -->` comment in the file overrides the configured
+    /// target.
+    fn codegen_options(&self, path: &Path, sfc: &Sfc) -> CodegenOptions {
+        CodegenOptions {
+            target: sfc
+                .vue_target()
+                .or(self.config.diagnostic_options.target)
+                .map(VueTarget::from_version)
+                .unwrap_or_default(),
+            filename: Some(path.display().to_string()),
+            parse_options: self.config.diagnostic_options.parse_options.clone(),
+            skip_template: self.args.no_template_check,
+            check_js: self
+                .config
+                .tsconfig
+                .as_ref()
+                .is_some_and(|tsconfig| tsconfig.compiler_options.checks_js()),
+            verbatim_module_syntax: self.config.tsconfig.as_ref().is_some_and(|tsconfig| {
+                tsconfig.compiler_options.verbatim_module_syntax == Some(true)
+            }),
+            ..Default::default()
+        }
+    }
+
     /// Run in watch mode.
     pub async fn run_watch_mode(&mut self) -> Result<()> {
         use notify::{Config as NotifyConfig, RecommendedWatcher, RecursiveMode, Watcher};
@@ -99,7 +611,7 @@ impl Orchestrator {
         eprintln!("Starting watch mode...\n");
 
         // Initial check
-        let _ = self.run_single_check().await;
+        self.run_watched_check().await;
 
         // Set up file watcher
         let (tx, rx) = channel();
@@ -122,21 +634,29 @@ impl Orchestrator {
         loop {
             match rx.recv_timeout(Duration::from_millis(100)) {
                 Ok(event) => {
+                    // One save can fire several events; rerun once for all
+                    let paths = coalesce_events(event, &rx, Duration::from_millis(50));
+
+                    // Diagnostics cached under the old configuration are stale
+                    if paths.iter().any(|p| self.is_config_path(p)) {
+                        if let Err(e) = self.reload_config() {
+                            eprintln!("Failed to reload configuration: {:?}", e);
+                            continue;
+                        }
+                        self.report_rerun("Configuration changed");
+                        self.run_watched_check().await;
+                        continue;
+                    }
+
                     // Check if the changed file is relevant
-                    let should_recheck = event.paths.iter().any(|p| {
-                        p.extension()
-                            .map(|e| e == "vue" || e == "ts" || e == "tsx")
-                            .unwrap_or(false)
+                    let should_recheck = paths.iter().any(|p| {
+                        has_file_extension(p, &self.config.extensions)
+                            || has_file_extension(p, &[".ts", ".tsx"])
                     });
 
                     if should_recheck {
-                        if !self.args.preserve_watch_output {
-                            // Clear screen
-                            print!("\x1B[2J\x1B[1;1H");
-                        }
-
-                        eprintln!("File change detected. Rerunning...\n");
-                        let _ = self.run_single_check().await;
+                        self.report_rerun("File change detected");
+                        self.run_watched_check().await;
                     }
                 }
                 Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
@@ -151,8 +671,62 @@ impl Orchestrator {
         Ok(())
     }
 
+    /// Announce a watch mode rerun.
+    fn report_rerun(&self, reason: &str) {
+        if let Some(reporter) = &self.watch_reporter {
+            reporter.rerun(reason);
+        }
+    }
+
+    /// Run a check in watch mode, ending with a summary line.
+    async fn run_watched_check(&mut self) {
+        match self.run_single_check().await {
+            Ok(result) => {
+                if let Some(reporter) = &self.watch_reporter {
+                    reporter.finish(&result);
+                }
+            }
+            Err(e) => eprintln!("{:?}", e),
+        }
+    }
+
     /// Find all Vue files in the workspace.
+    ///
+    /// Files are filtered by [`FileFilter`]: tsconfig `include` and `exclude`,
+    /// the workspace `.gitignore` and the ignore patterns all apply. Files
+    /// given on the command line restrict this to them; those named by path
+    /// are checked even if the filter would skip them.
     fn find_vue_files(&self) -> Result<Vec<PathBuf>> {
+        if !self.args.files.is_empty() {
+            return self.selected_vue_files(&FileSelection::new(&self.args.files));
+        }
+        self.walk_vue_files(|_| true)
+    }
+
+    /// Find the Vue files given on the command line.
+    fn selected_vue_files(&self, selection: &FileSelection) -> Result<Vec<PathBuf>> {
+        let mut files = if selection.has_globs() {
+            let workspace = &self.config.workspace;
+            self.walk_vue_files(|path| {
+                selection.matches_glob(path.strip_prefix(workspace).unwrap_or(path))
+            })?
+        } else {
+            Vec::new()
+        };
+        for path in selection.paths() {
+            if !path.is_file() {
+                miette::bail!("File not found: {}", path.display());
+            }
+            if !files.contains(path) {
+                files.push(path.clone());
+            }
+        }
+        Ok(files)
+    }
+
+    /// Find the Vue files in the workspace that pass the filter and `select`.
+    fn walk_vue_files(&self, select: impl Fn(&Path) -> bool) -> Result<Vec<PathBuf>> {
+        let filter = FileFilter::new(&self.config);
         let mut files = Vec::new();
 
         for entry in walkdir::WalkDir::new(&self.config.workspace)
@@ -167,143 +741,1173 @@ impl Orchestrator {
                 continue;
             }
 
-            // Check if should process
-            if !self.should_process_path(path) {
-                continue;
+            if filter.matches(path) && select(path) {
+                files.push(path.to_path_buf());
             }
-
-            files.push(path.to_path_buf());
         }
 
         Ok(files)
     }
 
-    /// Check if a path should be processed.
-    fn should_process_path(&self, path: &Path) -> bool {
-        // Check extension
-        let ext = path
-            .extension()
-            .map(|e| e.to_string_lossy().to_string())
-            .unwrap_or_default();
-
-        if ext != "vue" {
-            return false;
-        }
-
-        // Skip node_modules and hidden directories
-        let path_str = path.to_string_lossy();
-        if path_str.contains("node_modules") || path_str.contains("/.") {
-            return false;
-        }
-
-        // Check ignore patterns
-        for pattern in &self.config.ignore_patterns {
-            if path_str.contains(pattern.trim_matches('*')) {
-                return false;
-            }
-        }
+    /// Run Vue-specific diagnostics on files.
+    fn run_vue_diagnostics(&self, files: &[PathBuf]) -> Result<VueCheck> {
+        let results: Arc<Mutex<FileDiagnostics>> = Arc::new(Mutex::new(Vec::new()));
+        let sources = Mutex::new(HashMap::new());
+        let timings = Mutex::new(Vec::with_capacity(files.len()));
+        let active = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let reused = AtomicUsize::new(0);
+        let failed = Mutex::new(Vec::new());
 
-        true
-    }
+        files.par_iter().for_each(|file| {
+            let now_active = active.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now_active, Ordering::SeqCst);
 
-    /// Run Vue-specific diagnostics on files.
-    #[allow(clippy::type_complexity)]
-    fn run_vue_diagnostics(
-        &self,
-        files: &[PathBuf],
-    ) -> Result<Vec<(PathBuf, String, Vec<Diagnostic>)>> {
-        let results: Arc<Mutex<Vec<(PathBuf, String, Vec<Diagnostic>)>>> =
-            Arc::new(Mutex::new(Vec::new()));
-
-        files
-            .par_iter()
-            .for_each(|file| match self.check_vue_file(file) {
-                Ok((source, diagnostics)) => {
-                    if !diagnostics.is_empty() {
+            match self.check_vue_file(file) {
+                Ok(checked) => {
+                    if checked.reused {
+                        reused.fetch_add(1, Ordering::SeqCst);
+                    }
+                    if !checked.diagnostics.is_empty() {
                         let mut results = results.lock().unwrap();
-                        results.push((file.clone(), source, diagnostics));
+                        results.push((file.clone(), checked.diagnostics));
                     }
+                    sources.lock().unwrap().insert(file.clone(), checked.source);
+                    timings.lock().unwrap().push(checked.timings);
                 }
                 Err(e) => {
-                    eprintln!("Error checking {}: {}", file.display(), e);
+                    failed.lock().unwrap().push((file.clone(), e.to_string()));
                 }
-            });
+            }
+
+            active.fetch_sub(1, Ordering::SeqCst);
+            if let Some(reporter) = &self.watch_reporter {
+                reporter.file_done();
+            }
+        });
 
-        Ok(Arc::try_unwrap(results)
+        let results = Arc::try_unwrap(results)
             .unwrap_or_else(|_| panic!("Arc still has multiple references"))
             .into_inner()
-            .unwrap())
+            .unwrap();
+        let sources = SourceCache {
+            sources: sources.into_inner().unwrap(),
+            ..SourceCache::new(self.config.workspace.clone())
+        };
+        Ok(VueCheck {
+            diagnostics: results,
+            sources,
+            timings: timings.into_inner().unwrap(),
+            peak_concurrency: peak.into_inner(),
+            reused: reused.into_inner(),
+            failed: failed.into_inner().unwrap(),
+        })
     }
 
-    /// Check a single Vue file.
-    fn check_vue_file(&self, path: &Path) -> Result<(String, Vec<Diagnostic>)> {
+    /// Check a single Vue file, timing the parse and diagnose phases.
+    ///
+    /// In watch mode, a file unchanged since an earlier run reuses its cached
+    /// diagnostics.
+    fn check_vue_file(&self, path: &Path) -> Result<CheckedFile> {
         let content = std::fs::read_to_string(path)
             .into_diagnostic()
             .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
 
-        let sfc = vue_parser::parse(&content)
-            .map_err(|e| miette::miette!("Parse error in {}: {}", path.display(), e))?;
+        let hash = content_hash(&content);
+        let cache = self.diagnostic_cache.as_ref();
+        if let Some(diagnostics) = cache.and_then(|cache| cache.get(path, hash)) {
+            return Ok(CheckedFile {
+                source: content,
+                diagnostics,
+                timings: FileTimings {
+                    path: path.to_path_buf(),
+                    ..Default::default()
+                },
+                reused: true,
+            });
+        }
 
-        let diagnostics = diagnose_sfc(&sfc, &self.config.diagnostic_options);
+        let start = Instant::now();
+        let sfc = vue_parser::parse(&content).map_err(VueTscError::from)?;
+        let parse = start.elapsed();
 
-        Ok((content, diagnostics))
+        let start = Instant::now();
+        let mut diagnostics = if self.args.no_vue_diagnostics {
+            Vec::new()
+        } else {
+            diagnose_sfc(&sfc, &self.config.diagnostic_options)
+        };
+        let diagnose = start.elapsed();
+
+        // Code the virtual file can't check would otherwise go unnoticed
+        let start = Instant::now();
+        if !self.args.no_vue_diagnostics {
+            let result = vue_codegen::generate(&sfc, &self.codegen_options(path, &sfc));
+            let errors = result.errors.iter().map(|e| {
+                Diagnostic::error(e.message.clone(), e.span, DiagnosticCode::CodegenError)
+            });
+            diagnostics.extend(
+                self.config
+                    .diagnostic_options
+                    .apply_severity_overrides(errors.collect()),
+            );
+        }
+        let codegen = start.elapsed();
+
+        if let Some(cache) = cache {
+            cache.insert(path.to_path_buf(), hash, diagnostics.clone());
+        }
+        Ok(CheckedFile {
+            source: content,
+            diagnostics,
+            timings: FileTimings {
+                path: path.to_path_buf(),
+                parse,
+                diagnose,
+                codegen,
+                ..Default::default()
+            },
+            reused: false,
+        })
     }
 
     /// Run TypeScript type checking.
-    async fn run_ts_check(&self) -> Result<TsDiagnostics> {
+    async fn run_ts_check(&self) -> Result<(TsDiagnostics, RunTimings)> {
         let options = TsRunnerOptions {
             tsconfig: self.config.tsconfig_path.clone(),
             use_tsgo: self.args.use_tsgo,
             emit: self.args.emit_ts,
             generate_virtual: true,
             temp_dir: None,
-            tsc_args: Vec::new(),
+            tsc_args: self.args.tsc_args.clone(),
+            ts_path: self.config.ts_path.clone(),
+            root_dir: self.config.root_dir.clone(),
+            skip_template: self.args.no_template_check,
+            preserve_order: self.args.no_sort,
         };
 
         let runner = TsRunner::new(&self.config.workspace, options)
-            .map_err(|e| miette::miette!("Failed to create TypeScript runner: {}", e))?;
+            .map_err(VueTscError::from)
+            .wrap_err("Failed to create TypeScript runner")?;
 
         runner
-            .run()
+            .run_with_timings()
             .await
-            .map_err(|e| miette::miette!("TypeScript check failed: {}", e))
+            .map_err(VueTscError::from)
+            .wrap_err("TypeScript check failed")
     }
 
-    /// Output results and return error/warning counts.
-    fn output_results(
-        &self,
-        _files: &[PathBuf],
-        vue_diagnostics: &[(PathBuf, String, Vec<Diagnostic>)],
-        ts_diagnostics: &TsDiagnostics,
-    ) -> (usize, usize) {
-        let mut error_count = 0;
-        let mut warning_count = 0;
-
+    /// Print the diagnostics of a check.
+    fn print_diagnostics(&mut self, check: &ProjectCheckResult) -> std::io::Result<()> {
         // Output Vue diagnostics
-        for (file, source, diagnostics) in vue_diagnostics {
+        for (file, diagnostics) in &check.vue_diagnostics {
+            let source = check.sources.get(file);
             for diag in diagnostics {
-                self.formatter
-                    .print_vue_diagnostic(file, diag, Some(source));
-                match diag.severity {
-                    Severity::Error => error_count += 1,
-                    Severity::Warning => warning_count += 1,
-                    Severity::Hint => {}
-                }
+                self.formatter.print_vue_diagnostic(file, diag, source)?;
             }
         }
 
         // Output TypeScript diagnostics
-        for diag in &ts_diagnostics.diagnostics {
-            // Try to read source for context
+        for diag in &check.ts_diagnostics.diagnostics {
             let source = diag
                 .file
-                .as_ref()
-                .and_then(|f| std::fs::read_to_string(f).ok());
-            self.formatter.print_ts_diagnostic(diag, source.as_deref());
+                .as_deref()
+                .and_then(|f| check.sources.context_source(f));
+            self.formatter
+                .print_ts_diagnostic(diag, source.as_deref())?;
+        }
+        Ok(())
+    }
+}
+
+/// Count errors, warnings and hints.
+fn count_by_severity(
+    vue_diagnostics: &[(PathBuf, Vec<Diagnostic>)],
+    ts_diagnostics: &TsDiagnostics,
+) -> (usize, usize, usize) {
+    let mut error_count = ts_diagnostics.error_count;
+    let mut warning_count = ts_diagnostics.warning_count;
+    let mut hint_count = 0;
+    for diag in vue_diagnostics
+        .iter()
+        .flat_map(|(_, diagnostics)| diagnostics)
+    {
+        match diag.severity {
+            Severity::Error => error_count += 1,
+            Severity::Warning => warning_count += 1,
+            Severity::Hint => hint_count += 1,
+        }
+    }
+    (error_count, warning_count, hint_count)
+}
+
+/// Keep the TypeScript diagnostics reported in `files`.
+///
+/// Paths are compared canonicalized, as tsc and the command line may spell
+/// them differently.
+fn only_in_files(ts_diagnostics: TsDiagnostics, files: &[PathBuf]) -> TsDiagnostics {
+    let canonical = |path: &Path| std::fs::canonicalize(path).unwrap_or_else(|_| path.into());
+    let files: Vec<PathBuf> = files.iter().map(|file| canonical(file)).collect();
+    let mut kept = TsDiagnostics::new();
+    for diag in ts_diagnostics.diagnostics {
+        if diag
+            .file
+            .as_deref()
+            .is_some_and(|file| files.contains(&canonical(file)))
+        {
+            kept.add(diag);
+        }
+    }
+    kept
+}
+
+/// Drop TypeScript diagnostics that repeat a Vue diagnostic.
+///
+/// A template problem can be reported both by the Vue diagnostics and by tsc
+/// on the generated code. A TypeScript error of the same kind whose range in
+/// the `.vue` file overlaps the Vue diagnostic's span is dropped, keeping the
+/// more specific Vue message.
+fn dedupe_diagnostics(
+    vue_diagnostics: &FileDiagnostics,
+    ts_diagnostics: &mut TsDiagnostics,
+    sources: &SourceCache,
+) {
+    let by_file: HashMap<&Path, &[Diagnostic]> = vue_diagnostics
+        .iter()
+        .map(|(file, diagnostics)| (file.as_path(), diagnostics.as_slice()))
+        .collect();
+    let is_duplicate = |diag: &TsDiagnostic| -> bool {
+        let Some(file) = diag.file.as_deref() else {
+            return false;
+        };
+        let Some(vue) = by_file
+            .get(file)
+            .or_else(|| by_file.get(sources.workspace.join(file).as_path()))
+        else {
+            return false;
+        };
+        let Some(span) = sources.get(file).and_then(|source| ts_span(diag, source)) else {
+            return false;
+        };
+        vue.iter()
+            .any(|d| d.code.ts_codes().contains(&diag.code) && d.span.intersects(span))
+    };
+
+    if ts_diagnostics.diagnostics.iter().any(is_duplicate) {
+        let diagnostics = std::mem::take(&mut ts_diagnostics.diagnostics);
+        *ts_diagnostics = TsDiagnostics::new();
+        for diag in diagnostics.into_iter().filter(|d| !is_duplicate(d)) {
+            ts_diagnostics.add(diag);
         }
-        error_count += ts_diagnostics.error_count;
-        warning_count += ts_diagnostics.warning_count;
+    }
+}
 
-        (error_count, warning_count)
+/// Get the range of a TypeScript diagnostic as offsets in its source.
+///
+/// Without an end position the range is empty.
+fn ts_span(diag: &TsDiagnostic, source: &str) -> Option<Span> {
+    let index = LineIndex::new(source);
+    let offset = |line: Option<u32>, column: Option<u32>| {
+        index.offset(LineCol {
+            line: line?.checked_sub(1)?,
+            col: column?.checked_sub(1)?,
+        })
+    };
+    let start = offset(diag.line, diag.column)?;
+    let end = offset(diag.end_line, diag.end_column).unwrap_or(start);
+    Some(Span::new(start, end.max(start)))
+}
+
+/// Put diagnostics in output order.
+///
+/// Sorted, Vue diagnostics go by file and position like TypeScript's.
+/// Otherwise Vue diagnostics follow file discovery order, as they're checked
+/// in parallel, and TypeScript's keep the order tsc reported them in.
+fn order_diagnostics(
+    vue_diagnostics: &mut FileDiagnostics,
+    ts_diagnostics: &mut TsDiagnostics,
+    files: &[PathBuf],
+    sort: bool,
+) {
+    if sort {
+        vue_diagnostics.sort_by(|a, b| a.0.cmp(&b.0));
+        for (_, diagnostics) in vue_diagnostics.iter_mut() {
+            diagnostics.sort_by_key(|d| (d.span.start, d.span.end));
+        }
+        ts_diagnostics.sort();
+    } else {
+        let position: HashMap<&PathBuf, usize> =
+            files.iter().enumerate().map(|(i, f)| (f, i)).collect();
+        vue_diagnostics.sort_by_key(|(file, _)| position.get(file).copied());
+    }
+}
+
+/// Combine the diagnose-phase timings with those of the TypeScript runner,
+/// slowest file first.
+///
+/// The runner parses each file again, so both parse times are counted.
+fn merge_file_timings(
+    timings: Vec<FileTimings>,
+    virtual_files: Vec<ts_runner::FileTimings>,
+) -> Vec<FileTimings> {
+    let mut by_path: BTreeMap<PathBuf, FileTimings> = timings
+        .into_iter()
+        .map(|timings| (timings.path.clone(), timings))
+        .collect();
+    for file in virtual_files {
+        let timings = by_path
+            .entry(file.path.clone())
+            .or_insert_with(|| FileTimings {
+                path: file.path,
+                ..Default::default()
+            });
+        timings.parse += file.parse;
+        timings.codegen += file.codegen;
+        timings.write += file.write;
+    }
+
+    let mut timings: Vec<_> = by_path.into_values().collect();
+    timings.sort_by_key(|timings| std::cmp::Reverse(timings.total()));
+    timings
+}
+
+/// Write the slowest files and their per-phase timings, for `--verbose`.
+fn write_slowest_files(
+    writer: &mut impl Write,
+    timings: &[FileTimings],
+    workspace: &Path,
+) -> std::io::Result<()> {
+    if timings.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(writer, "Slowest files:")?;
+    for timings in timings.iter().take(SLOWEST_FILES) {
+        let path = timings
+            .path
+            .strip_prefix(workspace)
+            .unwrap_or(&timings.path);
+        writeln!(
+            writer,
+            "  {:>8.2}ms  {} (parse {:.2}ms, diagnose {:.2}ms, codegen {:.2}ms, write {:.2}ms)",
+            as_millis(timings.total()),
+            path.display(),
+            as_millis(timings.parse),
+            as_millis(timings.diagnose),
+            as_millis(timings.codegen),
+            as_millis(timings.write)
+        )?;
+    }
+    Ok(())
+}
+
+/// Count diagnostics by code.
+///
+/// TypeScript codes are prefixed with `TS`, as tsc prints them.
+fn count_by_code(
+    vue_diagnostics: &[(PathBuf, Vec<Diagnostic>)],
+    ts_diagnostics: &TsDiagnostics,
+) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for (_, diagnostics) in vue_diagnostics {
+        for diag in diagnostics {
+            *counts.entry(diag.code.as_str().to_string()).or_insert(0) += 1;
+        }
+    }
+    for diag in &ts_diagnostics.diagnostics {
+        *counts.entry(format!("TS{}", diag.code)).or_insert(0) += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ts_runner::TsSeverity;
+    use vue_diagnostics::DiagnosticCode;
+
+    #[test]
+    fn test_count_by_code_with_mixed_diagnostics() {
+        let vue = vec![(
+            PathBuf::from("App.vue"),
+            vec![
+                Diagnostic::warning("", Span::empty(0), DiagnosticCode::MissingKey),
+                Diagnostic::warning("", Span::empty(0), DiagnosticCode::MissingKey),
+                Diagnostic::warning("", Span::empty(0), DiagnosticCode::UnknownComponent),
+            ],
+        )];
+        let mut ts = TsDiagnostics::new();
+        for code in [2322, 2322, 2304] {
+            ts.add(TsDiagnostic {
+                message: String::new(),
+                code,
+                severity: TsSeverity::Error,
+                file: None,
+                line: None,
+                column: None,
+                end_line: None,
+                end_column: None,
+                related: Vec::new(),
+            });
+        }
+
+        let counts = count_by_code(&vue, &ts);
+        let expected = BTreeMap::from([
+            ("TS2304".to_string(), 1),
+            ("TS2322".to_string(), 2),
+            ("missing-key".to_string(), 2),
+            ("unknown-component".to_string(), 1),
+        ]);
+        assert_eq!(counts, expected);
+    }
+
+    #[test]
+    fn test_duplicate_ts_diagnostics_collapse() {
+        let source = "<template>\n  <MyCard :titel=\"t\" :size=\"s\" />\n</template>\n";
+        let prop = |name: &str| {
+            let start = source.find(name).unwrap() as u32 - 1;
+            Span::new(start, start + name.len() as u32 + 5)
+        };
+        let vue = vec![(
+            PathBuf::from("/project/App.vue"),
+            vec![
+                Diagnostic::error(
+                    "Unknown prop `titel`",
+                    prop("titel"),
+                    DiagnosticCode::UnknownProp,
+                ),
+                Diagnostic::warning("Missing key", prop("size"), DiagnosticCode::MissingKey),
+            ],
+        )];
+        let ts_diagnostic = |code, column| TsDiagnostic {
+            message: String::new(),
+            code,
+            severity: TsSeverity::Error,
+            file: Some(PathBuf::from("App.vue")),
+            line: Some(2),
+            column: Some(column),
+            end_line: None,
+            end_column: None,
+            related: Vec::new(),
+        };
+        let mut ts = TsDiagnostics::new();
+        // Same place and kind as the unknown prop
+        ts.add(ts_diagnostic(2353, 12));
+        // Same place, different kind
+        ts.add(ts_diagnostic(2322, 12));
+        // Same kind, elsewhere
+        ts.add(ts_diagnostic(2353, 33));
+        // Overlaps a Vue diagnostic with no TypeScript equivalent
+        ts.add(ts_diagnostic(2353, 25));
+
+        let mut sources = SourceCache::new(PathBuf::from("/project"));
+        sources
+            .sources
+            .insert(PathBuf::from("/project/App.vue"), source.to_string());
+        dedupe_diagnostics(&vue, &mut ts, &sources);
+
+        let kept: Vec<_> = ts.diagnostics.iter().map(|d| (d.code, d.column)).collect();
+        assert_eq!(
+            kept,
+            vec![(2322, Some(12)), (2353, Some(33)), (2353, Some(25))]
+        );
+        assert_eq!(ts.error_count, 3);
+    }
+
+    #[test]
+    fn test_ts_span() {
+        let diag = TsDiagnostic {
+            message: String::new(),
+            code: 2322,
+            severity: TsSeverity::Error,
+            file: None,
+            line: Some(2),
+            column: Some(3),
+            end_line: Some(2),
+            end_column: Some(7),
+            related: Vec::new(),
+        };
+        assert_eq!(ts_span(&diag, "abc\ndefghij\n"), Some(Span::new(6, 10)));
+        // Positions outside the source have no span
+        assert_eq!(ts_span(&diag, "abc"), None);
+    }
+
+    #[test]
+    fn test_sorted_and_original_order() {
+        let ts_diagnostic = |file: &str, line| TsDiagnostic {
+            message: String::new(),
+            code: 2322,
+            severity: TsSeverity::Error,
+            file: Some(PathBuf::from(file)),
+            line: Some(line),
+            column: Some(1),
+            end_line: None,
+            end_column: None,
+            related: Vec::new(),
+        };
+        let key = |start| Diagnostic::warning("", Span::empty(start), DiagnosticCode::MissingKey);
+        let files = vec![PathBuf::from("B.vue"), PathBuf::from("A.vue")];
+
+        // As reported: checked in parallel, and in tsc's order
+        let reported = || {
+            let vue = vec![
+                (PathBuf::from("A.vue"), vec![key(1)]),
+                (PathBuf::from("B.vue"), vec![key(20), key(5)]),
+            ];
+            let mut ts = TsDiagnostics::new();
+            for (file, line) in [("b.ts", 3), ("a.ts", 10), ("a.ts", 2)] {
+                ts.add(ts_diagnostic(file, line));
+            }
+            (vue, ts)
+        };
+        let order = |vue: &FileDiagnostics, ts: &TsDiagnostics| {
+            let vue: Vec<_> = vue
+                .iter()
+                .flat_map(|(f, d)| {
+                    d.iter()
+                        .map(move |d| (f.display().to_string(), d.span.start))
+                })
+                .collect();
+            let ts: Vec<_> = ts
+                .diagnostics
+                .iter()
+                .map(|d| {
+                    (
+                        d.file.as_ref().unwrap().display().to_string(),
+                        d.line.unwrap(),
+                    )
+                })
+                .collect();
+            (vue, ts)
+        };
+
+        let (mut vue, mut ts) = reported();
+        order_diagnostics(&mut vue, &mut ts, &files, true);
+        let (vue, ts) = order(&vue, &ts);
+        assert_eq!(
+            vue,
+            [
+                ("A.vue".into(), 1),
+                ("B.vue".into(), 5),
+                ("B.vue".into(), 20)
+            ]
+        );
+        assert_eq!(
+            ts,
+            [("a.ts".into(), 2), ("a.ts".into(), 10), ("b.ts".into(), 3)]
+        );
+
+        let (mut vue, mut ts) = reported();
+        order_diagnostics(&mut vue, &mut ts, &files, false);
+        let (vue, ts) = order(&vue, &ts);
+        assert_eq!(
+            vue,
+            [
+                ("B.vue".into(), 20),
+                ("B.vue".into(), 5),
+                ("A.vue".into(), 1)
+            ]
+        );
+        assert_eq!(
+            ts,
+            [("b.ts".into(), 3), ("a.ts".into(), 10), ("a.ts".into(), 2)]
+        );
+    }
+
+    #[test]
+    fn test_merge_file_timings() {
+        let ms = Duration::from_millis;
+        let timings = vec![
+            FileTimings {
+                path: PathBuf::from("/app/A.vue"),
+                parse: ms(1),
+                diagnose: ms(1),
+                ..Default::default()
+            },
+            FileTimings {
+                path: PathBuf::from("/app/B.vue"),
+                parse: ms(2),
+                diagnose: ms(3),
+                ..Default::default()
+            },
+        ];
+        let virtual_files = vec![ts_runner::FileTimings {
+            path: PathBuf::from("/app/A.vue"),
+            parse: ms(1),
+            codegen: ms(4),
+            write: ms(2),
+        }];
+
+        let merged = merge_file_timings(timings, virtual_files);
+        assert_eq!(merged[0].path, PathBuf::from("/app/A.vue"));
+        assert_eq!(merged[0].parse, ms(2));
+        assert_eq!(merged[0].total(), ms(9));
+        assert_eq!(merged[1].path, PathBuf::from("/app/B.vue"));
+        assert_eq!(merged[1].codegen, Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_verbose_output_includes_file_timings() {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-verbose")
+            .tempdir()
+            .unwrap();
+        for name in ["A.vue", "B.vue"] {
+            std::fs::write(dir.path().join(name), "<template><div /></template>\n").unwrap();
+        }
+
+        let args = Args {
+            verbose: true,
+            ..Args::for_workspace(dir.path())
+        };
+        let mut orchestrator = Orchestrator::new(dir.path().to_path_buf(), args).unwrap();
+        let result = orchestrator.run_single_check().await.unwrap();
+        assert_eq!(result.file_timings.len(), 2);
+
+        let mut output = Vec::new();
+        write_slowest_files(&mut output, &result.file_timings, dir.path()).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines[0], "Slowest files:");
+        for name in ["A.vue", "B.vue"] {
+            let line = lines.iter().find(|l| l.contains(name)).unwrap();
+            assert!(line.contains("ms  "));
+            assert!(line.contains("(parse "));
+            assert!(line.contains(", diagnose "));
+            assert!(line.contains(", codegen "));
+            assert!(line.contains(", write "));
+        }
+    }
+
+    #[test]
+    fn test_context_source_comes_from_cache() {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-sources")
+            .tempdir()
+            .unwrap();
+        let file = dir.path().join("App.vue");
+        std::fs::write(&file, "<template><div /></template>\n").unwrap();
+        let script = dir.path().join("main.ts");
+        std::fs::write(&script, "export const x: number = 'a'\n").unwrap();
+
+        let orchestrator =
+            Orchestrator::new(dir.path().to_path_buf(), Args::for_workspace(dir.path())).unwrap();
+        let sources = orchestrator
+            .run_vue_diagnostics(std::slice::from_ref(&file))
+            .unwrap()
+            .sources;
+
+        // Later edits on disk don't change the context shown for this run
+        std::fs::write(&file, "<template><span /></template>\n").unwrap();
+        assert_eq!(
+            sources.context_source(&file).as_deref(),
+            Some("<template><div /></template>\n")
+        );
+        let relative = file.strip_prefix(dir.path()).unwrap();
+        assert_eq!(
+            sources.get(relative),
+            Some("<template><div /></template>\n")
+        );
+
+        // Non-Vue files are still read from disk, unknown `.vue` files are not
+        assert_eq!(
+            sources.context_source(&script).as_deref(),
+            Some("export const x: number = 'a'\n")
+        );
+        assert!(sources
+            .context_source(&dir.path().join("Other.vue"))
+            .is_none());
+        assert!(sources
+            .context_source(&dir.path().join("App.abc123.ts"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_unchanged_files_reuse_diagnostics() {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-cache")
+            .tempdir()
+            .unwrap();
+        let files = vec![dir.path().join("A.vue"), dir.path().join("B.vue")];
+        for file in &files {
+            std::fs::write(
+                file,
+                "<template><div v-for=\"item in items\">{{ item }}</div></template>\n",
+            )
+            .unwrap();
+        }
+
+        let args = Args {
+            watch: true,
+            ..Args::for_workspace(dir.path())
+        };
+        let orchestrator = Orchestrator::new(dir.path().to_path_buf(), args).unwrap();
+        let first = orchestrator.run_vue_diagnostics(&files).unwrap();
+        assert_eq!(first.reused, 0);
+        assert_eq!(first.diagnostics.len(), 2);
+
+        // A second watch iteration with no changes parses nothing
+        let second = orchestrator.run_vue_diagnostics(&files).unwrap();
+        assert_eq!(second.reused, 2);
+        assert_eq!(second.diagnostics.len(), 2);
+        assert!(second.timings.iter().all(|t| t.total() == Duration::ZERO));
+
+        // An edited file is checked again
+        std::fs::write(&files[0], "<template><div /></template>\n").unwrap();
+        let third = orchestrator.run_vue_diagnostics(&files).unwrap();
+        assert_eq!(third.reused, 1);
+        assert_eq!(third.diagnostics.len(), 1);
+        assert_eq!(third.diagnostics[0].0, files[1]);
+
+        // A one-shot run has no later iteration to reuse anything in
+        let orchestrator =
+            Orchestrator::new(dir.path().to_path_buf(), Args::for_workspace(dir.path())).unwrap();
+        orchestrator.run_vue_diagnostics(&files).unwrap();
+        assert!(orchestrator.diagnostic_cache.is_none());
+        assert_eq!(orchestrator.run_vue_diagnostics(&files).unwrap().reused, 0);
+    }
+
+    #[test]
+    fn test_codegen_errors_are_reported() {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-codegen-errors")
+            .tempdir()
+            .unwrap();
+        let file = dir.path().join("App.vue");
+        let source = "<template>\n  <p v-if>hi</p>\n</template>\n";
+        std::fs::write(&file, source).unwrap();
+
+        let orchestrator =
+            Orchestrator::new(dir.path().to_path_buf(), Args::for_workspace(dir.path())).unwrap();
+        let check = orchestrator
+            .run_vue_diagnostics(std::slice::from_ref(&file))
+            .unwrap();
+        let codegen: Vec<_> = check.diagnostics[0]
+            .1
+            .iter()
+            .filter(|d| d.code == DiagnosticCode::CodegenError)
+            .collect();
+        assert_eq!(codegen.len(), 1);
+        assert_eq!(codegen[0].message, "v-if needs a condition");
+        assert_eq!(codegen[0].severity, Severity::Error);
+        assert_eq!(&source[codegen[0].span.to_range()], "v-if");
+    }
+
+    #[test]
+    fn test_no_vue_diagnostics() {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-no-vue-diagnostics")
+            .tempdir()
+            .unwrap();
+        let file = dir.path().join("App.vue");
+        std::fs::write(
+            &file,
+            "<template><div v-for=\"item in items\">{{ item }}</div></template>\n",
+        )
+        .unwrap();
+
+        let args = Args {
+            no_vue_diagnostics: true,
+            ..Args::for_workspace(dir.path())
+        };
+        let orchestrator = Orchestrator::new(dir.path().to_path_buf(), args).unwrap();
+        let check = orchestrator
+            .run_vue_diagnostics(std::slice::from_ref(&file))
+            .unwrap();
+        assert!(check.diagnostics.iter().all(|(_, d)| d.is_empty()));
+    }
+
+    #[test]
+    fn test_no_template_check_omits_template_code() {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-no-template-check")
+            .tempdir()
+            .unwrap();
+        let file = dir.path().join("App.vue");
+        std::fs::write(&file, "<template><div>{{ msg }}</div></template>\n").unwrap();
+
+        let args = Args {
+            no_template_check: true,
+            ..Args::for_workspace(dir.path())
+        };
+        let orchestrator = Orchestrator::new(dir.path().to_path_buf(), args).unwrap();
+        let code = orchestrator.virtual_code(&file).unwrap();
+        assert!(!code.contains("function __VLS_template()"));
+    }
+
+    #[test]
+    fn test_config_change_invalidates_diagnostic_cache() {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-cache-config")
+            .tempdir()
+            .unwrap();
+        let tsconfig = dir.path().join("tsconfig.json");
+        std::fs::write(&tsconfig, "{}").unwrap();
+        let file = dir.path().join("App.vue");
+        std::fs::write(&file, "<template><MyCard /></template>\n").unwrap();
+        let files = std::slice::from_ref(&file);
+
+        let components_dts = dir.path().join("components.d.ts");
+        std::fs::write(&components_dts, "export {}\n").unwrap();
+
+        let args = Args {
+            watch: true,
+            ..Args::for_workspace(dir.path())
+        };
+        let mut orchestrator = Orchestrator::new(dir.path().to_path_buf(), args).unwrap();
+        assert!(orchestrator.is_config_path(&tsconfig));
+        assert!(orchestrator.is_config_path(&components_dts));
+        assert!(!orchestrator.is_config_path(&file));
+        assert!(orchestrator
+            .run_vue_diagnostics(files)
+            .unwrap()
+            .diagnostics
+            .is_empty());
+
+        std::fs::write(
+            &tsconfig,
+            r#"{ "vueCompilerOptions": { "checkUnknownComponents": true } }"#,
+        )
+        .unwrap();
+        orchestrator.reload_config().unwrap();
+        let check = orchestrator.run_vue_diagnostics(files).unwrap();
+        assert_eq!(check.reused, 0);
+        assert_eq!(check.diagnostics.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_output_file() {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-output")
+            .tempdir()
+            .unwrap();
+        std::fs::write(
+            dir.path().join("App.vue"),
+            "<template><div v-for=\"item in items\">{{ item }}</div></template>\n",
+        )
+        .unwrap();
+        let output_file = dir.path().join("report.txt");
+
+        let args = Args {
+            output_file: Some(output_file.clone()),
+            ..Args::for_workspace(dir.path())
+        };
+        let mut orchestrator = Orchestrator::new(dir.path().to_path_buf(), args).unwrap();
+        orchestrator.run_single_check().await.unwrap();
+
+        let output = std::fs::read_to_string(&output_file).unwrap();
+        assert!(output.contains("App.vue:"));
+        assert!(output.contains(":warning:"));
+    }
+
+    #[tokio::test]
+    async fn test_multiple_projects() {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-projects")
+            .tempdir()
+            .unwrap();
+        let missing_key = "<template><div v-for=\"item in items\">{{ item }}</div></template>\n";
+        for (package, files) in [("a", &["App.vue", "List.vue"][..]), ("b", &["App.vue"][..])] {
+            let package = dir.path().join("packages").join(package);
+            std::fs::create_dir_all(&package).unwrap();
+            std::fs::write(package.join("tsconfig.json"), "{}").unwrap();
+            for file in files {
+                std::fs::write(package.join(file), missing_key).unwrap();
+            }
+        }
+        // Outside both projects, so not checked
+        std::fs::write(dir.path().join("Root.vue"), missing_key).unwrap();
+
+        let args = Args {
+            project: vec![
+                dir.path().join("packages/a/tsconfig.json"),
+                dir.path().join("packages/b/tsconfig.json"),
+            ],
+            ..Args::for_workspace(dir.path())
+        };
+        let orchestrator = Orchestrator::new(dir.path().to_path_buf(), args).unwrap();
+        let check = orchestrator.check().await.unwrap();
+
+        assert_eq!(check.summary.file_count, 3);
+        assert_eq!(check.summary.warning_count, 3);
+        assert_eq!(check.summary.diagnostic_counts["missing-key"], 3);
+        let mut files: Vec<_> = check
+            .vue_diagnostics
+            .iter()
+            .map(|(file, _)| file.strip_prefix(dir.path()).unwrap().to_path_buf())
+            .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            [
+                PathBuf::from("packages/a/App.vue"),
+                PathBuf::from("packages/a/List.vue"),
+                PathBuf::from("packages/b/App.vue"),
+            ]
+        );
+        for (file, _) in &check.vue_diagnostics {
+            assert!(check.sources.get(file).is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_selected_files() {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-files")
+            .tempdir()
+            .unwrap();
+        let missing_key = "<template><div v-for=\"item in items\">{{ item }}</div></template>\n";
+        std::fs::create_dir_all(dir.path().join("src/components")).unwrap();
+        for file in [
+            "src/App.vue",
+            "src/components/Button.vue",
+            "src/components/Card.vue",
+        ] {
+            std::fs::write(dir.path().join(file), missing_key).unwrap();
+        }
+        let orchestrator = |files: Vec<PathBuf>| {
+            let args = Args {
+                files,
+                ..Args::for_workspace(dir.path())
+            };
+            Orchestrator::new(dir.path().to_path_buf(), args).unwrap()
+        };
+
+        // A single file is checked alone
+        let app = dir.path().join("src/App.vue");
+        let result = orchestrator(vec![app.clone()]).check().await.unwrap();
+        assert_eq!(result.summary.file_count, 1);
+        assert_eq!(result.vue_diagnostics.len(), 1);
+        assert_eq!(result.vue_diagnostics[0].0, app);
+
+        // Globs are relative to the workspace
+        let result = orchestrator(vec![PathBuf::from("src/components/*.vue")])
+            .check()
+            .await
+            .unwrap();
+        assert_eq!(result.summary.file_count, 2);
+
+        let error = orchestrator(vec![dir.path().join("src/Missing.vue")])
+            .check()
+            .await
+            .unwrap_err();
+        assert!(error.to_string().starts_with("File not found"));
+    }
+
+    #[test]
+    fn test_only_in_files() {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-only")
+            .tempdir()
+            .unwrap();
+        std::fs::write(dir.path().join("App.vue"), "").unwrap();
+        let diagnostic = |file: &str| TsDiagnostic {
+            message: String::new(),
+            code: 2322,
+            severity: TsSeverity::Error,
+            file: Some(dir.path().join(file)),
+            line: Some(1),
+            column: Some(1),
+            end_line: None,
+            end_column: None,
+            related: Vec::new(),
+        };
+        let mut ts = TsDiagnostics::new();
+        ts.add(diagnostic("App.vue"));
+        ts.add(diagnostic("Other.vue"));
+
+        // The selected path is spelled differently from tsc's
+        let selected = dir.path().join(".").join("App.vue");
+        let kept = only_in_files(ts, &[selected]);
+        assert_eq!(kept.error_count, 1);
+        assert_eq!(kept.diagnostics[0].file, Some(dir.path().join("App.vue")));
+    }
+
+    #[test]
+    fn test_project_dir() {
+        assert_eq!(
+            project_dir(Path::new("packages/a/tsconfig.json")),
+            PathBuf::from("packages/a")
+        );
+        assert_eq!(project_dir(Path::new("tsconfig.json")), PathBuf::from("."));
+    }
+
+    #[test]
+    fn test_virtual_code() {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-virtual")
+            .tempdir()
+            .unwrap();
+        let file = dir.path().join("Hello.vue");
+        std::fs::write(
+            &file,
+            "<script setup lang=\"ts\">\nconst msg = 'hi'\n</script>\n<template>{{ msg }}</template>\n",
+        )
+        .unwrap();
+
+        let args = Args {
+            print_virtual: Some(file.clone()),
+            ..Args::for_workspace(dir.path())
+        };
+        let orchestrator = Orchestrator::new(dir.path().to_path_buf(), args).unwrap();
+        let code = orchestrator.virtual_code(&file).unwrap();
+        assert!(code.contains("__VLS_setup"));
+        assert!(!code.contains("//# sourceMappingURL="));
+
+        let args = Args {
+            print_virtual: Some(file.clone()),
+            inline_source_map: true,
+            ..Args::for_workspace(dir.path())
+        };
+        let orchestrator = Orchestrator::new(dir.path().to_path_buf(), args).unwrap();
+        let code = orchestrator.virtual_code(&file).unwrap();
+        let encoded = code
+            .lines()
+            .last()
+            .unwrap()
+            .strip_prefix("//# sourceMappingURL=data:application/json;base64,")
+            .unwrap();
+        let map: serde_json::Value = serde_json::from_slice(&base64_decode(encoded)).unwrap();
+        assert_eq!(map["version"], 3);
+        assert_eq!(map["sources"][0], file.display().to_string());
+        assert!(!map["mappings"].as_str().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_dump_ast() {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-dump-ast")
+            .tempdir()
+            .unwrap();
+        let file = dir.path().join("Hello.vue");
+        std::fs::write(&file, "<template><p v-if=\"ok\">{{ msg }}</p></template>\n").unwrap();
+        let script_only = dir.path().join("Script.vue");
+        std::fs::write(&script_only, "<script setup>\nconst a = 1\n</script>\n").unwrap();
+
+        let orchestrator =
+            Orchestrator::new(dir.path().to_path_buf(), Args::for_workspace(dir.path())).unwrap();
+        assert_eq!(
+            orchestrator.dump_ast(&file).unwrap(),
+            "Template @0..26\n  If @0..26\n    v-if `ok` @0..26\n      Element <p> @0..26\n        Interpolation `msg` @13..22\n"
+        );
+
+        let err = orchestrator.dump_ast(&script_only).unwrap_err();
+        assert!(err.to_string().contains("has no <template> block"));
+    }
+
+    #[test]
+    fn test_list_files() {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-list-files")
+            .tempdir()
+            .unwrap();
+        let root = dir.path();
+        for file in [
+            "src/App.vue",
+            "src/components/Button.vue",
+            "src/legacy/Old.vue",
+            "src/generated/Api.vue",
+            "src/dist/Bundle.vue",
+            "src/main.ts",
+            "stories/Button.vue",
+            "node_modules/lib/Lib.vue",
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, "<template><div /></template>\n").unwrap();
+        }
+        std::fs::write(
+            root.join("tsconfig.json"),
+            r#"{ "include": ["src"], "exclude": ["src/legacy"] }"#,
+        )
+        .unwrap();
+        std::fs::write(root.join(".gitignore"), "dist/\n").unwrap();
+
+        let mut args = Args::for_workspace(root);
+        args.ignore = vec!["**/generated/**".to_string()];
+        let orchestrator = Orchestrator::new(root.to_path_buf(), args).unwrap();
+        assert_eq!(
+            orchestrator.list_files().unwrap(),
+            vec![
+                PathBuf::from("src/App.vue"),
+                PathBuf::from("src/components/Button.vue"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_explain_component() {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-explain")
+            .tempdir()
+            .unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("src/App.vue"),
+            "<script setup lang=\"ts\">\nimport MyButton from './MyButton.vue'\n</script>\n\n<template>\n  <my-buton />\n</template>\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/Other.vue"), "<template><div /></template>\n").unwrap();
+
+        let orchestrator =
+            Orchestrator::new(root.to_path_buf(), Args::for_workspace(root)).unwrap();
+        let traces = orchestrator.explain_component("MyButon").unwrap();
+        assert_eq!(traces.len(), 1);
+
+        let (path, trace) = &traces[0];
+        assert_eq!(path, &PathBuf::from("src/App.vue"));
+        assert!(!trace.is_resolved());
+        assert_eq!(
+            trace.steps.last().unwrap().detail,
+            "not imported, script setup binds MyButton"
+        );
+        assert_eq!(trace.suggestions, ["MyButton"]);
+    }
+
+    #[test]
+    fn test_list_files_with_configured_extensions() {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-extensions")
+            .tempdir()
+            .unwrap();
+        let root = dir.path();
+        for file in ["App.vue", "Guide.md", "Page.vue.html", "Other.html"] {
+            std::fs::write(root.join(file), "<template><div /></template>\n").unwrap();
+        }
+        std::fs::write(
+            root.join("tsconfig.json"),
+            r#"{ "vueCompilerOptions": { "extensions": [".vue", ".md", ".vue.html"] } }"#,
+        )
+        .unwrap();
+
+        let orchestrator =
+            Orchestrator::new(root.to_path_buf(), Args::for_workspace(root)).unwrap();
+        assert_eq!(
+            orchestrator.list_files().unwrap(),
+            vec![
+                PathBuf::from("App.vue"),
+                PathBuf::from("Guide.md"),
+                PathBuf::from("Page.vue.html"),
+            ]
+        );
+    }
+
+    fn base64_decode(encoded: &str) -> Vec<u8> {
+        const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let digits: Vec<u32> = encoded
+            .bytes()
+            .filter(|&b| b != b'=')
+            .map(|b| CHARS.iter().position(|&c| c == b).unwrap() as u32)
+            .collect();
+        let mut bytes = Vec::new();
+        for chunk in digits.chunks(4) {
+            let n = chunk
+                .iter()
+                .enumerate()
+                .fold(0, |n, (i, d)| n | d << (18 - 6 * i));
+            bytes.extend(&n.to_be_bytes()[1..chunk.len()]);
+        }
+        bytes
     }
 }
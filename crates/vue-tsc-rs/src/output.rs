@@ -2,9 +2,13 @@
 
 use crate::cli::OutputFormat;
 use crate::orchestrator::CheckResult;
+use source_map::LineIndex;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
 use std::path::Path;
 use ts_runner::TsDiagnostic;
-use vue_diagnostics::{Diagnostic, Severity};
+use vue_diagnostics::report::DiagnosticReport;
+use vue_diagnostics::Diagnostic;
 
 // ANSI colors
 const RED: &str = "\x1b[31m";
@@ -18,110 +22,82 @@ const RESET: &str = "\x1b[0m";
 /// Formatter for diagnostic output.
 pub struct OutputFormatter {
     format: OutputFormat,
+    writer: Box<dyn Write + Send + Sync>,
 }
 
 impl OutputFormatter {
-    /// Create a new formatter.
+    /// Create a new formatter writing to stdout.
     pub fn new(format: OutputFormat) -> Self {
-        Self { format }
+        Self::with_writer(format, Box::new(io::stdout()))
+    }
+
+    /// Create a new formatter writing to `writer`.
+    pub fn with_writer(format: OutputFormat, writer: Box<dyn Write + Send + Sync>) -> Self {
+        Self { format, writer }
     }
 
     /// Print a Vue diagnostic.
-    pub fn print_vue_diagnostic(&self, file: &Path, diagnostic: &Diagnostic, source: Option<&str>) {
+    pub fn print_vue_diagnostic(
+        &mut self,
+        file: &Path,
+        diagnostic: &Diagnostic,
+        source: Option<&str>,
+    ) -> io::Result<()> {
         match self.format {
             OutputFormat::Human | OutputFormat::HumanVerbose => {
-                self.print_vue_human(file, diagnostic, source);
-            }
-            OutputFormat::Json => {
-                self.print_vue_json(file, diagnostic);
-            }
-            OutputFormat::Machine => {
-                self.print_vue_machine(file, diagnostic);
+                self.print_vue_human(file, diagnostic, source)
             }
+            OutputFormat::Json => self.print_vue_json(file, diagnostic, source),
+            OutputFormat::Machine => self.print_vue_machine(file, diagnostic),
         }
     }
 
     /// Print a TypeScript diagnostic.
-    pub fn print_ts_diagnostic(&self, diagnostic: &TsDiagnostic, source: Option<&str>) {
+    pub fn print_ts_diagnostic(
+        &mut self,
+        diagnostic: &TsDiagnostic,
+        source: Option<&str>,
+    ) -> io::Result<()> {
         match self.format {
             OutputFormat::Human | OutputFormat::HumanVerbose => {
-                self.print_ts_human(diagnostic, source);
-            }
-            OutputFormat::Json => {
-                self.print_ts_json(diagnostic);
-            }
-            OutputFormat::Machine => {
-                self.print_ts_machine(diagnostic);
+                self.print_ts_human(diagnostic, source)
             }
+            OutputFormat::Json => self.print_ts_json(diagnostic),
+            OutputFormat::Machine => self.print_ts_machine(diagnostic),
         }
     }
 
     /// Print the summary.
-    pub fn print_summary(&self, result: &CheckResult) {
+    pub fn print_summary(&mut self, result: &CheckResult) -> io::Result<()> {
         match self.format {
-            OutputFormat::Human | OutputFormat::HumanVerbose => {
-                self.print_summary_human(result);
-            }
-            OutputFormat::Json => {
-                self.print_summary_json(result);
-            }
-            OutputFormat::Machine => {}
-        }
+            OutputFormat::Human | OutputFormat::HumanVerbose => self.print_summary_human(result),
+            OutputFormat::Json => self.print_summary_json(result),
+            OutputFormat::Machine => Ok(()),
+        }?;
+        self.writer.flush()
     }
 
     // Human format - modern style like tsc/vite
 
-    fn print_vue_human(&self, file: &Path, diagnostic: &Diagnostic, source: Option<&str>) {
-        let (icon, color, label) = match diagnostic.severity {
-            Severity::Error => ("✖", RED, "error"),
-            Severity::Warning => ("⚠", YELLOW, "warning"),
-            Severity::Hint => ("ℹ", CYAN, "hint"),
-        };
-
-        // File location
-        let line = 1; // TODO: calculate from span
-        let col = diagnostic.span.start;
-        println!(
-            "\n{BOLD}{}{RESET}:{GRAY}{}:{}{RESET}",
-            file.display(),
-            line,
-            col
-        );
-
-        // Show source line if available
-        if let Some(src) = source {
-            if let Some(line_content) = src.lines().nth(0) {
-                let trimmed = line_content.trim_start();
-                let indent = line_content.len() - trimmed.len();
-                println!("  {GRAY}│{RESET}");
-                println!("  {GRAY}│{RESET} {}", trimmed);
-
-                // Underline
-                let underline_start = (diagnostic.span.start as usize).saturating_sub(indent);
-                let underline_len = (diagnostic.span.end - diagnostic.span.start) as usize;
-                let underline_len = underline_len
-                    .max(1)
-                    .min(trimmed.len().saturating_sub(underline_start));
-
-                if underline_len > 0 && underline_start < trimmed.len() {
-                    println!(
-                        "  {GRAY}│{RESET} {}{color}{}{RESET}",
-                        " ".repeat(underline_start),
-                        "~".repeat(underline_len)
-                    );
-                }
-            }
-        }
-
-        // Error message
-        println!(
-            "  {GRAY}╰─{RESET} {color}{icon} {label}{RESET}: {} {GRAY}[{}]{RESET}",
-            diagnostic.message,
-            diagnostic.code.as_str()
+    fn print_vue_human(
+        &mut self,
+        file: &Path,
+        diagnostic: &Diagnostic,
+        source: Option<&str>,
+    ) -> io::Result<()> {
+        let report = DiagnosticReport::new(
+            diagnostic.clone(),
+            file.to_string_lossy(),
+            source.unwrap_or_default().to_string(),
         );
+        writeln!(self.writer, "\n{:?}", miette::Report::new(report))
     }
 
-    fn print_ts_human(&self, diagnostic: &TsDiagnostic, source: Option<&str>) {
+    fn print_ts_human(
+        &mut self,
+        diagnostic: &TsDiagnostic,
+        source: Option<&str>,
+    ) -> io::Result<()> {
         let (icon, color, label) = match diagnostic.severity {
             ts_runner::TsSeverity::Error => ("✖", RED, "error"),
             ts_runner::TsSeverity::Warning => ("⚠", YELLOW, "warning"),
@@ -132,49 +108,72 @@ impl OutputFormatter {
         if let Some(file) = &diagnostic.file {
             let line = diagnostic.line.unwrap_or(1);
             let col = diagnostic.column.unwrap_or(1);
-            println!(
+            writeln!(
+                self.writer,
                 "\n{BOLD}{}{RESET}:{GRAY}{}:{}{RESET}",
                 file.display(),
                 line,
                 col
-            );
+            )?;
 
             // Show source line if available
             if let Some(src) = source {
                 if let Some(line_content) = src.lines().nth((line as usize).saturating_sub(1)) {
                     let trimmed = line_content.trim_start();
-                    println!("  {GRAY}│{RESET}");
-                    println!("  {GRAY}│{RESET} {}", trimmed);
+                    writeln!(self.writer, "  {GRAY}│{RESET}")?;
+                    writeln!(self.writer, "  {GRAY}│{RESET} {}", trimmed)?;
 
                     // Simple underline at column position
                     let col_pos = (col as usize).saturating_sub(1);
                     if col_pos < trimmed.len() {
-                        println!(
+                        writeln!(
+                            self.writer,
                             "  {GRAY}│{RESET} {}{color}^{RESET}",
                             " ".repeat(col_pos.min(trimmed.len()))
-                        );
+                        )?;
                     }
                 }
             }
         }
 
         // Error message
-        println!(
+        writeln!(
+            self.writer,
             "  {GRAY}╰─{RESET} {color}{icon} {label}{RESET}: {} {GRAY}[TS{}]{RESET}",
             diagnostic.message, diagnostic.code
-        );
+        )?;
+
+        // Related locations, such as where the expected type is declared
+        for related in &diagnostic.related {
+            match &related.file {
+                Some(file) => writeln!(
+                    self.writer,
+                    "     {GRAY}→ {}:{}:{}{RESET} {}",
+                    file.display(),
+                    related.line.unwrap_or(1),
+                    related.column.unwrap_or(1),
+                    related.message
+                )?,
+                None => writeln!(self.writer, "     {GRAY}→{RESET} {}", related.message)?,
+            }
+        }
+        Ok(())
     }
 
-    fn print_summary_human(&self, result: &CheckResult) {
-        println!();
-        println!("{GRAY}───────────────────────────────────────────{RESET}");
+    fn print_summary_human(&mut self, result: &CheckResult) -> io::Result<()> {
+        writeln!(self.writer)?;
+        writeln!(
+            self.writer,
+            "{GRAY}───────────────────────────────────────────{RESET}"
+        )?;
 
-        if result.error_count == 0 && result.warning_count == 0 {
-            println!(
+        if result.error_count == 0 && result.warning_count == 0 && result.hint_count == 0 {
+            writeln!(
+                self.writer,
                 "{GREEN}{BOLD}✓{RESET} {GREEN}No issues found{RESET} {GRAY}({} files in {}ms){RESET}",
                 result.file_count,
                 result.duration_ms
-            );
+            )?;
         } else {
             let mut parts = Vec::new();
 
@@ -194,20 +193,42 @@ impl OutputFormatter {
                 ));
             }
 
-            println!(
+            if result.hint_count > 0 {
+                parts.push(format!(
+                    "{CYAN}{BOLD}{}{RESET} {CYAN}hint{}{RESET}",
+                    result.hint_count,
+                    if result.hint_count == 1 { "" } else { "s" }
+                ));
+            }
+
+            writeln!(
+                self.writer,
                 "{} {GRAY}({} files in {}ms){RESET}",
                 parts.join(", "),
                 result.file_count,
                 result.duration_ms
-            );
+            )?;
+
+            if !result.diagnostic_counts.is_empty() {
+                writeln!(
+                    self.writer,
+                    "{GRAY}By code: {}{RESET}",
+                    format_code_counts(&result.diagnostic_counts)
+                )?;
+            }
         }
-        println!();
+        writeln!(self.writer)
     }
 
     // JSON format
 
-    fn print_vue_json(&self, file: &Path, diagnostic: &Diagnostic) {
-        let json = serde_json::json!({
+    fn print_vue_json(
+        &mut self,
+        file: &Path,
+        diagnostic: &Diagnostic,
+        source: Option<&str>,
+    ) -> io::Result<()> {
+        let mut json = serde_json::json!({
             "type": "vue",
             "file": file.to_string_lossy(),
             "severity": diagnostic.severity.as_str(),
@@ -218,10 +239,19 @@ impl OutputFormatter {
                 "end": diagnostic.span.end
             }
         });
-        println!("{}", json);
+        if let Some(source) = source {
+            let (start, end) = diagnostic.location(&LineIndex::new(source));
+            let (line, column) = start.to_display();
+            let (end_line, end_column) = end.to_display();
+            json["line"] = line.into();
+            json["column"] = column.into();
+            json["end_line"] = end_line.into();
+            json["end_column"] = end_column.into();
+        }
+        writeln!(self.writer, "{}", json)
     }
 
-    fn print_ts_json(&self, diagnostic: &TsDiagnostic) {
+    fn print_ts_json(&mut self, diagnostic: &TsDiagnostic) -> io::Result<()> {
         let json = serde_json::json!({
             "type": "typescript",
             "file": diagnostic.file.as_ref().map(|f| f.to_string_lossy().to_string()),
@@ -229,36 +259,40 @@ impl OutputFormatter {
             "message": diagnostic.message,
             "code": diagnostic.code,
             "line": diagnostic.line,
-            "column": diagnostic.column
+            "column": diagnostic.column,
+            "related": diagnostic.related
         });
-        println!("{}", json);
+        writeln!(self.writer, "{}", json)
     }
 
-    fn print_summary_json(&self, result: &CheckResult) {
+    fn print_summary_json(&mut self, result: &CheckResult) -> io::Result<()> {
         let json = serde_json::json!({
             "type": "summary",
             "files": result.file_count,
             "errors": result.error_count,
             "warnings": result.warning_count,
-            "duration_ms": result.duration_ms
+            "hints": result.hint_count,
+            "duration_ms": result.duration_ms,
+            "by_code": result.diagnostic_counts
         });
-        println!("{}", json);
+        writeln!(self.writer, "{}", json)
     }
 
     // Machine format
 
-    fn print_vue_machine(&self, file: &Path, diagnostic: &Diagnostic) {
-        println!(
+    fn print_vue_machine(&mut self, file: &Path, diagnostic: &Diagnostic) -> io::Result<()> {
+        writeln!(
+            self.writer,
             "{}:{}:{}:{}:{}",
             file.display(),
             diagnostic.span.start,
             diagnostic.span.end,
             diagnostic.severity.as_str(),
             diagnostic.message.replace(':', "\\:")
-        );
+        )
     }
 
-    fn print_ts_machine(&self, diagnostic: &TsDiagnostic) {
+    fn print_ts_machine(&mut self, diagnostic: &TsDiagnostic) -> io::Result<()> {
         let file = diagnostic
             .file
             .as_ref()
@@ -267,7 +301,8 @@ impl OutputFormatter {
         let line = diagnostic.line.unwrap_or(0);
         let col = diagnostic.column.unwrap_or(0);
 
-        println!(
+        writeln!(
+            self.writer,
             "{}:{}:{}:{}:TS{}:{}",
             file,
             line,
@@ -275,6 +310,172 @@ impl OutputFormatter {
             diagnostic.severity.as_str(),
             diagnostic.code,
             diagnostic.message.replace(':', "\\:")
+        )
+    }
+}
+
+/// Format diagnostic counts as `12 TS2322, 3 unknown-component`, most frequent first.
+fn format_code_counts(counts: &BTreeMap<String, usize>) -> String {
+    let mut counts: Vec<_> = counts.iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    counts
+        .into_iter()
+        .map(|(code, count)| format!("{} {}", count, code))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use source_map::Span;
+    use std::sync::{Arc, Mutex};
+    use vue_diagnostics::DiagnosticCode;
+
+    /// A writer whose contents can be read after the formatter takes it.
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Buffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    fn missing_key() -> Diagnostic {
+        Diagnostic::warning(
+            "Elements in v-for should have a :key",
+            Span::new(10, 20),
+            DiagnosticCode::MissingKey,
+        )
+    }
+
+    #[test]
+    fn test_machine_output_is_captured() {
+        let buffer = Buffer::default();
+        let mut formatter =
+            OutputFormatter::with_writer(OutputFormat::Machine, Box::new(buffer.clone()));
+        formatter
+            .print_vue_diagnostic(Path::new("App.vue"), &missing_key(), None)
+            .unwrap();
+        assert_eq!(
+            buffer.contents(),
+            "App.vue:10:20:warning:Elements in v-for should have a \\:key\n"
+        );
+    }
+
+    #[test]
+    fn test_json_summary_is_captured() {
+        let buffer = Buffer::default();
+        let mut formatter =
+            OutputFormatter::with_writer(OutputFormat::Json, Box::new(buffer.clone()));
+        let result = CheckResult {
+            file_count: 3,
+            warning_count: 1,
+            diagnostic_counts: BTreeMap::from([("missing-key".to_string(), 1)]),
+            ..Default::default()
+        };
+        formatter.print_summary(&result).unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(buffer.contents().trim()).unwrap();
+        assert_eq!(json["type"], "summary");
+        assert_eq!(json["files"], 3);
+        assert_eq!(json["warnings"], 1);
+        assert_eq!(json["by_code"]["missing-key"], 1);
+    }
+
+    #[test]
+    fn test_hints_are_summarized() {
+        let buffer = Buffer::default();
+        let mut formatter =
+            OutputFormatter::with_writer(OutputFormat::Human, Box::new(buffer.clone()));
+        let result = CheckResult {
+            file_count: 1,
+            hint_count: 2,
+            ..Default::default()
+        };
+        formatter.print_summary(&result).unwrap();
+
+        let contents = buffer.contents();
+        assert!(!contents.contains("No issues found"));
+        assert!(contents.contains(&format!("{CYAN}{BOLD}2{RESET} {CYAN}hints{RESET}")));
+    }
+
+    #[test]
+    fn test_format_code_counts() {
+        let counts = BTreeMap::from([
+            ("missing-key".to_string(), 5),
+            ("TS2322".to_string(), 12),
+            ("unknown-component".to_string(), 3),
+            ("invalid-v-for".to_string(), 3),
+        ]);
+        assert_eq!(
+            format_code_counts(&counts),
+            "12 TS2322, 5 missing-key, 3 invalid-v-for, 3 unknown-component"
         );
     }
+
+    fn related_diagnostic() -> TsDiagnostic {
+        TsDiagnostic {
+            message: "Type 'string' is not assignable to type 'number'.".to_string(),
+            code: 2322,
+            severity: ts_runner::TsSeverity::Error,
+            file: Some("src/App.vue".into()),
+            line: Some(3),
+            column: Some(5),
+            end_line: None,
+            end_column: None,
+            related: vec![ts_runner::RelatedInfo {
+                message: "The expected type comes from property 'size'".to_string(),
+                file: Some("src/Child.vue".into()),
+                line: Some(2),
+                column: Some(17),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_related_info_is_printed() {
+        let buffer = Buffer::default();
+        let mut formatter =
+            OutputFormatter::with_writer(OutputFormat::Human, Box::new(buffer.clone()));
+        formatter
+            .print_ts_diagnostic(&related_diagnostic(), None)
+            .unwrap();
+
+        let contents = buffer.contents();
+        let message = contents.find("[TS2322]").unwrap();
+        let related = contents
+            .find(&format!(
+                "     {GRAY}→ src/Child.vue:2:17{RESET} The expected type comes from property 'size'"
+            ))
+            .unwrap();
+        assert!(message < related);
+    }
+
+    #[test]
+    fn test_related_info_in_json() {
+        let buffer = Buffer::default();
+        let mut formatter =
+            OutputFormatter::with_writer(OutputFormat::Json, Box::new(buffer.clone()));
+        formatter
+            .print_ts_diagnostic(&related_diagnostic(), None)
+            .unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(buffer.contents().trim()).unwrap();
+        let related = &json["related"][0];
+        assert_eq!(related["file"], "src/Child.vue");
+        assert_eq!(related["line"], 2);
+        assert_eq!(related["column"], 17);
+    }
 }
@@ -0,0 +1,170 @@
+//! Machine-readable metrics report.
+
+use crate::orchestrator::{CheckResult, FileTimings};
+use miette::{IntoDiagnostic, Result, WrapErr};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+/// Metrics for a single check run, written by `--stats`.
+#[derive(Debug, Serialize)]
+pub struct StatsReport {
+    /// Number of files checked.
+    pub files: usize,
+    /// Number of errors.
+    pub errors: usize,
+    /// Number of warnings.
+    pub warnings: usize,
+    /// Number of hints.
+    pub hints: usize,
+    /// Total time in milliseconds.
+    pub total_ms: f64,
+    /// Time spent parsing and diagnosing Vue files, in milliseconds.
+    pub parse_ms: f64,
+    /// Time spent generating virtual TypeScript files, in milliseconds.
+    pub codegen_ms: f64,
+    /// Time spent running the TypeScript compiler, in milliseconds.
+    pub tsc_ms: f64,
+    /// Peak number of files checked concurrently.
+    pub peak_concurrency: usize,
+    /// Number of diagnostics per code.
+    pub diagnostics_by_code: BTreeMap<String, usize>,
+    /// Time spent on each file, slowest first.
+    pub file_timings: Vec<FileStats>,
+}
+
+/// Time spent on a single file, in milliseconds.
+#[derive(Debug, Serialize)]
+pub struct FileStats {
+    /// The file.
+    pub path: String,
+    /// Total time.
+    pub total_ms: f64,
+    /// Time spent parsing.
+    pub parse_ms: f64,
+    /// Time spent running Vue diagnostics.
+    pub diagnose_ms: f64,
+    /// Time spent generating the virtual TypeScript file.
+    pub codegen_ms: f64,
+    /// Time spent writing the virtual TypeScript file.
+    pub write_ms: f64,
+}
+
+impl FileStats {
+    fn from_timings(timings: &FileTimings) -> Self {
+        Self {
+            path: timings.path.display().to_string(),
+            total_ms: as_millis(timings.total()),
+            parse_ms: as_millis(timings.parse),
+            diagnose_ms: as_millis(timings.diagnose),
+            codegen_ms: as_millis(timings.codegen),
+            write_ms: as_millis(timings.write),
+        }
+    }
+}
+
+impl StatsReport {
+    /// Build a report from a check result.
+    pub fn from_result(result: &CheckResult) -> Self {
+        Self {
+            files: result.file_count,
+            errors: result.error_count,
+            warnings: result.warning_count,
+            hints: result.hint_count,
+            total_ms: result.duration_ms as f64,
+            parse_ms: as_millis(result.parse_duration),
+            codegen_ms: as_millis(result.codegen_duration),
+            tsc_ms: as_millis(result.tsc_duration),
+            peak_concurrency: result.peak_concurrency,
+            diagnostics_by_code: result.diagnostic_counts.clone(),
+            file_timings: result
+                .file_timings
+                .iter()
+                .map(FileStats::from_timings)
+                .collect(),
+        }
+    }
+
+    /// Write the report as JSON.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).into_diagnostic()?;
+        std::fs::write(path, json)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to write stats to {}", path.display()))
+    }
+}
+
+/// Convert a duration to fractional milliseconds.
+pub(crate) fn as_millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Args;
+    use crate::orchestrator::Orchestrator;
+
+    #[test]
+    fn test_report_from_result() {
+        let mut result = CheckResult {
+            file_count: 2,
+            parse_duration: Duration::from_micros(1500),
+            ..Default::default()
+        };
+        result.diagnostic_counts.insert("TS2322".to_string(), 3);
+
+        let report = StatsReport::from_result(&result);
+        assert_eq!(report.files, 2);
+        assert_eq!(report.parse_ms, 1.5);
+        assert_eq!(report.diagnostics_by_code["TS2322"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_stats_report_for_multi_file_run() {
+        let dir = tempfile::Builder::new()
+            .prefix("vue-tsc-rs-stats")
+            .tempdir()
+            .unwrap();
+        for name in ["A.vue", "B.vue", "C.vue"] {
+            std::fs::write(
+                dir.path().join(name),
+                r#"<template><li v-for="item in items">{{ item }}</li></template>"#,
+            )
+            .unwrap();
+        }
+        let stats_path = dir.path().join("stats.json");
+
+        let args = Args {
+            stats: Some(stats_path.clone()),
+            ..Args::for_workspace(dir.path())
+        };
+        let mut orchestrator = Orchestrator::new(dir.path().to_path_buf(), args).unwrap();
+        orchestrator.run_single_check().await.unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&stats_path).unwrap()).unwrap();
+        for key in [
+            "files",
+            "errors",
+            "warnings",
+            "hints",
+            "total_ms",
+            "parse_ms",
+            "codegen_ms",
+            "tsc_ms",
+            "peak_concurrency",
+            "diagnostics_by_code",
+            "file_timings",
+        ] {
+            assert!(json.get(key).is_some(), "missing key: {key}");
+        }
+        assert_eq!(json["files"], 3);
+        assert!(json["parse_ms"].as_f64().unwrap() > 0.0);
+        assert!(json["peak_concurrency"].as_u64().unwrap() >= 1);
+        assert_eq!(json["diagnostics_by_code"]["missing-key"], 3);
+        assert_eq!(json["file_timings"].as_array().unwrap().len(), 3);
+        assert!(json["file_timings"][0]["diagnose_ms"].is_f64());
+    }
+}
@@ -0,0 +1,210 @@
+//! Status output for watch mode.
+
+use crate::orchestrator::CheckResult;
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Clears the terminal and moves the cursor to the top left.
+const CLEAR_SCREEN: &str = "\x1B[2J\x1B[1;1H";
+
+/// Returns to the start of the line and erases it.
+const CLEAR_LINE: &str = "\r\x1B[2K";
+
+/// Reports the progress of watch mode checks.
+///
+/// Progress is counted atomically and written under a lock, so files checked
+/// in parallel can report from any thread.
+pub(crate) struct WatchReporter {
+    out: Mutex<Box<dyn Write + Send>>,
+    /// Keep earlier output instead of clearing the screen on a rerun.
+    preserve_output: bool,
+    total: AtomicUsize,
+    done: AtomicUsize,
+}
+
+impl WatchReporter {
+    /// Create a reporter writing to `out`.
+    pub(crate) fn new(out: Box<dyn Write + Send>, preserve_output: bool) -> Self {
+        Self {
+            out: Mutex::new(out),
+            preserve_output,
+            total: AtomicUsize::new(0),
+            done: AtomicUsize::new(0),
+        }
+    }
+
+    /// Create a reporter writing to stderr.
+    pub(crate) fn stderr(preserve_output: bool) -> Self {
+        Self::new(Box::new(std::io::stderr()), preserve_output)
+    }
+
+    /// Announce a rerun, clearing the screen unless output is preserved.
+    pub(crate) fn rerun(&self, reason: &str) {
+        let mut out = self.out.lock().unwrap();
+        if !self.preserve_output {
+            let _ = write!(out, "{}", CLEAR_SCREEN);
+        }
+        let _ = writeln!(out, "{}. Rerunning...\n", reason);
+        let _ = out.flush();
+    }
+
+    /// Start the status line for checking `total` files.
+    pub(crate) fn start(&self, total: usize) {
+        self.total.store(total, Ordering::SeqCst);
+        self.done.store(0, Ordering::SeqCst);
+        let mut out = self.out.lock().unwrap();
+        let _ = write!(out, "Checking {} files...", total);
+        let _ = out.flush();
+    }
+
+    /// Count one more checked file and update the status line.
+    pub(crate) fn file_done(&self) {
+        let done = self.done.fetch_add(1, Ordering::SeqCst) + 1;
+        let total = self.total.load(Ordering::SeqCst);
+        let mut out = self.out.lock().unwrap();
+        let _ = write!(out, "{}Checking {}/{} files...", CLEAR_LINE, done, total);
+        let _ = out.flush();
+    }
+
+    /// Erase the status line, before diagnostics are printed.
+    pub(crate) fn end(&self) {
+        let mut out = self.out.lock().unwrap();
+        let _ = write!(out, "{}", CLEAR_LINE);
+        let _ = out.flush();
+    }
+
+    /// Summarize a finished check.
+    pub(crate) fn finish(&self, result: &CheckResult) {
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        let mut out = self.out.lock().unwrap();
+        let _ = writeln!(
+            out,
+            "\nFound {} error{} and {} warning{} in {} file{} ({}ms). Watching for file changes.",
+            result.error_count,
+            plural(result.error_count),
+            result.warning_count,
+            plural(result.warning_count),
+            result.file_count,
+            plural(result.file_count),
+            result.duration_ms
+        );
+        let _ = out.flush();
+    }
+}
+
+/// Collect the paths of a file system event and of the events that follow
+/// it in quick succession.
+///
+/// Saving a file often fires several events; waiting until none arrive for
+/// `quiet` turns them into one rerun. Paths are deduplicated and sorted.
+pub(crate) fn coalesce_events(
+    first: notify::Event,
+    rx: &Receiver<notify::Event>,
+    quiet: Duration,
+) -> Vec<PathBuf> {
+    let mut paths: BTreeSet<PathBuf> = first.paths.into_iter().collect();
+    while let Ok(event) = rx.recv_timeout(quiet) {
+        paths.extend(event.paths);
+    }
+    paths.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// A writer whose contents can be read after it is moved into a reporter.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_rerun_clears_screen() {
+        let buffer = SharedBuffer::default();
+        let reporter = WatchReporter::new(Box::new(buffer.clone()), false);
+        reporter.rerun("File change detected");
+        assert_eq!(
+            buffer.contents(),
+            format!("{}File change detected. Rerunning...\n\n", CLEAR_SCREEN)
+        );
+    }
+
+    #[test]
+    fn test_rerun_preserves_output() {
+        let buffer = SharedBuffer::default();
+        let reporter = WatchReporter::new(Box::new(buffer.clone()), true);
+        reporter.rerun("Configuration changed");
+        assert_eq!(buffer.contents(), "Configuration changed. Rerunning...\n\n");
+    }
+
+    #[test]
+    fn test_progress_from_threads() {
+        let buffer = SharedBuffer::default();
+        let reporter = WatchReporter::new(Box::new(buffer.clone()), true);
+        reporter.start(8);
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    reporter.file_done();
+                    reporter.file_done();
+                });
+            }
+        });
+        reporter.end();
+        reporter.finish(&CheckResult {
+            file_count: 8,
+            error_count: 1,
+            ..Default::default()
+        });
+
+        let contents = buffer.contents();
+        assert!(contents.starts_with("Checking 8 files..."));
+        for done in 1..=8 {
+            assert!(contents.contains(&format!("Checking {}/8 files...", done)));
+        }
+        assert!(contents.ends_with(&format!(
+            "{}\nFound 1 error and 0 warnings in 8 files (0ms). Watching for file changes.\n",
+            CLEAR_LINE
+        )));
+    }
+
+    #[test]
+    fn test_coalesce_events() {
+        let event = |path: &str| notify::Event::default().add_path(PathBuf::from(path));
+        let (tx, rx) = std::sync::mpsc::channel();
+        tx.send(event("b.vue")).unwrap();
+        tx.send(event("a.vue")).unwrap();
+        tx.send(event("b.vue")).unwrap();
+
+        let paths = coalesce_events(event("c.ts"), &rx, Duration::from_millis(10));
+        assert_eq!(
+            paths,
+            [
+                PathBuf::from("a.vue"),
+                PathBuf::from("b.vue"),
+                PathBuf::from("c.ts")
+            ]
+        );
+    }
+}